@@ -0,0 +1,336 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+use serde_json::{json, Value};
+use tokio::sync::Notify;
+
+use crate::backend::events::AppServerEvent;
+use crate::utils::now_millis;
+
+#[derive(Clone)]
+struct ActiveTurn {
+    turn_id: String,
+    started_at: i64,
+}
+
+#[derive(Default)]
+struct TurnsState {
+    /// Running turns per workspace, keyed by thread id.
+    running: HashMap<String, HashMap<String, ActiveTurn>>,
+    /// Admitted `turn/start` requests by reservation id, with the workspace
+    /// and thread whose `turn/started` will take over the slot.
+    reserved: HashMap<u64, (String, String)>,
+    next_reservation: u64,
+}
+
+impl TurnsState {
+    fn busy(&self) -> usize {
+        let running: usize = self.running.values().map(|threads| threads.len()).sum();
+        running + self.reserved.len()
+    }
+
+    fn drop_reservations(&mut self, workspace_id: &str, thread_id: Option<&str>) {
+        self.reserved.retain(|_, (workspace, thread)| {
+            workspace != workspace_id || thread_id.is_some_and(|id| id != thread)
+        });
+    }
+}
+
+/// In-flight turns per workspace, keyed by thread id. Updated from the
+/// app-server event stream so reconnecting clients can tell a thread is busy.
+#[derive(Clone, Default)]
+pub(crate) struct ActiveTurns {
+    state: Arc<Mutex<TurnsState>>,
+    changed: Arc<Notify>,
+}
+
+/// A slot held against the turn limit. Dropping it frees the slot, so a
+/// cancelled or failed `turn/start` cannot leak one; [`Self::hand_off`] keeps
+/// it until the thread's `turn/started` event arrives.
+pub(crate) struct TurnReservation {
+    turns: ActiveTurns,
+    id: u64,
+    handed_off: bool,
+}
+
+impl TurnReservation {
+    /// Keeps the slot after a successful `turn/start` until the turn shows up
+    /// as running, or ends, or the workspace's session goes away.
+    pub(crate) fn hand_off(mut self) {
+        self.handed_off = true;
+    }
+}
+
+impl Drop for TurnReservation {
+    fn drop(&mut self) {
+        if self.handed_off {
+            return;
+        }
+        self.turns.lock().reserved.remove(&self.id);
+        self.turns.changed.notify_waiters();
+    }
+}
+
+impl ActiveTurns {
+    /// A panic while the lock was held cannot leave the counts half-updated,
+    /// so a poisoned lock is recovered rather than treated as "no turns".
+    fn lock(&self) -> MutexGuard<'_, TurnsState> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    pub(crate) fn observe(&self, event: &AppServerEvent) {
+        let Some(method) = event.message.get("method").and_then(|value| value.as_str()) else {
+            return;
+        };
+        let params = event.message.get("params").cloned().unwrap_or(Value::Null);
+        let turn = params.get("turn");
+        let thread_id = ["threadId", "thread_id"]
+            .iter()
+            .find_map(|key| {
+                params
+                    .get(*key)
+                    .or_else(|| turn.and_then(|turn| turn.get(*key)))
+            })
+            .and_then(|value| value.as_str())
+            .unwrap_or("")
+            .to_string();
+        if thread_id.is_empty() {
+            return;
+        }
+        let turn_id = turn
+            .and_then(|turn| turn.get("id"))
+            .or_else(|| params.get("turnId"))
+            .or_else(|| params.get("turn_id"))
+            .and_then(|value| value.as_str())
+            .unwrap_or("")
+            .to_string();
+
+        let mut state = self.lock();
+        match method {
+            "turn/started" => {
+                let workspace_id = &event.workspace_id;
+                let reservation = state
+                    .reserved
+                    .iter()
+                    .find(|(_, (workspace, thread))| {
+                        workspace == workspace_id && *thread == thread_id
+                    })
+                    .map(|(id, _)| *id);
+                if let Some(id) = reservation {
+                    state.reserved.remove(&id);
+                }
+                state.running.entry(workspace_id.clone()).or_default().insert(
+                    thread_id,
+                    ActiveTurn {
+                        turn_id,
+                        started_at: now_millis(),
+                    },
+                );
+            }
+            "turn/completed" => {
+                if let Some(threads) = state.running.get_mut(&event.workspace_id) {
+                    threads.remove(&thread_id);
+                }
+                state.drop_reservations(&event.workspace_id, Some(&thread_id));
+                self.changed.notify_waiters();
+            }
+            "error" => {
+                let will_retry = params
+                    .get("willRetry")
+                    .or_else(|| params.get("will_retry"))
+                    .and_then(|value| value.as_bool())
+                    .unwrap_or(false);
+                if !will_retry {
+                    if let Some(threads) = state.running.get_mut(&event.workspace_id) {
+                        threads.remove(&thread_id);
+                    }
+                    state.drop_reservations(&event.workspace_id, Some(&thread_id));
+                    self.changed.notify_waiters();
+                }
+            }
+            _ => {}
+        }
+    }
+
+    pub(crate) fn list(&self, workspace_id: &str) -> Vec<Value> {
+        let state = self.lock();
+        let mut result: Vec<Value> = state
+            .running
+            .get(workspace_id)
+            .map(|threads| {
+                threads
+                    .iter()
+                    .map(|(thread_id, turn)| {
+                        json!({
+                            "threadId": thread_id,
+                            "turnId": turn.turn_id,
+                            "startedAt": turn.started_at,
+                        })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        result.sort_by_key(|value| value["startedAt"].as_i64().unwrap_or(0));
+        result
+    }
+
+    pub(crate) fn clear(&self, workspace_id: &str) {
+        {
+            let mut state = self.lock();
+            state.running.remove(workspace_id);
+            state.drop_reservations(workspace_id, None);
+        }
+        self.changed.notify_waiters();
+    }
+
+    /// Admits one more turn on `thread_id` if running plus reserved turns stay
+    /// under `limit`. Returns the number of busy slots when the limit is
+    /// already reached.
+    fn try_reserve(
+        &self,
+        limit: usize,
+        workspace_id: &str,
+        thread_id: &str,
+    ) -> Result<TurnReservation, usize> {
+        let mut state = self.lock();
+        let busy = state.busy();
+        if busy >= limit {
+            return Err(busy);
+        }
+        state.next_reservation += 1;
+        let id = state.next_reservation;
+        state
+            .reserved
+            .insert(id, (workspace_id.to_string(), thread_id.to_string()));
+        Ok(TurnReservation {
+            turns: self.clone(),
+            id,
+            handed_off: false,
+        })
+    }
+
+    /// Reserves a slot for a turn on `thread_id`, waiting for one to free up
+    /// when `queue` is set. Dropping the future while it waits holds nothing.
+    pub(crate) async fn reserve(
+        &self,
+        limit: usize,
+        queue: bool,
+        workspace_id: &str,
+        thread_id: &str,
+    ) -> Result<TurnReservation, String> {
+        loop {
+            let changed = self.changed.notified();
+            tokio::pin!(changed);
+            changed.as_mut().enable();
+            match self.try_reserve(limit, workspace_id, thread_id) {
+                Ok(reservation) => return Ok(reservation),
+                Err(busy) if !queue => {
+                    return Err(format!(
+                        "too_many_active_turns: {busy} turns already running (limit {limit})"
+                    ));
+                }
+                Err(_) => changed.await,
+            }
+        }
+    }
+
+    /// Running plus reserved turns across all workspaces.
+    pub(crate) fn busy(&self) -> usize {
+        self.lock().busy()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(workspace_id: &str, method: &str, thread_id: &str) -> AppServerEvent {
+        AppServerEvent {
+            workspace_id: workspace_id.to_string(),
+            message: json!({
+                "method": method,
+                "params": { "threadId": thread_id, "turn": { "id": "turn-1" } },
+            }),
+        }
+    }
+
+    #[test]
+    fn reservations_are_freed_on_drop_and_handed_off_to_started_turns() {
+        let turns = ActiveTurns::default();
+        let first = turns.try_reserve(2, "ws", "thread-a").expect("first slot");
+        let second = turns.try_reserve(2, "ws", "thread-b").expect("second slot");
+        assert_eq!(turns.try_reserve(2, "ws", "thread-c").err(), Some(2));
+
+        drop(second);
+        assert_eq!(turns.busy(), 1);
+
+        // Between the `turn/start` response and `turn/started` the slot stays
+        // taken, then moves into the running set without double counting.
+        first.hand_off();
+        assert_eq!(turns.busy(), 1);
+        turns.observe(&event("ws", "turn/started", "thread-a"));
+        assert_eq!(turns.busy(), 1);
+        assert_eq!(turns.list("ws").len(), 1);
+
+        turns.observe(&event("ws", "turn/completed", "thread-a"));
+        assert_eq!(turns.busy(), 0);
+
+        turns.try_reserve(1, "ws", "thread-d").expect("slot").hand_off();
+        turns.clear("ws");
+        assert_eq!(turns.busy(), 0);
+    }
+
+    #[test]
+    fn cancelling_a_send_frees_its_slot_whether_queued_or_admitted() {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .expect("runtime");
+        let turns = ActiveTurns::default();
+        turns.try_reserve(1, "ws", "busy").expect("slot").hand_off();
+        turns.observe(&event("ws", "turn/started", "busy"));
+
+        // Cancel the way `run_request` does: drop the handler future.
+        let cancel = |send: std::pin::Pin<Box<dyn std::future::Future<Output = ()>>>| {
+            runtime.block_on(async {
+                let (cancel_tx, cancel_rx) = tokio::sync::oneshot::channel::<()>();
+                cancel_tx.send(()).expect("cancel");
+                tokio::select! {
+                    biased;
+                    _ = cancel_rx => {}
+                    _ = send => panic!("send should still be waiting"),
+                }
+            });
+        };
+
+        let queued = turns.clone();
+        cancel(Box::pin(async move {
+            let _slot = queued.reserve(1, true, "ws", "queued").await;
+        }));
+        assert_eq!(turns.busy(), 1);
+
+        turns.observe(&event("ws", "turn/completed", "busy"));
+        let admitted = turns.clone();
+        cancel(Box::pin(async move {
+            let _slot = admitted.reserve(1, true, "ws", "admitted").await;
+            // Waiting on the `turn/start` response.
+            std::future::pending::<()>().await;
+        }));
+        assert_eq!(turns.busy(), 0);
+        assert!(turns.try_reserve(1, "ws", "next").is_ok());
+    }
+
+    #[test]
+    fn a_poisoned_lock_still_enforces_the_limit() {
+        let turns = ActiveTurns::default();
+        turns.try_reserve(1, "ws", "thread-a").expect("slot").hand_off();
+        let poisoner = turns.clone();
+        let _ = std::thread::spawn(move || {
+            let _guard = poisoner.state.lock().unwrap();
+            panic!("poison the lock");
+        })
+        .join();
+        assert!(turns.state.is_poisoned());
+        assert_eq!(turns.try_reserve(1, "ws", "thread-b").err(), Some(1));
+    }
+}
@@ -1,5 +1,5 @@
 use serde_json::{json, Value};
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::env;
 use std::io::ErrorKind;
 use std::path::{Path, PathBuf};
@@ -15,6 +15,119 @@ use tokio::time::timeout;
 use crate::backend::events::{AppServerEvent, EventSink};
 use crate::types::WorkspaceEntry;
 
+/// Number of recent app-server stderr lines kept per session for diagnostics.
+pub(crate) const STDERR_TAIL_LINES: usize = 50;
+
+/// `thread/list` pages searched for a workspace's latest thread. The list
+/// covers every workspace, so a few pages may pass without a match.
+const LATEST_THREAD_MAX_PAGES: usize = 5;
+const LATEST_THREAD_PAGE_SIZE: u32 = 50;
+
+const FORBIDDEN_ARG_SEQUENCES: [&str; 9] = [";", "&&", "||", "|", "`", "$(", ">", "<", "\0"];
+
+/// Rejects per-workspace app-server args that look like shell plumbing. The
+/// command is never run through a shell, but these are almost always a
+/// mistake (or an attempt to chain commands) rather than a real Codex flag.
+pub(crate) fn validate_extra_args(args: &[String]) -> Result<(), String> {
+    for arg in args {
+        if arg.trim().is_empty() {
+            return Err("Extra arguments cannot be empty.".to_string());
+        }
+        if arg.contains('\n') || arg.contains('\r') {
+            return Err(format!("Extra argument contains a line break: {arg:?}"));
+        }
+        if let Some(sequence) = FORBIDDEN_ARG_SEQUENCES
+            .iter()
+            .find(|sequence| arg.contains(**sequence))
+        {
+            return Err(format!(
+                "Extra argument {arg:?} contains a forbidden sequence: {sequence}"
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Resolves the `cwd` sent to the app-server for a thread or turn. Relative
+/// overrides are joined onto the workspace path and must stay inside it.
+pub(crate) fn resolve_workspace_cwd(
+    workspace_path: &str,
+    cwd: Option<&str>,
+) -> Result<String, String> {
+    let Some(cwd) = cwd.map(|value| value.trim()).filter(|value| !value.is_empty()) else {
+        return Ok(workspace_path.to_string());
+    };
+    let root = PathBuf::from(workspace_path);
+    let candidate = if Path::new(cwd).is_absolute() {
+        PathBuf::from(cwd)
+    } else {
+        root.join(cwd)
+    };
+    let root = root.canonicalize().map_err(|err| err.to_string())?;
+    let candidate = candidate
+        .canonicalize()
+        .map_err(|_| format!("Working directory not found: {cwd}"))?;
+    if !candidate.is_dir() {
+        return Err(format!("Working directory is not a folder: {cwd}"));
+    }
+    if !candidate.starts_with(&root) {
+        return Err(format!("Working directory must be inside the workspace: {cwd}"));
+    }
+    Ok(candidate.to_string_lossy().to_string())
+}
+
+fn normalize_root_path(path: &str) -> String {
+    path.replace('\\', "/").trim_end_matches('/').to_string()
+}
+
+/// Milliseconds since the epoch for a `thread/list` entry, from its update
+/// time when present and its creation time otherwise.
+fn thread_timestamp(thread: &Value) -> i64 {
+    let raw = ["updatedAt", "updated_at", "createdAt", "created_at"]
+        .iter()
+        .find_map(|key| thread.get(*key));
+    let value = match raw {
+        Some(Value::Number(number)) => number.as_f64().unwrap_or(0.0) as i64,
+        Some(Value::String(text)) => text.trim().parse::<f64>().unwrap_or(0.0) as i64,
+        _ => 0,
+    };
+    if value > 0 && value < 1_000_000_000_000 {
+        value * 1000
+    } else {
+        value
+    }
+}
+
+/// The most recently updated thread in `threads` that belongs to the
+/// workspace at `workspace_path`, as `(threadId, timestamp)`.
+fn latest_thread_for_path(threads: &[Value], workspace_path: &str) -> Option<(String, i64)> {
+    let workspace_path = normalize_root_path(workspace_path);
+    threads
+        .iter()
+        .filter(|thread| {
+            thread
+                .get("cwd")
+                .and_then(Value::as_str)
+                .is_some_and(|cwd| normalize_root_path(cwd) == workspace_path)
+        })
+        .filter_map(|thread| {
+            let id = thread.get("id").and_then(Value::as_str)?;
+            Some((id.to_string(), thread_timestamp(thread)))
+        })
+        .max_by_key(|(_, timestamp)| *timestamp)
+}
+
+fn response_error_message(response: &Value) -> Option<String> {
+    let error = response.get("error")?;
+    Some(
+        error
+            .get("message")
+            .and_then(Value::as_str)
+            .map(str::to_string)
+            .unwrap_or_else(|| error.to_string()),
+    )
+}
+
 fn extract_thread_id(value: &Value) -> Option<String> {
     value
         .get("params")
@@ -31,9 +144,19 @@ pub(crate) struct WorkspaceSession {
     pub(crate) next_id: AtomicU64,
     /// Callbacks for background threads - events for these threadIds are sent through the channel
     pub(crate) background_thread_callbacks: Mutex<HashMap<String, mpsc::UnboundedSender<Value>>>,
+    /// Most recent stderr lines from the app-server, oldest first.
+    pub(crate) stderr_tail: Mutex<VecDeque<String>>,
 }
 
 impl WorkspaceSession {
+    async fn push_stderr_line(&self, line: String) {
+        let mut tail = self.stderr_tail.lock().await;
+        if tail.len() >= STDERR_TAIL_LINES {
+            tail.pop_front();
+        }
+        tail.push_back(line);
+    }
+
     async fn write_message(&self, value: Value) -> Result<(), String> {
         let mut stdin = self.stdin.lock().await;
         let mut line = serde_json::to_string(&value).map_err(|e| e.to_string())?;
@@ -70,6 +193,53 @@ impl WorkspaceSession {
         self.write_message(json!({ "id": id, "result": result }))
             .await
     }
+
+    /// Resumes the workspace's most recent thread, or starts a new one when
+    /// it has none. Returns the `thread/resume` or `thread/start` response
+    /// with a top-level `resumed` flag telling the two apart.
+    pub(crate) async fn resume_latest_thread(&self) -> Result<Value, String> {
+        let mut latest: Option<(String, i64)> = None;
+        let mut cursor: Option<String> = None;
+        for _ in 0..LATEST_THREAD_MAX_PAGES {
+            let params = json!({ "cursor": cursor, "limit": LATEST_THREAD_PAGE_SIZE });
+            let response = self.send_request("thread/list", params).await?;
+            if let Some(message) = response_error_message(&response) {
+                return Err(message);
+            }
+            let result = response.get("result").unwrap_or(&response);
+            let threads = result
+                .get("data")
+                .and_then(Value::as_array)
+                .map(Vec::as_slice)
+                .unwrap_or_default();
+            latest = latest_thread_for_path(threads, &self.entry.path);
+            cursor = result
+                .get("nextCursor")
+                .or_else(|| result.get("next_cursor"))
+                .and_then(Value::as_str)
+                .map(str::to_string);
+            // The list is newest first, so later pages only hold older threads.
+            if latest.is_some() || cursor.is_none() {
+                break;
+            }
+        }
+
+        let (mut response, resumed) = match latest {
+            Some((thread_id, _)) => {
+                let params = json!({ "threadId": thread_id });
+                (self.send_request("thread/resume", params).await?, true)
+            }
+            None => {
+                let cwd = resolve_workspace_cwd(&self.entry.path, None)?;
+                let params = json!({ "cwd": cwd, "approvalPolicy": "on-request" });
+                (self.send_request("thread/start", params).await?, false)
+            }
+        };
+        if let Some(object) = response.as_object_mut() {
+            object.insert("resumed".to_string(), json!(resumed));
+        }
+        Ok(response)
+    }
 }
 
 pub(crate) fn build_codex_path_env(codex_bin: Option<&str>) -> Option<String> {
@@ -123,6 +293,37 @@ pub(crate) fn build_codex_path_env(codex_bin: Option<&str>) -> Option<String> {
     }
 }
 
+/// The `codex_bin` a workspace session launches with: the workspace's own
+/// setting, else the app default. `None` means plain `codex` from PATH.
+pub(crate) fn effective_codex_bin(
+    workspace_bin: Option<&str>,
+    default_bin: Option<&str>,
+) -> Option<String> {
+    workspace_bin
+        .filter(|value| !value.trim().is_empty())
+        .or(default_bin)
+        .map(str::to_string)
+}
+
+/// Full path of the binary a workspace session would launch, searching the
+/// same PATH as [`build_codex_command_with_bin`]. `None` when it is missing.
+pub(crate) fn resolve_codex_bin(
+    workspace_bin: Option<&str>,
+    default_bin: Option<&str>,
+) -> Option<String> {
+    let codex_bin = effective_codex_bin(workspace_bin, default_bin)
+        .filter(|value| !value.trim().is_empty());
+    let bin = codex_bin.clone().unwrap_or_else(|| "codex".to_string());
+    if Path::new(&bin).components().count() > 1 {
+        return Path::new(&bin).is_file().then_some(bin);
+    }
+    build_codex_path_env(codex_bin.as_deref())?
+        .split(':')
+        .map(|dir| Path::new(dir).join(&bin))
+        .find(|candidate| candidate.is_file())
+        .map(|candidate| candidate.to_string_lossy().to_string())
+}
+
 pub(crate) fn build_codex_command_with_bin(codex_bin: Option<String>) -> Command {
     let bin = codex_bin
         .clone()
@@ -190,16 +391,14 @@ pub(crate) async fn spawn_workspace_session<E: EventSink>(
     event_sink: E,
     codex_home: Option<PathBuf>,
 ) -> Result<Arc<WorkspaceSession>, String> {
-    let codex_bin = entry
-        .codex_bin
-        .clone()
-        .filter(|value| !value.trim().is_empty())
-        .or(default_codex_bin);
+    let codex_bin = effective_codex_bin(entry.codex_bin.as_deref(), default_codex_bin.as_deref());
     let _ = check_codex_installation(codex_bin.clone()).await?;
+    validate_extra_args(&entry.settings.extra_args)?;
 
     let mut command = build_codex_command_with_bin(codex_bin);
     command.current_dir(&entry.path);
     command.arg("app-server");
+    command.args(&entry.settings.extra_args);
     if let Some(codex_home) = codex_home {
         command.env("CODEX_HOME", codex_home);
     }
@@ -219,6 +418,7 @@ pub(crate) async fn spawn_workspace_session<E: EventSink>(
         pending: Mutex::new(HashMap::new()),
         next_id: AtomicU64::new(1),
         background_thread_callbacks: Mutex::new(HashMap::new()),
+        stderr_tail: Mutex::new(VecDeque::with_capacity(STDERR_TAIL_LINES)),
     });
 
     let session_clone = Arc::clone(&session);
@@ -300,6 +500,7 @@ pub(crate) async fn spawn_workspace_session<E: EventSink>(
         }
     });
 
+    let session_clone = Arc::clone(&session);
     let workspace_id = entry.id.clone();
     let event_sink_clone = event_sink.clone();
     tokio::spawn(async move {
@@ -308,6 +509,7 @@ pub(crate) async fn spawn_workspace_session<E: EventSink>(
             if line.trim().is_empty() {
                 continue;
             }
+            session_clone.push_stderr_line(line.clone()).await;
             let payload = AppServerEvent {
                 workspace_id: workspace_id.clone(),
                 message: json!({
@@ -359,7 +561,10 @@ pub(crate) async fn spawn_workspace_session<E: EventSink>(
 
 #[cfg(test)]
 mod tests {
-    use super::extract_thread_id;
+    use super::{
+        effective_codex_bin, extract_thread_id, latest_thread_for_path, resolve_codex_bin,
+        resolve_workspace_cwd, validate_extra_args,
+    };
     use serde_json::json;
 
     #[test]
@@ -379,4 +584,63 @@ mod tests {
         let value = json!({ "params": {} });
         assert_eq!(extract_thread_id(&value), None);
     }
+
+    #[test]
+    fn latest_thread_for_path_picks_newest_thread_in_workspace() {
+        let threads = vec![
+            json!({ "id": "other", "cwd": "/repo/other", "updatedAt": 1_900_000_000 }),
+            json!({ "id": "old", "cwd": "/repo/app/", "createdAt": 1_700_000_000 }),
+            json!({ "id": "new", "cwd": "/repo/app", "updatedAt": 1_800_000_000_000_i64 }),
+        ];
+        assert_eq!(
+            latest_thread_for_path(&threads, "/repo/app"),
+            Some(("new".to_string(), 1_800_000_000_000))
+        );
+        assert_eq!(latest_thread_for_path(&threads, "/repo/missing"), None);
+    }
+
+    #[test]
+    fn resolve_workspace_cwd_stays_inside_workspace() {
+        let root = std::env::temp_dir()
+            .join(format!("codex-monitor-cwd-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(root.join("packages/app")).expect("create package dir");
+        let root_str = root.to_string_lossy().to_string();
+
+        assert_eq!(resolve_workspace_cwd(&root_str, None).unwrap(), root_str);
+        let resolved = resolve_workspace_cwd(&root_str, Some("packages/app")).unwrap();
+        assert!(resolved.ends_with("packages/app"));
+        assert!(resolve_workspace_cwd(&root_str, Some("..")).is_err());
+        assert!(resolve_workspace_cwd(&root_str, Some("missing")).is_err());
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn codex_bin_prefers_the_workspace_then_the_default() {
+        let default_bin = Some("/app/codex");
+        assert_eq!(effective_codex_bin(Some("/ws/codex"), default_bin).unwrap(), "/ws/codex");
+        assert_eq!(effective_codex_bin(Some("  "), default_bin).unwrap(), "/app/codex");
+        assert_eq!(effective_codex_bin(None, None), None);
+
+        let dir = std::env::temp_dir().join(format!("codex-monitor-bin-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).expect("create bin dir");
+        let bin = dir.join("codex-custom");
+        std::fs::write(&bin, "#!/bin/sh\n").expect("write bin");
+        let bin_str = bin.to_string_lossy().to_string();
+        assert_eq!(resolve_codex_bin(None, Some(&bin_str)), Some(bin_str.clone()));
+        let missing = dir.join("missing").to_string_lossy().to_string();
+        assert_eq!(resolve_codex_bin(Some(&missing), Some(&bin_str)), None);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn validate_extra_args_accepts_flags_and_rejects_separators() {
+        let ok = vec!["--config".to_string(), "features.foo=true".to_string()];
+        assert!(validate_extra_args(&ok).is_ok());
+
+        for bad in ["--flag; rm -rf /", "a && b", "$(whoami)", "   ", "line\nbreak"] {
+            assert!(validate_extra_args(&[bad.to_string()]).is_err(), "{bad}");
+        }
+    }
 }
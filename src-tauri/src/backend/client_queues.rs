@@ -0,0 +1,74 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use tokio::sync::mpsc;
+
+/// Events buffered per lossless client before it is cut off.
+pub(crate) const CLIENT_QUEUE_CAPACITY: usize = 16_384;
+
+/// Dedicated bounded event queues for clients that asked for lossless
+/// delivery, keyed by connection id. Unlike the shared broadcast channel, a
+/// queue never skips events: when one fills up it is closed, so its reader
+/// sees the end of the stream after draining what was queued.
+#[derive(Clone)]
+pub(crate) struct ClientQueues<T> {
+    inner: Arc<Mutex<HashMap<u64, mpsc::Sender<T>>>>,
+}
+
+impl<T> Default for ClientQueues<T> {
+    fn default() -> Self {
+        Self {
+            inner: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+}
+
+impl<T: Clone> ClientQueues<T> {
+    pub(crate) fn register(&self, id: u64, capacity: usize) -> mpsc::Receiver<T> {
+        let (tx, rx) = mpsc::channel(capacity);
+        if let Ok(mut queues) = self.inner.lock() {
+            queues.insert(id, tx);
+        }
+        rx
+    }
+
+    pub(crate) fn remove(&self, id: u64) {
+        if let Ok(mut queues) = self.inner.lock() {
+            queues.remove(&id);
+        }
+    }
+
+    /// Queues `event` for every registered client, dropping the queues that
+    /// are full or whose reader has gone away.
+    pub(crate) fn push(&self, event: &T) {
+        if let Ok(mut queues) = self.inner.lock() {
+            queues.retain(|_, queue| queue.try_send(event.clone()).is_ok());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn full_queue_is_closed_without_affecting_others() {
+        let queues = ClientQueues::default();
+        let mut slow = queues.register(1, 2);
+        let mut fast = queues.register(2, 8);
+
+        for event in 0..3 {
+            queues.push(&event);
+            if let Ok(received) = fast.try_recv() {
+                assert_eq!(received, event);
+            }
+        }
+
+        assert_eq!(slow.try_recv(), Ok(0));
+        assert_eq!(slow.try_recv(), Ok(1));
+        assert_eq!(slow.try_recv(), Err(mpsc::error::TryRecvError::Disconnected));
+
+        queues.push(&3);
+        assert_eq!(fast.try_recv(), Ok(3));
+    }
+}
@@ -0,0 +1,151 @@
+use serde_json::{json, Value};
+
+pub(crate) const DEFAULT_DIFF_DIGEST_MAX_BYTES: usize = 32 * 1024;
+/// Patches larger than this are reduced to their hunk headers.
+pub(crate) const DIFF_DIGEST_FULL_PATCH_BYTES: usize = 4 * 1024;
+
+pub(crate) struct FilePatch {
+    pub(crate) path: String,
+    pub(crate) additions: usize,
+    pub(crate) deletions: usize,
+    pub(crate) patch: String,
+}
+
+fn hunk_outline(patch: &str) -> String {
+    let mut outline = String::new();
+    for line in patch.lines() {
+        if line.starts_with("diff --git ") || line.starts_with("@@") {
+            outline.push_str(line);
+            outline.push('\n');
+        }
+    }
+    outline
+}
+
+/// Builds a prompt-sized summary of a diff: a `+/-` line per file, then the
+/// full patch for small files and a hunk-header outline for large ones, until
+/// `max_bytes` is reached. Files that no longer fit are listed as omitted.
+pub(crate) fn build_diff_digest(files: &[FilePatch], max_bytes: usize) -> Value {
+    let mut text = String::new();
+    for file in files {
+        text.push_str(&format!(
+            "{} +{} -{}\n",
+            file.path, file.additions, file.deletions
+        ));
+    }
+    let mut truncated = text.len() > max_bytes;
+    if truncated {
+        let mut end = max_bytes;
+        while !text.is_char_boundary(end) {
+            end -= 1;
+        }
+        text.truncate(end);
+    }
+
+    let mut entries = Vec::with_capacity(files.len());
+    for file in files {
+        let (mode, body) = if file.patch.len() <= DIFF_DIGEST_FULL_PATCH_BYTES {
+            ("patch", file.patch.clone())
+        } else {
+            ("outline", hunk_outline(&file.patch))
+        };
+        let mode = if !truncated && text.len() + 1 + body.len() <= max_bytes {
+            text.push('\n');
+            text.push_str(&body);
+            mode
+        } else {
+            truncated = true;
+            "omitted"
+        };
+        entries.push(json!({
+            "path": file.path,
+            "additions": file.additions,
+            "deletions": file.deletions,
+            "mode": mode,
+        }));
+    }
+
+    json!({
+        "text": text,
+        "files": entries,
+        "truncated": truncated,
+        "bytes": text.len(),
+        "maxBytes": max_bytes,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Splits `git diff` output into one patch per file.
+    fn split_unified_diff(diff: &str) -> Vec<FilePatch> {
+        let mut patches: Vec<FilePatch> = Vec::new();
+        for line in diff.lines() {
+            if let Some(header) = line.strip_prefix("diff --git ") {
+                let path = header
+                    .split_once(" b/")
+                    .map(|(_, path)| path)
+                    .unwrap_or(header)
+                    .to_string();
+                patches.push(FilePatch {
+                    path,
+                    additions: 0,
+                    deletions: 0,
+                    patch: String::new(),
+                });
+            }
+            let Some(current) = patches.last_mut() else {
+                continue;
+            };
+            if line.starts_with('+') && !line.starts_with("+++") {
+                current.additions += 1;
+            } else if line.starts_with('-') && !line.starts_with("---") {
+                current.deletions += 1;
+            }
+            current.patch.push_str(line);
+            current.patch.push('\n');
+        }
+        patches
+    }
+
+    fn sample_diff(large_lines: usize) -> String {
+        let mut diff = String::from(
+            "diff --git a/small.txt b/small.txt\n--- a/small.txt\n+++ b/small.txt\n@@ -1 +1 @@\n-old\n+new\n",
+        );
+        diff.push_str("diff --git a/large.txt b/large.txt\n--- a/large.txt\n+++ b/large.txt\n");
+        diff.push_str(&format!("@@ -0,0 +1,{large_lines} @@\n"));
+        for index in 0..large_lines {
+            diff.push_str(&format!("+line {index}\n"));
+        }
+        diff
+    }
+
+    #[test]
+    fn small_files_keep_patch_and_large_files_get_outline() {
+        let files = split_unified_diff(&sample_diff(2000));
+        assert_eq!(files.len(), 2);
+        assert_eq!(files[0].path, "small.txt");
+        assert_eq!((files[0].additions, files[0].deletions), (1, 1));
+        assert_eq!(files[1].additions, 2000);
+
+        let digest = build_diff_digest(&files, DEFAULT_DIFF_DIGEST_MAX_BYTES);
+        assert_eq!(digest["files"][0]["mode"], "patch");
+        assert_eq!(digest["files"][1]["mode"], "outline");
+        assert_eq!(digest["truncated"], false);
+        let text = digest["text"].as_str().unwrap();
+        assert!(text.starts_with("small.txt +1 -1\nlarge.txt +2000 -0\n"));
+        assert!(text.contains("+new"));
+        assert!(text.contains("@@ -0,0 +1,2000 @@"));
+        assert!(!text.contains("+line 1\n"));
+    }
+
+    #[test]
+    fn digest_respects_max_bytes() {
+        let files = split_unified_diff(&sample_diff(10));
+        let digest = build_diff_digest(&files, 60);
+        assert_eq!(digest["truncated"], true);
+        assert!(digest["bytes"].as_u64().unwrap() <= 60);
+        assert_eq!(digest["files"][1]["mode"], "omitted");
+    }
+}
@@ -0,0 +1,57 @@
+use serde_json::Value;
+
+const REDACTED: &str = "[redacted]";
+
+/// Replaces the text of user prompts in an app-server message so the event
+/// log does not keep them. Only `userMessage` items carry prompt text; agent
+/// output and tool activity are left intact.
+pub(crate) fn redact_prompts(message: &mut Value) {
+    let Some(item) = message
+        .get_mut("params")
+        .and_then(|params| params.get_mut("item"))
+    else {
+        return;
+    };
+    if item.get("type").and_then(Value::as_str) != Some("userMessage") {
+        return;
+    }
+    let Some(inputs) = item.get_mut("content").and_then(Value::as_array_mut) else {
+        return;
+    };
+    for input in inputs {
+        if let Some(text) = input.get_mut("text") {
+            *text = Value::String(REDACTED.to_string());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn only_user_message_text_is_redacted() {
+        let mut user = json!({
+            "method": "item/started",
+            "params": { "item": {
+                "type": "userMessage",
+                "content": [
+                    { "type": "text", "text": "deploy with key abc" },
+                    { "type": "localImage", "path": "/tmp/a.png" },
+                ],
+            }},
+        });
+        redact_prompts(&mut user);
+        assert_eq!(user["params"]["item"]["content"][0]["text"], REDACTED);
+        assert_eq!(user["params"]["item"]["content"][1]["path"], "/tmp/a.png");
+
+        let agent = json!({
+            "method": "item/completed",
+            "params": { "item": { "type": "agentMessage", "text": "done" } },
+        });
+        let mut redacted = agent.clone();
+        redact_prompts(&mut redacted);
+        assert_eq!(redacted, agent);
+    }
+}
@@ -0,0 +1,122 @@
+use std::path::Path;
+
+use git2::{DiffOptions, Repository};
+use serde_json::Value;
+
+use crate::backend::diff_digest::{build_diff_digest, FilePatch, DEFAULT_DIFF_DIGEST_MAX_BYTES};
+use crate::git_utils::diff_patch_to_string;
+use crate::types::GitFileDiff;
+use crate::utils::normalize_git_path;
+
+/// Hands each changed file between HEAD and the working tree to `on_file`,
+/// untracked files included, building one patch at a time so callers can
+/// stream large diffs.
+pub(crate) fn for_each_file_diff(
+    repo_root: &Path,
+    mut on_file: impl FnMut(GitFileDiff),
+) -> Result<(), String> {
+    let repo = Repository::open(repo_root).map_err(|e| e.to_string())?;
+    let head_tree = repo
+        .head()
+        .ok()
+        .and_then(|head| head.peel_to_tree().ok());
+
+    let mut options = DiffOptions::new();
+    options
+        .include_untracked(true)
+        .recurse_untracked_dirs(true)
+        .show_untracked_content(true);
+
+    let mut diff = match head_tree.as_ref() {
+        Some(tree) => repo
+            .diff_tree_to_workdir_with_index(Some(tree), Some(&mut options))
+            .map_err(|e| e.to_string())?,
+        None => repo
+            .diff_tree_to_workdir_with_index(None, Some(&mut options))
+            .map_err(|e| e.to_string())?,
+    };
+    // Pair deleted and added files (including untracked ones) into renames
+    // so a moved file shows up as one entry.
+    let mut find_options = git2::DiffFindOptions::new();
+    find_options.renames(true).for_untracked(true);
+    diff.find_similar(Some(&mut find_options))
+        .map_err(|e| e.to_string())?;
+
+    for (index, delta) in diff.deltas().enumerate() {
+        let path = delta
+            .new_file()
+            .path()
+            .or_else(|| delta.old_file().path());
+        let Some(path) = path else {
+            continue;
+        };
+        let renamed_from = (delta.status() == git2::Delta::Renamed)
+            .then(|| delta.old_file().path())
+            .flatten()
+            .map(|old_path| normalize_git_path(old_path.to_string_lossy().as_ref()));
+        let patch = match git2::Patch::from_diff(&diff, index) {
+            Ok(patch) => patch,
+            Err(_) => continue,
+        };
+        let Some(mut patch) = patch else {
+            continue;
+        };
+        let path = normalize_git_path(path.to_string_lossy().as_ref());
+        if patch.delta().flags().is_binary() {
+            on_file(GitFileDiff {
+                path,
+                diff: String::new(),
+                additions: 0,
+                deletions: 0,
+                renamed_from,
+                is_binary: true,
+            });
+            continue;
+        }
+        let content = match diff_patch_to_string(&mut patch) {
+            Ok(content) => content,
+            Err(_) => continue,
+        };
+        if content.trim().is_empty() {
+            continue;
+        }
+        let (_, additions, deletions) = patch.line_stats().map_err(|e| e.to_string())?;
+        on_file(GitFileDiff {
+            path,
+            diff: content,
+            additions,
+            deletions,
+            renamed_from,
+            is_binary: false,
+        });
+    }
+    Ok(())
+}
+
+pub(crate) fn git_diffs_for_path(repo_root: &Path) -> Result<Vec<GitFileDiff>, String> {
+    let mut results = Vec::new();
+    for_each_file_diff(repo_root, |file| results.push(file))?;
+    Ok(results)
+}
+
+/// `build_diff_digest` over the same files `git_diffs_for_path` reports.
+/// Binary files have no patch text and are left out.
+pub(crate) fn diff_digest_for_path(
+    repo_root: &Path,
+    max_bytes: Option<usize>,
+) -> Result<Value, String> {
+    let files: Vec<FilePatch> = git_diffs_for_path(repo_root)?
+        .into_iter()
+        .filter(|file| !file.is_binary)
+        .map(|file| FilePatch {
+            path: file.path,
+            additions: file.additions,
+            deletions: file.deletions,
+            patch: file.diff,
+        })
+        .collect();
+    Ok(build_diff_digest(
+        &files,
+        max_bytes.unwrap_or(DEFAULT_DIFF_DIGEST_MAX_BYTES),
+    ))
+}
@@ -0,0 +1,65 @@
+use serde_json::{json, Value};
+
+/// Sorts `git fsck` output into dangling objects (harmless leftovers),
+/// missing objects and anything else git reported as an error.
+pub(crate) fn parse_fsck_output(output: &str, success: bool) -> Value {
+    let mut dangling = Vec::new();
+    let mut missing = Vec::new();
+    let mut errors = Vec::new();
+    for line in output.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with("Checking ") {
+            continue;
+        }
+        let mut parts = line.split_whitespace();
+        let (kind, object) = match (parts.next(), parts.next(), parts.next()) {
+            (Some(kind @ ("dangling" | "missing")), Some(object), Some(oid)) => {
+                (kind, json!({ "type": object, "oid": oid }))
+            }
+            _ => {
+                errors.push(line.to_string());
+                continue;
+            }
+        };
+        if kind == "dangling" {
+            dangling.push(object);
+        } else {
+            missing.push(object);
+        }
+    }
+    let ok = success && missing.is_empty() && errors.is_empty();
+    json!({
+        "ok": ok,
+        "dangling": dangling,
+        "missing": missing,
+        "errors": errors,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classifies_fsck_lines() {
+        let output = "dangling commit 1111\n\
+                      missing blob 2222\n\
+                      broken link from    tree 3333\n\
+                      \x20             to    blob 2222\n\
+                      error: object file .git/objects/44/44 is empty\n";
+        let result = parse_fsck_output(output, false);
+        assert_eq!(result["ok"], false);
+        assert_eq!(
+            result["dangling"],
+            json!([{ "type": "commit", "oid": "1111" }])
+        );
+        assert_eq!(
+            result["missing"],
+            json!([{ "type": "blob", "oid": "2222" }])
+        );
+        assert_eq!(result["errors"].as_array().unwrap().len(), 3);
+
+        let clean = parse_fsck_output("dangling blob 5555\n", true);
+        assert_eq!(clean["ok"], true);
+    }
+}
@@ -0,0 +1,144 @@
+use std::path::{Path, PathBuf};
+
+use serde_json::{json, Map, Value};
+
+/// Parses `git worktree list --porcelain` into one object per worktree:
+/// `{ path, head, branch, detached, bare, locked, lockedReason, prunable,
+/// prunableReason }`. `branch` drops the `refs/heads/` prefix.
+pub(crate) fn parse_worktree_list(output: &str) -> Vec<Value> {
+    let mut worktrees = Vec::new();
+    let mut current: Option<Map<String, Value>> = None;
+    for line in output.lines() {
+        let (key, value) = match line.split_once(' ') {
+            Some((key, value)) => (key, Some(value)),
+            None => (line, None),
+        };
+        if key == "worktree" {
+            worktrees.extend(current.take().map(Value::Object));
+            current = Some(worktree_entry(value.unwrap_or_default()));
+            continue;
+        }
+        let Some(entry) = current.as_mut() else {
+            continue;
+        };
+        match key {
+            "HEAD" => {
+                entry.insert("head".to_string(), json!(value));
+            }
+            "branch" => {
+                let branch = value.map(|name| name.strip_prefix("refs/heads/").unwrap_or(name));
+                entry.insert("branch".to_string(), json!(branch));
+            }
+            "detached" | "bare" => {
+                entry.insert(key.to_string(), json!(true));
+            }
+            "locked" | "prunable" => {
+                entry.insert(key.to_string(), json!(true));
+                let reason = value.filter(|reason| !reason.is_empty());
+                entry.insert(format!("{key}Reason"), json!(reason));
+            }
+            _ => {}
+        }
+    }
+    worktrees.extend(current.map(Value::Object));
+    worktrees
+}
+
+/// Picks the `(id, path)` children whose directory is gone or that `listing`
+/// (from [`parse_worktree_list`]) no longer reports as a live worktree.
+pub(crate) fn stale_worktree_ids(children: &[(String, String)], listing: &[Value]) -> Vec<String> {
+    let canonical =
+        |path: &str| std::fs::canonicalize(path).unwrap_or_else(|_| PathBuf::from(path));
+    let registered: Vec<PathBuf> = listing
+        .iter()
+        .filter(|worktree| worktree["prunable"] != true)
+        .filter_map(|worktree| worktree["path"].as_str())
+        .map(canonical)
+        .collect();
+    children
+        .iter()
+        .filter(|(_, path)| !Path::new(path).is_dir() || !registered.contains(&canonical(path)))
+        .map(|(id, _)| id.clone())
+        .collect()
+}
+
+fn worktree_entry(path: &str) -> Map<String, Value> {
+    let mut entry = Map::new();
+    entry.insert("path".to_string(), json!(path));
+    for key in ["head", "branch", "lockedReason", "prunableReason"] {
+        entry.insert(key.to_string(), Value::Null);
+    }
+    for key in ["detached", "bare", "locked", "prunable"] {
+        entry.insert(key.to_string(), json!(false));
+    }
+    entry
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stale_worktrees_are_missing_or_unregistered() {
+        let root = std::env::temp_dir()
+            .join(format!("codex-monitor-worktrees-{}", uuid::Uuid::new_v4()));
+        for name in ["live", "unregistered", "prunable"] {
+            std::fs::create_dir_all(root.join(name)).expect("create worktree dir");
+        }
+        let path = |name: &str| root.join(name).to_string_lossy().to_string();
+        let listing = parse_worktree_list(&format!(
+            "worktree {}\nHEAD 1111\nbranch refs/heads/live\n\n\
+             worktree {}\nHEAD 2222\nprunable gitdir file points to non-existent location\n",
+            path("live"),
+            path("prunable"),
+        ));
+        let children = vec![
+            ("live".to_string(), path("live")),
+            ("unregistered".to_string(), path("unregistered")),
+            ("prunable".to_string(), path("prunable")),
+            ("deleted".to_string(), path("deleted")),
+        ];
+
+        let stale = stale_worktree_ids(&children, &listing);
+        assert_eq!(stale, vec!["unregistered", "prunable", "deleted"]);
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn parses_porcelain_worktree_list() {
+        let output = "worktree /repo\n\
+                      HEAD 1111\n\
+                      branch refs/heads/main\n\
+                      \n\
+                      worktree /tmp/review\n\
+                      HEAD 2222\n\
+                      detached\n\
+                      locked on a USB drive\n\
+                      \n\
+                      worktree /tmp/gone\n\
+                      HEAD 3333\n\
+                      branch refs/heads/feature/x\n\
+                      locked\n\
+                      prunable gitdir file points to non-existent location\n\
+                      \n";
+        let worktrees = parse_worktree_list(output);
+        assert_eq!(worktrees.len(), 3);
+        assert_eq!(worktrees[0]["path"], "/repo");
+        assert_eq!(worktrees[0]["head"], "1111");
+        assert_eq!(worktrees[0]["branch"], "main");
+        assert_eq!(worktrees[0]["locked"], false);
+
+        assert!(worktrees[1]["branch"].is_null());
+        assert_eq!(worktrees[1]["detached"], true);
+        assert_eq!(worktrees[1]["locked"], true);
+        assert_eq!(worktrees[1]["lockedReason"], "on a USB drive");
+
+        assert_eq!(worktrees[2]["branch"], "feature/x");
+        assert!(worktrees[2]["lockedReason"].is_null());
+        assert_eq!(worktrees[2]["prunable"], true);
+        assert_eq!(
+            worktrees[2]["prunableReason"],
+            "gitdir file points to non-existent location"
+        );
+    }
+}
@@ -0,0 +1,86 @@
+use std::collections::{BTreeSet, HashMap};
+use std::path::Path;
+use std::time::Duration;
+
+pub(crate) const DEFAULT_ISSUE_POLL_INTERVAL: Duration = Duration::from_secs(300);
+pub(crate) const MIN_ISSUE_POLL_INTERVAL: Duration = Duration::from_secs(60);
+
+pub(crate) fn issue_poll_interval(seconds: Option<u64>) -> Duration {
+    seconds
+        .map(Duration::from_secs)
+        .unwrap_or(DEFAULT_ISSUE_POLL_INTERVAL)
+        .max(MIN_ISSUE_POLL_INTERVAL)
+}
+
+/// Issue numbers already reported per workspace, persisted so a restart does
+/// not re-announce every open issue.
+#[derive(Default)]
+pub(crate) struct SeenIssues {
+    seen: HashMap<String, BTreeSet<u64>>,
+}
+
+impl SeenIssues {
+    pub(crate) fn load(path: &Path) -> Self {
+        let seen = std::fs::read_to_string(path)
+            .ok()
+            .and_then(|data| serde_json::from_str(&data).ok())
+            .unwrap_or_default();
+        Self { seen }
+    }
+
+    pub(crate) fn save(&self, path: &Path) -> Result<(), String> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        let data = serde_json::to_string_pretty(&self.seen).map_err(|e| e.to_string())?;
+        std::fs::write(path, data).map_err(|e| e.to_string())
+    }
+
+    /// Records `numbers` for a workspace and returns the ones not seen before.
+    /// The first observation of a workspace only seeds the set.
+    pub(crate) fn observe(&mut self, workspace_id: &str, numbers: &[u64]) -> Vec<u64> {
+        let Some(seen) = self.seen.get_mut(workspace_id) else {
+            self.seen
+                .insert(workspace_id.to_string(), numbers.iter().copied().collect());
+            return Vec::new();
+        };
+        let mut fresh: Vec<u64> = numbers
+            .iter()
+            .copied()
+            .filter(|number| seen.insert(*number))
+            .collect();
+        fresh.sort_unstable();
+        fresh.dedup();
+        fresh
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn first_poll_seeds_and_later_polls_report_new_issues() {
+        let mut seen = SeenIssues::default();
+        assert!(seen.observe("ws", &[1, 2, 3]).is_empty());
+        assert_eq!(seen.observe("ws", &[4, 2, 5]), vec![4, 5]);
+        assert!(seen.observe("ws", &[4, 5]).is_empty());
+        assert!(seen.observe("other", &[1]).is_empty());
+    }
+
+    #[test]
+    fn seen_issues_survive_reload() {
+        let path = std::env::temp_dir()
+            .join(format!("codex-monitor-issues-{}", uuid::Uuid::new_v4()))
+            .join("seen.json");
+        let mut seen = SeenIssues::default();
+        seen.observe("ws", &[7]);
+        seen.save(&path).expect("save seen issues");
+
+        let mut reloaded = SeenIssues::load(&path);
+        assert_eq!(reloaded.observe("ws", &[7, 8]), vec![8]);
+        assert_eq!(issue_poll_interval(Some(5)), MIN_ISSUE_POLL_INTERVAL);
+
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
+}
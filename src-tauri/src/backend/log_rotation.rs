@@ -0,0 +1,91 @@
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+
+pub(crate) const DEFAULT_LOG_MAX_BYTES: u64 = 10 * 1024 * 1024;
+pub(crate) const DEFAULT_LOG_KEEP: usize = 5;
+
+/// Size cap shared by the daemon's append-only logs. A `max_bytes` of zero
+/// disables rotation.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) struct LogRotation {
+    pub(crate) max_bytes: u64,
+    pub(crate) keep: usize,
+}
+
+impl Default for LogRotation {
+    fn default() -> Self {
+        Self {
+            max_bytes: DEFAULT_LOG_MAX_BYTES,
+            keep: DEFAULT_LOG_KEEP,
+        }
+    }
+}
+
+fn rotated_path(path: &Path, index: usize) -> PathBuf {
+    let mut name = OsString::from(path.as_os_str());
+    name.push(format!(".{index}"));
+    PathBuf::from(name)
+}
+
+impl LogRotation {
+    /// Rotates `path` when appending `incoming` bytes would push it past
+    /// `max_bytes`: `<path>.1` .. `<path>.<keep>` shift up by one, the oldest
+    /// is deleted, and `path` becomes `<path>.1`. With `keep` of zero the
+    /// current file is simply discarded.
+    pub(crate) fn rotate_if_needed(&self, path: &Path, incoming: u64) -> Result<(), String> {
+        if self.max_bytes == 0 {
+            return Ok(());
+        }
+        let Ok(metadata) = std::fs::metadata(path) else {
+            return Ok(());
+        };
+        if metadata.len() == 0 || metadata.len() + incoming <= self.max_bytes {
+            return Ok(());
+        }
+        if self.keep == 0 {
+            return std::fs::remove_file(path).map_err(|err| err.to_string());
+        }
+        let oldest = rotated_path(path, self.keep);
+        if oldest.exists() {
+            std::fs::remove_file(&oldest).map_err(|err| err.to_string())?;
+        }
+        for index in (1..self.keep).rev() {
+            let from = rotated_path(path, index);
+            if from.exists() {
+                std::fs::rename(&from, rotated_path(path, index + 1))
+                    .map_err(|err| err.to_string())?;
+            }
+        }
+        std::fs::rename(path, rotated_path(path, 1)).map_err(|err| err.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rotation_keeps_the_newest_files() {
+        let dir = std::env::temp_dir().join(format!("codex-monitor-logs-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).expect("create log dir");
+        let path = dir.join("events.jsonl");
+        let rotation = LogRotation {
+            max_bytes: 4,
+            keep: 2,
+        };
+
+        for generation in ["a", "b", "c", "d"] {
+            rotation.rotate_if_needed(&path, 1).expect("rotate");
+            std::fs::write(&path, generation.repeat(4)).expect("write log");
+        }
+        rotation.rotate_if_needed(&path, 0).expect("under the cap");
+
+        let read = |path: PathBuf| std::fs::read_to_string(path).unwrap_or_default();
+        assert_eq!(read(path.clone()), "dddd");
+        assert_eq!(read(rotated_path(&path, 1)), "cccc");
+        assert_eq!(read(rotated_path(&path, 2)), "bbbb");
+        assert!(!rotated_path(&path, 3).exists());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
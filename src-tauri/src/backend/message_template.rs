@@ -0,0 +1,72 @@
+use std::collections::HashMap;
+
+/// The `{{...}}` placeholders in `template`, in order, without the braces.
+pub(crate) fn template_placeholders(template: &str) -> Result<Vec<String>, String> {
+    let mut placeholders = Vec::new();
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        let after = &rest[start + 2..];
+        let end = after
+            .find("}}")
+            .ok_or("Unterminated template placeholder")?;
+        placeholders.push(after[..end].to_string());
+        rest = &after[end + 2..];
+    }
+    Ok(placeholders)
+}
+
+/// Replaces each placeholder with its value in one left-to-right pass.
+/// Values are copied literally, so a diff or issue body that itself contains
+/// `{{...}}` is never expanded again.
+pub(crate) fn render_template(
+    template: &str,
+    values: &HashMap<String, String>,
+) -> Result<String, String> {
+    let mut expanded = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        expanded.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let end = after
+            .find("}}")
+            .ok_or("Unterminated template placeholder")?;
+        let placeholder = &after[..end];
+        let value = values
+            .get(placeholder)
+            .ok_or_else(|| format!("Unknown template placeholder: {{{{{placeholder}}}}}"))?;
+        expanded.push_str(value);
+        rest = &after[end + 2..];
+    }
+    expanded.push_str(rest);
+    Ok(expanded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn values_are_inserted_literally_in_one_pass() {
+        let template = "Fix {{issue:12}} on {{branch}}.\n{{diff}}";
+        assert_eq!(
+            template_placeholders(template).unwrap(),
+            vec!["issue:12", "branch", "diff"]
+        );
+        let values: HashMap<String, String> = [
+            ("issue:12", "Crash when {{diff}} or {{branch}} is empty"),
+            ("branch", "main"),
+            ("diff", "+ let x = \"{{issue:12}}\";"),
+        ]
+        .into_iter()
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect();
+
+        assert_eq!(
+            render_template(template, &values).unwrap(),
+            "Fix Crash when {{diff}} or {{branch}} is empty on main.\n\
+             + let x = \"{{issue:12}}\";"
+        );
+        assert!(render_template("{{branch", &values).is_err());
+        assert!(render_template("{{other}}", &values).is_err());
+    }
+}
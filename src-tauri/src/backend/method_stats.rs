@@ -0,0 +1,119 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use serde_json::{json, Value};
+
+/// Upper bounds (in milliseconds) of the latency buckets; the final bucket
+/// catches everything slower.
+const BUCKET_BOUNDS_MS: [u64; 14] = [
+    1, 2, 5, 10, 20, 50, 100, 200, 500, 1_000, 2_000, 5_000, 10_000, 30_000,
+];
+
+#[derive(Default)]
+struct MethodHistogram {
+    calls: u64,
+    errors: u64,
+    max_ms: u64,
+    buckets: [u64; BUCKET_BOUNDS_MS.len() + 1],
+}
+
+impl MethodHistogram {
+    fn record(&mut self, elapsed_ms: u64, ok: bool) {
+        self.calls += 1;
+        if !ok {
+            self.errors += 1;
+        }
+        self.max_ms = self.max_ms.max(elapsed_ms);
+        let bucket = BUCKET_BOUNDS_MS
+            .iter()
+            .position(|bound| elapsed_ms <= *bound)
+            .unwrap_or(BUCKET_BOUNDS_MS.len());
+        self.buckets[bucket] += 1;
+    }
+
+    /// Approximates a percentile as the upper bound of the bucket containing
+    /// it, capped at the slowest call seen.
+    fn percentile_ms(&self, percentile: f64) -> u64 {
+        if self.calls == 0 {
+            return 0;
+        }
+        let target = ((self.calls as f64) * percentile).ceil().max(1.0) as u64;
+        let mut seen = 0u64;
+        for (index, count) in self.buckets.iter().enumerate() {
+            seen += count;
+            if seen >= target {
+                let bound = BUCKET_BOUNDS_MS.get(index).copied().unwrap_or(self.max_ms);
+                return bound.min(self.max_ms);
+            }
+        }
+        self.max_ms
+    }
+}
+
+/// Per-method call counts, error counts and bucketed latencies for the
+/// daemon's RPC surface.
+#[derive(Default)]
+pub(crate) struct MethodStats {
+    methods: HashMap<String, MethodHistogram>,
+}
+
+impl MethodStats {
+    pub(crate) fn record(&mut self, method: &str, elapsed: Duration, ok: bool) {
+        let elapsed_ms = elapsed.as_millis().min(u64::MAX as u128) as u64;
+        match self.methods.get_mut(method) {
+            Some(histogram) => histogram.record(elapsed_ms, ok),
+            None => {
+                let mut histogram = MethodHistogram::default();
+                histogram.record(elapsed_ms, ok);
+                self.methods.insert(method.to_string(), histogram);
+            }
+        }
+    }
+
+    pub(crate) fn snapshot(&self) -> Vec<Value> {
+        let mut names: Vec<&String> = self.methods.keys().collect();
+        names.sort();
+        names
+            .into_iter()
+            .map(|name| {
+                let histogram = &self.methods[name];
+                json!({
+                    "method": name,
+                    "calls": histogram.calls,
+                    "errors": histogram.errors,
+                    "p50Ms": histogram.percentile_ms(0.50),
+                    "p95Ms": histogram.percentile_ms(0.95),
+                    "maxMs": histogram.max_ms,
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn records_counts_errors_and_percentiles() {
+        let mut stats = MethodStats::default();
+        for _ in 0..90 {
+            stats.record("get_git_status", Duration::from_millis(8), true);
+        }
+        for _ in 0..10 {
+            stats.record("get_git_status", Duration::from_millis(1_500), false);
+        }
+        stats.record("ping", Duration::from_micros(200), true);
+
+        let snapshot = stats.snapshot();
+        assert_eq!(snapshot.len(), 2);
+        let git = &snapshot[0];
+        assert_eq!(git["method"], "get_git_status");
+        assert_eq!(git["calls"], 100);
+        assert_eq!(git["errors"], 10);
+        assert_eq!(git["p50Ms"], 10);
+        assert_eq!(git["p95Ms"], 1_500);
+        assert_eq!(git["maxMs"], 1_500);
+        assert_eq!(snapshot[1]["p95Ms"], 0);
+    }
+}
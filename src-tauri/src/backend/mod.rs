@@ -1,2 +1,28 @@
+pub(crate) mod active_turns;
 pub(crate) mod app_server;
+pub(crate) mod client_queues;
+pub(crate) mod diff_digest;
+pub(crate) mod event_log;
 pub(crate) mod events;
+pub(crate) mod git_diffs;
+pub(crate) mod git_fsck;
+pub(crate) mod git_worktrees;
+pub(crate) mod issue_watch;
+pub(crate) mod log_rotation;
+pub(crate) mod message_template;
+pub(crate) mod method_stats;
+pub(crate) mod proxy_env;
+pub(crate) mod request_cancel;
+pub(crate) mod rpc_framing;
+pub(crate) mod rpc_schema;
+pub(crate) mod thread_list;
+pub(crate) mod tls;
+pub(crate) mod turn_messages;
+pub(crate) mod turn_policy;
+#[cfg(unix)]
+pub(crate) mod unix_socket;
+pub(crate) mod upstream_watch;
+pub(crate) mod workspace_files;
+pub(crate) mod workspace_groups;
+pub(crate) mod word_diff;
+pub(crate) mod workspace_paths;
@@ -0,0 +1,82 @@
+use std::sync::RwLock;
+
+/// Variables added to every git and gh subprocess, from `proxyUrl`/`noProxy`.
+static PROXY_ENV: RwLock<Vec<(String, String)>> = RwLock::new(Vec::new());
+
+/// Builds the environment that routes git and gh through `proxy_url` (an
+/// `http://`, `https://` or `socks5://` URL): the proxy variables curl and Go
+/// read, plus git's `http.proxy` passed as `GIT_CONFIG_*` after any config
+/// entries the daemon already inherited.
+pub(crate) fn proxy_vars(
+    proxy_url: Option<&str>,
+    no_proxy: Option<&str>,
+    inherited_config_count: usize,
+) -> Vec<(String, String)> {
+    let Some(proxy_url) = proxy_url.map(str::trim).filter(|value| !value.is_empty()) else {
+        return Vec::new();
+    };
+    let mut vars: Vec<(String, String)> = [
+        "HTTPS_PROXY",
+        "https_proxy",
+        "HTTP_PROXY",
+        "http_proxy",
+        "ALL_PROXY",
+        "all_proxy",
+    ]
+    .iter()
+    .map(|name| (name.to_string(), proxy_url.to_string()))
+    .collect();
+    if let Some(no_proxy) = no_proxy.map(str::trim).filter(|value| !value.is_empty()) {
+        vars.push(("NO_PROXY".to_string(), no_proxy.to_string()));
+        vars.push(("no_proxy".to_string(), no_proxy.to_string()));
+    }
+    let index = inherited_config_count;
+    vars.push(("GIT_CONFIG_COUNT".to_string(), (index + 1).to_string()));
+    vars.push((format!("GIT_CONFIG_KEY_{index}"), "http.proxy".to_string()));
+    vars.push((format!("GIT_CONFIG_VALUE_{index}"), proxy_url.to_string()));
+    vars
+}
+
+/// Replaces the proxy environment used by later [`proxy_env`] calls.
+pub(crate) fn set_proxy(proxy_url: Option<&str>, no_proxy: Option<&str>) {
+    let inherited = std::env::var("GIT_CONFIG_COUNT")
+        .ok()
+        .and_then(|count| count.parse().ok())
+        .unwrap_or(0);
+    let vars = proxy_vars(proxy_url, no_proxy, inherited);
+    if let Ok(mut current) = PROXY_ENV.write() {
+        *current = vars;
+    }
+}
+
+/// The current proxy variables, for `Command::envs` on git and gh.
+pub(crate) fn proxy_env() -> Vec<(String, String)> {
+    PROXY_ENV
+        .read()
+        .map(|vars| vars.clone())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn proxy_vars_cover_curl_go_and_git_config() {
+        assert!(proxy_vars(None, Some("localhost"), 0).is_empty());
+        assert!(proxy_vars(Some("  "), None, 0).is_empty());
+
+        let vars = proxy_vars(Some("socks5://127.0.0.1:1080"), Some("localhost,.corp"), 2);
+        let get = |name: &str| {
+            vars.iter()
+                .find(|(key, _)| key == name)
+                .map(|(_, value)| value.as_str())
+        };
+        assert_eq!(get("HTTPS_PROXY"), Some("socks5://127.0.0.1:1080"));
+        assert_eq!(get("all_proxy"), Some("socks5://127.0.0.1:1080"));
+        assert_eq!(get("NO_PROXY"), Some("localhost,.corp"));
+        assert_eq!(get("GIT_CONFIG_COUNT"), Some("3"));
+        assert_eq!(get("GIT_CONFIG_KEY_2"), Some("http.proxy"));
+        assert_eq!(get("GIT_CONFIG_VALUE_2"), Some("socks5://127.0.0.1:1080"));
+    }
+}
@@ -0,0 +1,167 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+
+use serde_json::Value;
+use tokio::sync::{oneshot, Semaphore};
+
+/// In-flight requests of one connection that can still be cancelled, keyed by
+/// their JSON-encoded request `id`. Each registration gets a ticket so a
+/// finished request never unregisters a newer one that reused its id.
+#[derive(Clone, Default)]
+pub(crate) struct PendingRequests {
+    inner: Arc<Mutex<Pending>>,
+}
+
+#[derive(Default)]
+struct Pending {
+    next_ticket: u64,
+    requests: HashMap<String, (u64, oneshot::Sender<()>)>,
+}
+
+impl PendingRequests {
+    /// Registers request `id`. The receiver resolves when it is cancelled.
+    pub(crate) fn register(&self, id: &Value) -> (u64, oneshot::Receiver<()>) {
+        let (tx, rx) = oneshot::channel();
+        let Ok(mut pending) = self.inner.lock() else {
+            return (0, rx);
+        };
+        pending.next_ticket += 1;
+        let ticket = pending.next_ticket;
+        pending.requests.insert(id.to_string(), (ticket, tx));
+        (ticket, rx)
+    }
+
+    /// Drops the registration made with `ticket` once the request is done.
+    pub(crate) fn finish(&self, id: &Value, ticket: u64) {
+        if let Ok(mut pending) = self.inner.lock() {
+            let key = id.to_string();
+            if pending.requests.get(&key).map(|(current, _)| *current) == Some(ticket) {
+                pending.requests.remove(&key);
+            }
+        }
+    }
+
+    /// Signals request `id` to stop. Returns false when it is not in flight.
+    pub(crate) fn cancel(&self, id: &Value) -> bool {
+        let entry = match self.inner.lock() {
+            Ok(mut pending) => pending.requests.remove(&id.to_string()),
+            Err(_) => None,
+        };
+        entry.is_some_and(|(_, tx)| tx.send(()).is_ok())
+    }
+
+    /// Signals every request still in flight, returning how many there were.
+    pub(crate) fn cancel_all(&self) -> usize {
+        let requests = match self.inner.lock() {
+            Ok(mut pending) => std::mem::take(&mut pending.requests),
+            Err(_) => return 0,
+        };
+        requests
+            .into_values()
+            .filter(|(_, tx)| !tx.is_closed())
+            .map(|(_, tx)| tx.send(()))
+            .filter(Result::is_ok)
+            .count()
+    }
+}
+
+/// Runs `request` once one of the connection's `slots` is free. A request
+/// that is cancelled answers "cancelled" whether it was still waiting for a
+/// slot or already running, so the read loop never blocks on a full
+/// connection and can always reach `cancel` and `close`.
+pub(crate) async fn run_when_admitted(
+    slots: &Semaphore,
+    cancelled: Option<oneshot::Receiver<()>>,
+    request: impl Future<Output = Result<Value, String>>,
+) -> Result<Value, String> {
+    let run = async {
+        let Ok(_slot) = slots.acquire().await else {
+            return Err("connection closed".to_string());
+        };
+        request.await
+    };
+    match cancelled {
+        Some(cancelled) => tokio::select! {
+            result = run => result,
+            _ = cancelled => Err("cancelled".to_string()),
+        },
+        None => run.await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn cancel_signals_only_the_pending_request() {
+        let pending = PendingRequests::default();
+        let (_, mut first) = pending.register(&json!(1));
+        let (ticket, mut second) = pending.register(&json!("1"));
+
+        assert!(pending.cancel(&json!(1)));
+        assert_eq!(first.try_recv(), Ok(()));
+        assert!(!pending.cancel(&json!(1)));
+        assert!(second.try_recv().is_err());
+
+        pending.finish(&json!("1"), ticket);
+        assert!(!pending.cancel(&json!("1")));
+
+        let (stale, _old) = pending.register(&json!(7));
+        let (_, mut reused) = pending.register(&json!(7));
+        pending.finish(&json!(7), stale);
+        assert!(pending.cancel(&json!(7)));
+        assert_eq!(reused.try_recv(), Ok(()));
+    }
+
+    #[test]
+    fn cancel_all_signals_every_pending_request() {
+        let pending = PendingRequests::default();
+        let (_, mut first) = pending.register(&json!(1));
+        let (_, mut second) = pending.register(&json!(2));
+        let (_, finished) = pending.register(&json!(3));
+        drop(finished);
+
+        assert_eq!(pending.cancel_all(), 2);
+        assert_eq!((first.try_recv(), second.try_recv()), (Ok(()), Ok(())));
+        assert_eq!(pending.cancel_all(), 0);
+    }
+
+    #[test]
+    fn cancel_reaches_requests_when_every_slot_is_busy() {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .expect("runtime");
+        runtime.block_on(async {
+            let slots = Arc::new(Semaphore::new(16));
+            let pending = PendingRequests::default();
+            let mut requests = tokio::task::JoinSet::new();
+            for id in 0..17 {
+                let (_, cancelled) = pending.register(&json!(id));
+                let slots = Arc::clone(&slots);
+                requests.spawn(async move {
+                    let slow = std::future::pending();
+                    (id, run_when_admitted(&slots, Some(cancelled), slow).await)
+                });
+            }
+            tokio::task::yield_now().await;
+            assert_eq!(slots.available_permits(), 0);
+
+            // Cancelling a running request hands its slot to the waiting one.
+            assert!(pending.cancel(&json!(3)));
+            let (id, result) = requests.join_next().await.expect("task").expect("join");
+            assert_eq!((id, result), (3, Err("cancelled".to_string())));
+            tokio::task::yield_now().await;
+            assert_eq!(slots.available_permits(), 0);
+
+            assert!(pending.cancel(&json!(16)));
+            let (id, result) = requests.join_next().await.expect("task").expect("join");
+            assert_eq!((id, result), (16, Err("cancelled".to_string())));
+            assert_eq!(slots.available_permits(), 1);
+            requests.abort_all();
+        });
+    }
+}
@@ -0,0 +1,120 @@
+use std::sync::{Arc, OnceLock};
+
+use serde_json::{json, Value};
+
+/// JSON-RPC 2.0 error codes used by the daemon.
+pub(crate) const PARSE_ERROR: i64 = -32700;
+pub(crate) const INVALID_REQUEST: i64 = -32600;
+pub(crate) const METHOD_NOT_FOUND: i64 = -32601;
+pub(crate) const INVALID_PARAMS: i64 = -32602;
+pub(crate) const SERVER_ERROR: i64 = -32000;
+pub(crate) const UNAUTHORIZED: i64 = -32001;
+pub(crate) const REQUEST_CANCELLED: i64 = -32800;
+
+/// Wire format of one connection. `Legacy` is the original JSON-lines shape
+/// (`{id, result}` / `{id, error: {message}}`); `JsonRpc` adds the
+/// `"jsonrpc": "2.0"` envelope and numeric error codes.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) enum Framing {
+    #[default]
+    Legacy,
+    JsonRpc,
+}
+
+impl Framing {
+    /// A connection speaks JSON-RPC 2.0 when its first message says so.
+    pub(crate) fn detect(message: &Value) -> Self {
+        if message.get("jsonrpc").and_then(Value::as_str) == Some("2.0") {
+            Framing::JsonRpc
+        } else {
+            Framing::Legacy
+        }
+    }
+
+    pub(crate) fn result(self, id: &Value, result: Value) -> Value {
+        self.envelope(json!({ "id": id, "result": result }))
+    }
+
+    pub(crate) fn error(self, id: &Value, message: &str) -> Value {
+        let error = match self {
+            Framing::Legacy => json!({ "message": message }),
+            Framing::JsonRpc => json!({ "code": error_code(message), "message": message }),
+        };
+        self.envelope(json!({ "id": id, "error": error }))
+    }
+
+    /// Wraps a server → client notification (`{method, params}`).
+    pub(crate) fn notification(self, payload: Value) -> Value {
+        self.envelope(payload)
+    }
+
+    fn envelope(self, mut message: Value) -> Value {
+        if let (Framing::JsonRpc, Some(map)) = (self, message.as_object_mut()) {
+            map.insert("jsonrpc".to_string(), json!("2.0"));
+        }
+        message
+    }
+}
+
+/// Maps a handler error message to a JSON-RPC error code. Handlers report
+/// errors as plain strings, so this goes by their established wording.
+pub(crate) fn error_code(message: &str) -> i64 {
+    if message.starts_with("parse error") {
+        PARSE_ERROR
+    } else if message == "invalid request" {
+        INVALID_REQUEST
+    } else if message.starts_with("unknown method") {
+        METHOD_NOT_FOUND
+    } else if message.starts_with("missing ") || message.starts_with("invalid `") {
+        INVALID_PARAMS
+    } else if message == "unauthorized" || message == "invalid token" {
+        UNAUTHORIZED
+    } else if message == "cancelled" {
+        REQUEST_CANCELLED
+    } else {
+        SERVER_ERROR
+    }
+}
+
+/// Framing of a connection, fixed by its first message and shared with the
+/// tasks that write events to it.
+#[derive(Clone, Default)]
+pub(crate) struct ConnectionFraming(Arc<OnceLock<Framing>>);
+
+impl ConnectionFraming {
+    pub(crate) fn detect(&self, message: &Value) -> Framing {
+        *self.0.get_or_init(|| Framing::detect(message))
+    }
+
+    pub(crate) fn get(&self) -> Framing {
+        self.0.get().copied().unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn jsonrpc_framing_adds_envelope_and_codes() {
+        let framing = ConnectionFraming::default();
+        assert_eq!(framing.get(), Framing::Legacy);
+        let first = json!({ "jsonrpc": "2.0", "id": 1, "method": "ping" });
+        assert_eq!(framing.detect(&first), Framing::JsonRpc);
+        assert_eq!(framing.detect(&json!({ "id": 2 })), Framing::JsonRpc);
+
+        let id = json!(3);
+        let error = Framing::JsonRpc.error(&id, "unknown method: nope");
+        assert_eq!(error["jsonrpc"], "2.0");
+        assert_eq!(error["error"]["code"], METHOD_NOT_FOUND);
+        let legacy = Framing::Legacy.error(&id, "unknown method: nope");
+        assert_eq!(legacy, json!({ "id": 3, "error": { "message": "unknown method: nope" } }));
+        assert_eq!(Framing::JsonRpc.result(&id, json!(true))["jsonrpc"], "2.0");
+        let event = Framing::JsonRpc.notification(json!({ "method": "x", "params": {} }));
+        assert_eq!(event["jsonrpc"], "2.0");
+
+        assert_eq!(error_code("parse error: EOF while parsing"), PARSE_ERROR);
+        assert_eq!(error_code("missing `workspaceId`"), INVALID_PARAMS);
+        assert_eq!(error_code("workspace not found"), SERVER_ERROR);
+    }
+}
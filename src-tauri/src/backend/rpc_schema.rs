@@ -0,0 +1,534 @@
+use serde_json::{json, Map, Value};
+
+/// JSON type of a single RPC param.
+#[derive(Clone, Copy)]
+enum ParamType {
+    String,
+    Integer,
+    Boolean,
+    Object,
+    StringArray,
+    OneOf(&'static [&'static str]),
+    Any,
+}
+
+impl ParamType {
+    fn to_schema(self) -> Value {
+        match self {
+            ParamType::String => json!({ "type": "string" }),
+            ParamType::Integer => json!({ "type": "integer" }),
+            ParamType::Boolean => json!({ "type": "boolean" }),
+            ParamType::Object => json!({ "type": "object" }),
+            ParamType::StringArray => json!({ "type": "array", "items": { "type": "string" } }),
+            ParamType::OneOf(values) => json!({ "type": "string", "enum": values }),
+            ParamType::Any => json!({}),
+        }
+    }
+}
+
+struct Param {
+    name: &'static str,
+    kind: ParamType,
+    required: bool,
+}
+
+const fn req(name: &'static str, kind: ParamType) -> Param {
+    Param {
+        name,
+        kind,
+        required: true,
+    }
+}
+
+const fn opt(name: &'static str, kind: ParamType) -> Param {
+    Param {
+        name,
+        kind,
+        required: false,
+    }
+}
+
+struct Method {
+    name: &'static str,
+    params: &'static [Param],
+    /// Informal description of the result shape, using the TypeScript names
+    /// from `src/types.ts` where one exists.
+    result: &'static str,
+}
+
+use ParamType::{Any, Boolean, Integer, Object, OneOf, String as Str, StringArray};
+
+const WORKSPACE: &[Param] = &[req("workspaceId", Str)];
+const WORKSPACE_THREAD: &[Param] = &[req("workspaceId", Str), req("threadId", Str)];
+const ID: &[Param] = &[req("id", Str)];
+
+const SEND_USER_MESSAGE: &[Param] = &[
+    req("workspaceId", Str),
+    req("threadId", Str),
+    req("text", Str),
+    opt("model", Str),
+    opt("effort", Str),
+    opt("accessMode", Str),
+    opt("images", StringArray),
+    opt("collaborationMode", Any),
+    opt("cwd", Str),
+    opt("confirmFullAccess", Boolean),
+    opt("whenBusy", OneOf(&["reject", "queue"])),
+];
+
+/// `send_user_message` params with `template`/`params` in place of `text`.
+const SEND_TEMPLATED_MESSAGE: &[Param] = &[
+    req("workspaceId", Str),
+    req("threadId", Str),
+    req("template", Str),
+    opt("params", Object),
+    opt("model", Str),
+    opt("effort", Str),
+    opt("accessMode", Str),
+    opt("images", StringArray),
+    opt("collaborationMode", Any),
+    opt("cwd", Str),
+    opt("confirmFullAccess", Boolean),
+    opt("whenBusy", OneOf(&["reject", "queue"])),
+];
+
+/// Every method `handle_rpc_request` dispatches, kept in the same order as
+/// the match arms. Update this table alongside the handlers.
+const METHODS: &[Method] = &[
+    Method {
+        name: "ping",
+        params: &[],
+        result: "{ ok: true }",
+    },
+    Method {
+        name: "auth",
+        params: &[
+            opt("token", Str),
+            opt("clientName", Str),
+            opt("clientVersion", Str),
+            opt("platform", Str),
+            opt("eventDelivery", OneOf(&["broadcast", "lossless"])),
+        ],
+        result: "{ ok: true, client: { name, version, platform }, eventDelivery }",
+    },
+    Method {
+        name: "set_client_info",
+        params: &[
+            opt("clientName", Str),
+            opt("clientVersion", Str),
+            opt("platform", Str),
+            opt("eventDelivery", OneOf(&["broadcast", "lossless"])),
+        ],
+        result: "{ ok: true, client: { name, version, platform }, eventDelivery }",
+    },
+    Method {
+        name: "cancel",
+        params: &[req("requestId", Any)],
+        result: "{ cancelled }",
+    },
+    Method {
+        name: "close",
+        params: &[],
+        result: "{ ok: true, cancelled }",
+    },
+    Method {
+        name: "describe_rpc",
+        params: &[],
+        result: "{ methods: RpcMethodSchema[] }",
+    },
+    Method {
+        name: "list_workspaces",
+        params: &[],
+        result: "WorkspaceInfo[]",
+    },
+    Method {
+        name: "is_workspace_path_dir",
+        params: &[req("path", Str)],
+        result: "boolean",
+    },
+    Method {
+        name: "add_workspace",
+        params: &[
+            req("path", Str),
+            opt("codex_bin", Str),
+            opt("force", Boolean),
+        ],
+        result: "WorkspaceInfo",
+    },
+    Method {
+        name: "add_worktree",
+        params: &[req("parentId", Str), req("branch", Str), opt("baseRef", Str)],
+        result: "WorkspaceInfo",
+    },
+    Method {
+        name: "connect_workspace",
+        params: ID,
+        result: "{ ok: true }",
+    },
+    Method {
+        name: "disconnect_workspace",
+        params: ID,
+        result: "{ ok: true }",
+    },
+    Method {
+        name: "remove_workspace",
+        params: ID,
+        result: "{ ok: true }",
+    },
+    Method {
+        name: "remove_worktree",
+        params: ID,
+        result: "{ ok: true }",
+    },
+    Method {
+        name: "prune_worktrees",
+        params: &[req("parentId", Str)],
+        result: "{ pruned: string[] }",
+    },
+    Method {
+        name: "rename_worktree",
+        params: &[req("id", Str), req("branch", Str)],
+        result: "WorkspaceInfo",
+    },
+    Method {
+        name: "rename_worktree_upstream",
+        params: &[req("id", Str), req("oldBranch", Str), req("newBranch", Str)],
+        result: "{ ok: true }",
+    },
+    Method {
+        name: "update_workspace_settings",
+        params: &[req("id", Str), req("settings", Object)],
+        result: "WorkspaceInfo",
+    },
+    Method {
+        name: "update_workspace_codex_bin",
+        params: &[req("id", Str), opt("codex_bin", Str)],
+        result: "WorkspaceInfo",
+    },
+    Method {
+        name: "rename_workspace",
+        params: &[req("id", Str), req("name", Str)],
+        result: "WorkspaceInfo",
+    },
+    Method {
+        name: "relocate_workspace",
+        params: &[req("id", Str), req("path", Str)],
+        result: "WorkspaceInfo",
+    },
+    Method {
+        name: "list_workspace_files",
+        params: &[req("workspaceId", Str), opt("maxFiles", Integer)],
+        result: "{ files: string[], truncated }",
+    },
+    Method {
+        name: "get_diff_digest",
+        params: &[req("workspaceId", Str), opt("maxBytes", Integer)],
+        result: "DiffDigest",
+    },
+    Method {
+        name: "stream_git_diffs",
+        params: &[req("workspaceId", Str), opt("streamId", Str)],
+        result: "{ streamId, files, additions, deletions }",
+    },
+    Method {
+        name: "read_workspace_file",
+        params: &[
+            req("workspaceId", Str),
+            req("path", Str),
+            opt("startLine", Integer),
+            opt("endLine", Integer),
+        ],
+        result: "{ path, content, startLine, endLine, totalLines }",
+    },
+    Method {
+        name: "grep_workspace",
+        params: &[
+            req("workspaceId", Str),
+            req("pattern", Str),
+            opt("caseInsensitive", Boolean),
+            opt("globs", StringArray),
+            opt("maxMatches", Integer),
+            opt("maxMatchesPerFile", Integer),
+        ],
+        result: "{ matches: { path, line, column, text }[], truncated }",
+    },
+    Method {
+        name: "replace_in_files",
+        params: &[
+            req("workspaceId", Str),
+            req("pattern", Str),
+            req("replacement", Str),
+            opt("paths", StringArray),
+            opt("globs", StringArray),
+            opt("dryRun", Boolean),
+        ],
+        result: "{ dryRun, files: { path, status, replacements?, diff?, error? }[] }",
+    },
+    Method {
+        name: "list_workspace_tree",
+        params: &[
+            req("workspaceId", Str),
+            opt("path", Str),
+            opt("maxDepth", Integer),
+        ],
+        result: "{ name, path, type, children }[]",
+    },
+    Method {
+        name: "resolve_file_path",
+        params: &[req("workspaceId", Str), req("path", Str)],
+        result: "{ path, absolutePath }",
+    },
+    Method {
+        name: "refresh_remote_state",
+        params: WORKSPACE,
+        result: "{ branch, upstream, ahead, behind }",
+    },
+    Method {
+        name: "verify_git_repo",
+        params: WORKSPACE,
+        result: "{ ok, dangling: { type, oid }[], missing: { type, oid }[], errors: string[] }",
+    },
+    Method {
+        name: "list_git_worktrees",
+        params: WORKSPACE,
+        result: "{ worktrees: { path, head, branch, detached, bare, locked, lockedReason, \
+                 prunable, prunableReason, workspaceId }[] }",
+    },
+    Method {
+        name: "get_codex_project_config",
+        params: WORKSPACE,
+        result: "CodexProjectConfig",
+    },
+    Method {
+        name: "get_app_settings",
+        params: &[],
+        result: "AppSettings",
+    },
+    Method {
+        name: "update_app_settings",
+        params: &[req("settings", Object)],
+        result: "AppSettings",
+    },
+    Method {
+        name: "start_thread",
+        params: &[req("workspaceId", Str), opt("cwd", Str)],
+        result: "app-server thread/start response",
+    },
+    Method {
+        name: "resume_thread",
+        params: WORKSPACE_THREAD,
+        result: "app-server thread/resume response",
+    },
+    Method {
+        name: "resume_latest_thread",
+        params: WORKSPACE,
+        result: "app-server thread/resume or thread/start response plus resumed",
+    },
+    Method {
+        name: "list_threads",
+        params: &[
+            req("workspaceId", Str),
+            opt("cursor", Str),
+            opt("limit", Integer),
+        ],
+        result: "app-server thread/list response",
+    },
+    Method {
+        name: "workspace_health",
+        params: WORKSPACE,
+        result: "object",
+    },
+    Method {
+        name: "admin_list_clients",
+        params: &[],
+        result: "{ clients: object[] }",
+    },
+    Method {
+        name: "admin_method_stats",
+        params: &[],
+        result: "{ methods: { method, calls, errors, p50Ms, p95Ms, maxMs }[] }",
+    },
+    Method {
+        name: "admin_disconnect_client",
+        params: &[req("connectionId", Integer)],
+        result: "{ ok: true }",
+    },
+    Method {
+        name: "get_active_turns",
+        params: WORKSPACE,
+        result: "object",
+    },
+    Method {
+        name: "get_turn_message",
+        params: &[req("workspaceId", Str), req("threadId", Str)],
+        result: "{ message: { turnId, itemId, text, completed } | null }",
+    },
+    Method {
+        name: "archive_thread",
+        params: WORKSPACE_THREAD,
+        result: "app-server thread/archive response",
+    },
+    Method {
+        name: "send_user_message",
+        params: SEND_USER_MESSAGE,
+        result: "app-server turn/start response",
+    },
+    Method {
+        name: "send_templated_message",
+        params: SEND_TEMPLATED_MESSAGE,
+        result: "app-server turn/start response plus expandedText",
+    },
+    Method {
+        name: "turn_interrupt",
+        params: &[
+            req("workspaceId", Str),
+            req("threadId", Str),
+            req("turnId", Str),
+        ],
+        result: "app-server turn/interrupt response",
+    },
+    Method {
+        name: "start_review",
+        params: &[
+            req("workspaceId", Str),
+            req("threadId", Str),
+            req("target", Any),
+            opt("delivery", Str),
+        ],
+        result: "app-server review/start response",
+    },
+    Method {
+        name: "codex_raw",
+        params: &[
+            req("workspaceId", Str),
+            req("method", Str),
+            opt("params", Any),
+        ],
+        result: "raw app-server response",
+    },
+    Method {
+        name: "model_list",
+        params: WORKSPACE,
+        result: "app-server model/list response",
+    },
+    Method {
+        name: "collaboration_mode_list",
+        params: WORKSPACE,
+        result: "app-server collaborationMode/list response",
+    },
+    Method {
+        name: "account_rate_limits",
+        params: WORKSPACE,
+        result: "app-server account/rateLimits/read response",
+    },
+    Method {
+        name: "skills_list",
+        params: WORKSPACE,
+        result: "app-server skills/list response",
+    },
+    Method {
+        name: "respond_to_server_request",
+        params: &[
+            req("workspaceId", Str),
+            req("requestId", Integer),
+            req("result", Any),
+        ],
+        result: "{ ok: true }",
+    },
+    Method {
+        name: "remember_approval_rule",
+        params: &[req("workspaceId", Str), req("command", StringArray)],
+        result: "{ ok: true, rulesPath }",
+    },
+];
+
+fn params_schema(params: &[Param]) -> Value {
+    let mut properties = Map::new();
+    let mut required = Vec::new();
+    for param in params {
+        properties.insert(param.name.to_string(), param.kind.to_schema());
+        if param.required {
+            required.push(param.name);
+        }
+    }
+    json!({
+        "type": "object",
+        "properties": properties,
+        "required": required,
+    })
+}
+
+/// Describes every daemon RPC method: its params as a JSON Schema object and
+/// an informal result shape.
+pub(crate) fn describe_rpc() -> Value {
+    let methods: Vec<Value> = METHODS
+        .iter()
+        .map(|method| {
+            json!({
+                "method": method.name,
+                "params": params_schema(method.params),
+                "result": method.result,
+            })
+        })
+        .collect();
+    json!({ "methods": methods })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn methods_are_unique_and_required_params_listed() {
+        let mut names: Vec<&str> = METHODS.iter().map(|method| method.name).collect();
+        names.sort_unstable();
+        let total = names.len();
+        names.dedup();
+        assert_eq!(names.len(), total);
+
+        let schema = describe_rpc();
+        let send = schema["methods"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|method| method["method"] == "send_user_message")
+            .unwrap();
+        assert_eq!(
+            send["params"]["required"],
+            json!(["workspaceId", "threadId", "text"])
+        );
+        assert_eq!(
+            send["params"]["properties"]["whenBusy"]["enum"],
+            json!(["reject", "queue"])
+        );
+    }
+
+    /// Every described method is dispatched by the daemon, either by
+    /// `handle_rpc_request` or as a connection-level method, and vice versa.
+    #[test]
+    fn methods_match_the_daemon_dispatch() {
+        let source = include_str!("../bin/codex_monitor_daemon.rs");
+        let dispatch = source
+            .split("async fn handle_rpc_request(")
+            .nth(1)
+            .and_then(|rest| rest.split("\n}\n").next())
+            .expect("handle_rpc_request in the daemon");
+        let connection = source
+            .split("const CONNECTION_METHODS")
+            .nth(1)
+            .and_then(|rest| rest.split_once("= [")?.1.split_once(']'))
+            .map(|(list, _)| list)
+            .expect("CONNECTION_METHODS in the daemon");
+
+        let mut dispatched: Vec<&str> = dispatch
+            .lines()
+            .filter_map(|line| line.strip_prefix("        \"")?.split_once("\" =>"))
+            .map(|(name, _)| name)
+            .chain(connection.split(',').map(|name| name.trim().trim_matches('"')))
+            .collect();
+        dispatched.sort_unstable();
+        let mut described: Vec<&str> = METHODS.iter().map(|method| method.name).collect();
+        described.sort_unstable();
+        assert_eq!(described, dispatched);
+    }
+}
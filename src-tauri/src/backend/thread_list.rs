@@ -0,0 +1,74 @@
+use serde_json::{json, Value};
+
+const DEFAULT_THREAD_LIST_LIMIT: u32 = 50;
+const MAX_THREAD_LIST_LIMIT: u32 = 200;
+
+/// Page size for `list_threads`: the default when unset, clamped to 1..=200.
+pub(crate) fn effective_thread_list_limit(limit: Option<u32>) -> u32 {
+    limit
+        .unwrap_or(DEFAULT_THREAD_LIST_LIMIT)
+        .clamp(1, MAX_THREAD_LIST_LIMIT)
+}
+
+/// Reshapes a `thread/list` response into `{ result: { data, nextCursor, limit } }`
+/// so clients get the same pagination contract regardless of app-server version.
+pub(crate) fn normalize_thread_list_response(response: Value, limit: u32) -> Value {
+    if response.get("error").is_some() {
+        return response;
+    }
+    let result = response.get("result").unwrap_or(&response);
+    let data = result
+        .get("data")
+        .filter(|value| value.is_array())
+        .cloned()
+        .unwrap_or_else(|| json!([]));
+    let next_cursor = result
+        .get("nextCursor")
+        .or_else(|| result.get("next_cursor"))
+        .and_then(|value| value.as_str())
+        .map(|value| value.to_string());
+    json!({
+        "result": {
+            "data": data,
+            "nextCursor": next_cursor,
+            "limit": limit,
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn thread_list_limit_defaults_and_clamps() {
+        assert_eq!(effective_thread_list_limit(None), 50);
+        assert_eq!(effective_thread_list_limit(Some(0)), 1);
+        assert_eq!(effective_thread_list_limit(Some(500)), 200);
+        assert_eq!(effective_thread_list_limit(Some(25)), 25);
+    }
+
+    #[test]
+    fn thread_list_responses_share_one_shape() {
+        let wrapped = json!({ "result": { "data": [{ "id": "t1" }], "nextCursor": "c2" } });
+        assert_eq!(
+            normalize_thread_list_response(wrapped, 25),
+            json!({ "result": { "data": [{ "id": "t1" }], "nextCursor": "c2", "limit": 25 } })
+        );
+
+        let bare = json!({ "data": [], "next_cursor": "c3" });
+        assert_eq!(
+            normalize_thread_list_response(bare, 50)["result"]["nextCursor"],
+            "c3"
+        );
+
+        let missing = json!({ "result": { "data": null } });
+        assert_eq!(
+            normalize_thread_list_response(missing, 50),
+            json!({ "result": { "data": [], "nextCursor": null, "limit": 50 } })
+        );
+
+        let error = json!({ "error": { "message": "boom" } });
+        assert_eq!(normalize_thread_list_response(error.clone(), 50), error);
+    }
+}
@@ -0,0 +1,199 @@
+use std::collections::HashMap;
+
+use serde_json::{json, Value};
+
+/// Assistant text of the latest agent message in a thread's current turn.
+#[derive(Default)]
+struct TurnMessage {
+    turn_id: String,
+    item_id: String,
+    text: String,
+    completed: bool,
+}
+
+/// Reassembles `item/agentMessage/delta` events per thread, keyed by
+/// workspace and thread id. A finished turn keeps its message until the
+/// thread starts the next one, so late readers can still fetch it.
+#[derive(Default)]
+pub(crate) struct TurnMessages {
+    threads: HashMap<(String, String), TurnMessage>,
+}
+
+impl TurnMessages {
+    /// Feeds one app-server message. Returns the `turn-message-final` params
+    /// when it completes a turn that produced assistant text.
+    pub(crate) fn observe(&mut self, workspace_id: &str, message: &Value) -> Option<Value> {
+        let method = message.get("method").and_then(Value::as_str)?;
+        let params = message.get("params").unwrap_or(&Value::Null);
+        let turn = params.get("turn");
+        let lookup = |key: &str, snake: &str| {
+            [params, turn.unwrap_or(&Value::Null)]
+                .iter()
+                .find_map(|source| source.get(key).or_else(|| source.get(snake)))
+                .and_then(Value::as_str)
+                .map(str::to_string)
+        };
+        let thread_id = lookup("threadId", "thread_id").filter(|id| !id.is_empty())?;
+        let turn_id = turn
+            .and_then(|turn| turn.get("id"))
+            .and_then(Value::as_str)
+            .map(str::to_string)
+            .or_else(|| lookup("turnId", "turn_id"))
+            .unwrap_or_default();
+        let key = (workspace_id.to_string(), thread_id.clone());
+
+        match method {
+            "turn/started" => {
+                self.threads.insert(
+                    key,
+                    TurnMessage {
+                        turn_id,
+                        ..TurnMessage::default()
+                    },
+                );
+            }
+            "item/agentMessage/delta" => {
+                let item_id = lookup("itemId", "item_id").unwrap_or_default();
+                let delta = params.get("delta").and_then(Value::as_str).unwrap_or("");
+                let entry = self.threads.entry(key).or_default();
+                if entry.completed {
+                    *entry = TurnMessage::default();
+                }
+                // A later agent message in the same turn replaces the earlier one.
+                if entry.item_id != item_id {
+                    entry.item_id = item_id;
+                    entry.text.clear();
+                }
+                entry.text.push_str(delta);
+            }
+            "item/completed" => {
+                let item = params.get("item")?;
+                if item.get("type").and_then(Value::as_str) != Some("agentMessage") {
+                    return None;
+                }
+                let entry = self.threads.entry(key).or_default();
+                if entry.completed {
+                    *entry = TurnMessage::default();
+                }
+                // The completed item carries the full text, covering any
+                // deltas that were never seen.
+                if let Some(text) = item.get("text").and_then(Value::as_str) {
+                    entry.text = text.to_string();
+                }
+                if let Some(item_id) = item.get("id").and_then(Value::as_str) {
+                    entry.item_id = item_id.to_string();
+                }
+            }
+            "turn/completed" => {
+                let entry = self.threads.get_mut(&key)?;
+                if entry.completed {
+                    return None;
+                }
+                entry.completed = true;
+                if entry.turn_id.is_empty() {
+                    entry.turn_id = turn_id;
+                }
+                if entry.text.is_empty() {
+                    return None;
+                }
+                let mut params = entry_value(entry);
+                params["workspaceId"] = json!(workspace_id);
+                params["threadId"] = json!(thread_id);
+                return Some(params);
+            }
+            "error" => {
+                let will_retry = params
+                    .get("willRetry")
+                    .or_else(|| params.get("will_retry"))
+                    .and_then(Value::as_bool)
+                    .unwrap_or(false);
+                if !will_retry {
+                    self.threads.remove(&key);
+                }
+            }
+            _ => {}
+        }
+        None
+    }
+
+    /// The thread's current (or last completed) message, if any.
+    pub(crate) fn get(&self, workspace_id: &str, thread_id: &str) -> Option<Value> {
+        self.threads
+            .get(&(workspace_id.to_string(), thread_id.to_string()))
+            .map(entry_value)
+    }
+
+    pub(crate) fn clear(&mut self, workspace_id: &str) {
+        self.threads.retain(|(workspace, _), _| workspace != workspace_id);
+    }
+}
+
+fn entry_value(entry: &TurnMessage) -> Value {
+    json!({
+        "turnId": entry.turn_id,
+        "itemId": entry.item_id,
+        "text": entry.text,
+        "completed": entry.completed,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(method: &str, params: Value) -> Value {
+        json!({ "method": method, "params": params })
+    }
+
+    fn delta(item_id: &str, text: &str) -> Value {
+        event(
+            "item/agentMessage/delta",
+            json!({ "threadId": "t1", "itemId": item_id, "delta": text }),
+        )
+    }
+
+    #[test]
+    fn assembles_deltas_until_turn_completes() {
+        let mut messages = TurnMessages::default();
+        let turn = json!({ "turn": { "id": "turn-1", "threadId": "t1" } });
+        let started = event("turn/started", turn.clone());
+        assert!(messages.observe("ws", &started).is_none());
+        messages.observe("ws", &delta("a", "Thinking"));
+        messages.observe("ws", &delta("b", "Hello, "));
+        messages.observe("ws", &delta("b", "world"));
+
+        let current = messages.get("ws", "t1").expect("in progress");
+        assert_eq!(current["text"], "Hello, world");
+        assert_eq!(current["completed"], false);
+        assert!(messages.get("other", "t1").is_none());
+
+        let completed = event("turn/completed", turn);
+        let final_message = messages.observe("ws", &completed).expect("final message");
+        assert_eq!(final_message["text"], "Hello, world");
+        assert_eq!(final_message["turnId"], "turn-1");
+        assert_eq!(final_message["itemId"], "b");
+        assert_eq!(final_message["threadId"], "t1");
+        assert_eq!(final_message["workspaceId"], "ws");
+        assert!(messages.observe("ws", &completed).is_none());
+        assert_eq!(messages.get("ws", "t1").expect("kept")["completed"], true);
+    }
+
+    #[test]
+    fn completed_item_replaces_missed_deltas() {
+        let mut messages = TurnMessages::default();
+        messages.observe("ws", &delta("b", "partial"));
+        let item = event(
+            "item/completed",
+            json!({
+                "threadId": "t1",
+                "item": { "type": "agentMessage", "id": "b", "text": "full text" },
+            }),
+        );
+        messages.observe("ws", &item);
+        assert_eq!(messages.get("ws", "t1").expect("message")["text"], "full text");
+
+        let failed = event("error", json!({ "threadId": "t1", "willRetry": false }));
+        messages.observe("ws", &failed);
+        assert!(messages.get("ws", "t1").is_none());
+    }
+}
@@ -0,0 +1,171 @@
+use serde_json::{json, Value};
+
+use crate::types::AccessModePolicies;
+
+/// Approval policies `codex app-server` accepts.
+const APPROVAL_POLICIES: [&str; 4] = ["untrusted", "on-failure", "on-request", "never"];
+
+/// Sandbox and approval settings resolved from a turn's `access_mode`.
+pub(crate) struct TurnPolicy {
+    pub(crate) access_mode: String,
+    pub(crate) sandbox_policy: Value,
+    pub(crate) approval_policy: String,
+    /// Empty for read-only and for full access, where the sandbox does not
+    /// restrict writes to specific roots.
+    pub(crate) writable_roots: Vec<String>,
+    pub(crate) network_access: bool,
+}
+
+impl TurnPolicy {
+    /// Resolves the policy for `access_mode`, taking the approval policy from
+    /// `overrides` when one is configured and recognized.
+    pub(crate) fn for_access_mode(
+        access_mode: Option<&str>,
+        workspace_path: &str,
+        overrides: &AccessModePolicies,
+    ) -> Self {
+        let access_mode = access_mode.unwrap_or("current").to_string();
+        let mut policy = Self::builtin(access_mode, workspace_path);
+        let configured = match policy.access_mode.as_str() {
+            "full-access" => overrides.full_access.as_deref(),
+            "read-only" => overrides.read_only.as_deref(),
+            _ => overrides.workspace_write.as_deref(),
+        };
+        if let Some(approval) = configured.filter(|value| APPROVAL_POLICIES.contains(value)) {
+            policy.approval_policy = approval.to_string();
+        }
+        policy
+    }
+
+    fn builtin(access_mode: String, workspace_path: &str) -> Self {
+        match access_mode.as_str() {
+            "full-access" => Self {
+                access_mode,
+                sandbox_policy: json!({
+                    "type": "dangerFullAccess"
+                }),
+                approval_policy: "never".to_string(),
+                writable_roots: Vec::new(),
+                network_access: true,
+            },
+            "read-only" => Self {
+                access_mode,
+                sandbox_policy: json!({
+                    "type": "readOnly"
+                }),
+                approval_policy: "on-request".to_string(),
+                writable_roots: Vec::new(),
+                network_access: false,
+            },
+            _ => Self {
+                access_mode,
+                sandbox_policy: json!({
+                    "type": "workspaceWrite",
+                    "writableRoots": [workspace_path],
+                    "networkAccess": true
+                }),
+                approval_policy: "on-request".to_string(),
+                writable_roots: vec![workspace_path.to_string()],
+                network_access: true,
+            },
+        }
+    }
+
+    pub(crate) fn is_full_access(&self) -> bool {
+        self.access_mode == "full-access"
+    }
+
+    /// Full access disables the sandbox and all approvals, so it must be
+    /// confirmed per request unless the workspace explicitly opts in.
+    pub(crate) fn ensure_allowed(
+        &self,
+        confirm_full_access: bool,
+        workspace_allows_full_access: bool,
+    ) -> Result<(), String> {
+        if !self.is_full_access() || confirm_full_access || workspace_allows_full_access {
+            return Ok(());
+        }
+        Err(
+            "Full-access turns require confirmation: pass confirmFullAccess: true or enable allowFullAccess for this workspace."
+                .to_string(),
+        )
+    }
+
+    pub(crate) fn to_value(&self) -> Value {
+        json!({
+            "accessMode": self.access_mode,
+            "sandboxPolicy": self.sandbox_policy,
+            "approvalPolicy": self.approval_policy,
+            "writableRoots": self.writable_roots,
+            "networkAccess": self.network_access,
+        })
+    }
+
+    /// Adds `appliedPolicy` next to the app-server's turn response so clients
+    /// can show exactly what a turn was allowed to do.
+    pub(crate) fn attach_to_response(&self, response: Value) -> Value {
+        match response {
+            Value::Object(mut map) => {
+                map.insert("appliedPolicy".to_string(), self.to_value());
+                Value::Object(map)
+            }
+            other => json!({
+                "result": other,
+                "appliedPolicy": self.to_value(),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TurnPolicy;
+    use crate::types::AccessModePolicies;
+    use serde_json::json;
+
+    #[test]
+    fn current_mode_writes_only_to_workspace() {
+        let policy = TurnPolicy::for_access_mode(None, "/repo", &AccessModePolicies::default());
+        assert_eq!(policy.approval_policy, "on-request");
+        assert_eq!(policy.writable_roots, vec!["/repo".to_string()]);
+        assert_eq!(policy.sandbox_policy["type"], "workspaceWrite");
+    }
+
+    #[test]
+    fn configured_approval_policies_override_defaults() {
+        let overrides = AccessModePolicies {
+            read_only: Some("bogus".to_string()),
+            workspace_write: Some("never".to_string()),
+            full_access: Some("on-failure".to_string()),
+        };
+        let write = TurnPolicy::for_access_mode(Some("current"), "/repo", &overrides);
+        assert_eq!(write.approval_policy, "never");
+        let full = TurnPolicy::for_access_mode(Some("full-access"), "/repo", &overrides);
+        assert_eq!(full.approval_policy, "on-failure");
+        let read = TurnPolicy::for_access_mode(Some("read-only"), "/repo", &overrides);
+        assert_eq!(read.approval_policy, "on-request");
+    }
+
+    #[test]
+    fn full_access_requires_confirmation_or_opt_in() {
+        let defaults = AccessModePolicies::default();
+        let policy = TurnPolicy::for_access_mode(Some("full-access"), "/repo", &defaults);
+        assert!(policy.ensure_allowed(false, false).is_err());
+        assert!(policy.ensure_allowed(true, false).is_ok());
+        assert!(policy.ensure_allowed(false, true).is_ok());
+
+        let policy = TurnPolicy::for_access_mode(Some("read-only"), "/repo", &defaults);
+        assert!(policy.ensure_allowed(false, false).is_ok());
+    }
+
+    #[test]
+    fn applied_policy_is_attached_to_response() {
+        let defaults = AccessModePolicies::default();
+        let policy = TurnPolicy::for_access_mode(Some("full-access"), "/repo", &defaults);
+        let response = policy.attach_to_response(json!({ "id": 1, "result": {} }));
+        assert_eq!(response["appliedPolicy"]["approvalPolicy"], "never");
+        assert_eq!(response["appliedPolicy"]["sandboxPolicy"]["type"], "dangerFullAccess");
+        assert_eq!(response["appliedPolicy"]["networkAccess"], true);
+        assert_eq!(response["id"], 1);
+    }
+}
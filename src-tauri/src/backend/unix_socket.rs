@@ -0,0 +1,74 @@
+use std::os::unix::fs::{FileTypeExt, PermissionsExt};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::Path;
+
+/// Removes a socket file left behind by a daemon that is no longer running so
+/// `--socket` can bind again. Fails when another daemon still accepts
+/// connections on it, or when the path is not a socket at all.
+pub(crate) fn remove_stale_socket(path: &Path) -> Result<(), String> {
+    let metadata = match std::fs::symlink_metadata(path) {
+        Ok(metadata) => metadata,
+        Err(_) => return Ok(()),
+    };
+    if !metadata.file_type().is_socket() {
+        return Err(format!("{} exists and is not a socket", path.display()));
+    }
+    if UnixStream::connect(path).is_ok() {
+        return Err(format!(
+            "{} is in use by another running daemon",
+            path.display()
+        ));
+    }
+    std::fs::remove_file(path).map_err(|err| format!("failed to remove stale socket: {err}"))
+}
+
+/// Limits the socket to its owner, since connecting to it is the only access
+/// check when no `--token` is set.
+fn restrict_socket_permissions(path: &Path) -> Result<(), String> {
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))
+        .map_err(|err| format!("failed to set socket permissions: {err}"))
+}
+
+/// Binds a non-blocking listener on `path` that only its owner can connect
+/// to. The socket is created under a `0o077` umask, so there is no window
+/// between `bind` and `chmod` in which other local users could connect.
+pub(crate) fn bind_owner_only(path: &Path) -> Result<UnixListener, String> {
+    // SAFETY: `umask` only swaps the process file-mode creation mask.
+    let previous = unsafe { libc::umask(0o077) };
+    let bound = UnixListener::bind(path);
+    // SAFETY: as above, restoring the mask saved before binding.
+    unsafe { libc::umask(previous) };
+    let listener = bound.map_err(|err| format!("failed to bind {}: {err}", path.display()))?;
+    restrict_socket_permissions(path)?;
+    listener
+        .set_nonblocking(true)
+        .map_err(|err| format!("failed to configure {}: {err}", path.display()))?;
+    Ok(listener)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stale_sockets_are_removed_and_live_ones_kept() {
+        let dir = std::env::temp_dir().join(format!("codex-monitor-sock-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).expect("create dir");
+        let path = dir.join("daemon.sock");
+        assert!(remove_stale_socket(&path).is_ok());
+
+        let listener = bind_owner_only(&path).expect("bind");
+        let mode = std::fs::metadata(&path).expect("metadata").permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+        let err = remove_stale_socket(&path).expect_err("live socket");
+        assert!(err.contains("in use"), "{err}");
+
+        drop(listener);
+        remove_stale_socket(&path).expect("stale socket");
+        assert!(!path.exists());
+
+        std::fs::write(&path, "not a socket").expect("write file");
+        assert!(remove_stale_socket(&path).is_err());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,59 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+pub(crate) const DEFAULT_UPSTREAM_POLL_INTERVAL: Duration = Duration::from_secs(600);
+pub(crate) const MIN_UPSTREAM_POLL_INTERVAL: Duration = Duration::from_secs(60);
+
+pub(crate) fn upstream_poll_interval(seconds: Option<u64>) -> Duration {
+    seconds
+        .map(Duration::from_secs)
+        .unwrap_or(DEFAULT_UPSTREAM_POLL_INTERVAL)
+        .max(MIN_UPSTREAM_POLL_INTERVAL)
+}
+
+/// Parses `git rev-list --left-right --count HEAD...@{upstream}` output into
+/// `(ahead, behind)`.
+pub(crate) fn parse_ahead_behind(output: &str) -> Option<(usize, usize)> {
+    let mut parts = output.split_whitespace();
+    let ahead = parts.next()?.parse().ok()?;
+    let behind = parts.next()?.parse().ok()?;
+    Some((ahead, behind))
+}
+
+/// Remembers the last `behind` count per workspace so only increases (new
+/// upstream commits) are reported.
+#[derive(Default)]
+pub(crate) struct BehindTracker {
+    last_behind: HashMap<String, usize>,
+}
+
+impl BehindTracker {
+    /// Returns the previous count when `behind` grew since the last check.
+    /// The first check of a workspace only records the baseline.
+    pub(crate) fn observe(&mut self, workspace_id: &str, behind: usize) -> Option<usize> {
+        let previous = self.last_behind.insert(workspace_id.to_string(), behind)?;
+        (behind > previous).then_some(previous)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn behind_increases_are_reported_once() {
+        let mut tracker = BehindTracker::default();
+        assert_eq!(tracker.observe("ws", 2), None);
+        assert_eq!(tracker.observe("ws", 2), None);
+        assert_eq!(tracker.observe("ws", 5), Some(2));
+        assert_eq!(tracker.observe("ws", 0), None);
+        assert_eq!(tracker.observe("ws", 1), Some(0));
+    }
+
+    #[test]
+    fn parses_rev_list_counts() {
+        assert_eq!(parse_ahead_behind("3\t4"), Some((3, 4)));
+        assert_eq!(parse_ahead_behind(""), None);
+        assert_eq!(upstream_poll_interval(None), DEFAULT_UPSTREAM_POLL_INTERVAL);
+    }
+}
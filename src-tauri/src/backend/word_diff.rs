@@ -0,0 +1,128 @@
+/// Lines longer than this (in characters) are not word-diffed.
+pub(crate) const MAX_WORD_DIFF_LINE_CHARS: usize = 1000;
+
+/// A changed region as `[start, end)` character offsets into a line.
+pub(crate) type CharSpan = (usize, usize);
+
+#[derive(Clone, Copy, PartialEq)]
+enum TokenClass {
+    Word,
+    Space,
+    Punct,
+}
+
+fn classify(ch: char) -> TokenClass {
+    if ch.is_alphanumeric() || ch == '_' {
+        TokenClass::Word
+    } else if ch.is_whitespace() {
+        TokenClass::Space
+    } else {
+        TokenClass::Punct
+    }
+}
+
+/// Splits a line into words, whitespace runs and single punctuation
+/// characters, returning each token with its character span.
+fn tokenize(line: &str) -> Vec<(CharSpan, &str)> {
+    let mut tokens = Vec::new();
+    let mut start: Option<(usize, usize, TokenClass)> = None;
+    let mut char_index = 0;
+    for (byte_index, ch) in line.char_indices() {
+        let class = classify(ch);
+        if let Some((token_char, token_byte, token_class)) = start {
+            if class != token_class || class == TokenClass::Punct {
+                tokens.push(((token_char, char_index), &line[token_byte..byte_index]));
+                start = None;
+            }
+        }
+        if start.is_none() {
+            start = Some((char_index, byte_index, class));
+        }
+        char_index += 1;
+    }
+    if let Some((token_char, token_byte, _)) = start {
+        tokens.push(((token_char, char_index), &line[token_byte..]));
+    }
+    tokens
+}
+
+/// Marks tokens that are not part of the longest common token subsequence.
+fn changed_tokens(old: &[(CharSpan, &str)], new: &[(CharSpan, &str)]) -> (Vec<bool>, Vec<bool>) {
+    let (n, m) = (old.len(), new.len());
+    let mut lengths = vec![0u32; (n + 1) * (m + 1)];
+    let at = |i: usize, j: usize| i * (m + 1) + j;
+    for i in (0..n).rev() {
+        for j in (0..m).rev() {
+            lengths[at(i, j)] = if old[i].1 == new[j].1 {
+                lengths[at(i + 1, j + 1)] + 1
+            } else {
+                lengths[at(i + 1, j)].max(lengths[at(i, j + 1)])
+            };
+        }
+    }
+    let mut old_changed = vec![true; n];
+    let mut new_changed = vec![true; m];
+    let (mut i, mut j) = (0, 0);
+    while i < n && j < m {
+        if old[i].1 == new[j].1 {
+            old_changed[i] = false;
+            new_changed[j] = false;
+            i += 1;
+            j += 1;
+        } else if lengths[at(i + 1, j)] >= lengths[at(i, j + 1)] {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+    (old_changed, new_changed)
+}
+
+fn merge_spans(tokens: &[(CharSpan, &str)], changed: &[bool]) -> Vec<CharSpan> {
+    let mut spans: Vec<CharSpan> = Vec::new();
+    for ((span, _), changed) in tokens.iter().zip(changed) {
+        if !changed {
+            continue;
+        }
+        match spans.last_mut() {
+            Some(last) if last.1 == span.0 => last.1 = span.1,
+            _ => spans.push(*span),
+        }
+    }
+    spans
+}
+
+/// Character spans that differ between a removed line and the added line
+/// replacing it. Returns `None` when either line is too long to diff.
+pub(crate) fn word_diff(old: &str, new: &str) -> Option<(Vec<CharSpan>, Vec<CharSpan>)> {
+    if old.chars().count() > MAX_WORD_DIFF_LINE_CHARS
+        || new.chars().count() > MAX_WORD_DIFF_LINE_CHARS
+    {
+        return None;
+    }
+    let old_tokens = tokenize(old);
+    let new_tokens = tokenize(new);
+    let (old_changed, new_changed) = changed_tokens(&old_tokens, &new_tokens);
+    Some((
+        merge_spans(&old_tokens, &old_changed),
+        merge_spans(&new_tokens, &new_changed),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn highlights_only_changed_words() {
+        let (old, new) = word_diff("let total = price * 2;", "let total = cost * 3;").unwrap();
+        assert_eq!(old, vec![(12, 17), (20, 21)]);
+        assert_eq!(new, vec![(12, 16), (19, 20)]);
+
+        let (old, new) = word_diff("café au lait", "café noir").unwrap();
+        assert_eq!(old, vec![(5, 12)]);
+        assert_eq!(new, vec![(5, 9)]);
+
+        assert!(word_diff(&"x".repeat(MAX_WORD_DIFF_LINE_CHARS + 1), "x").is_none());
+    }
+}
@@ -0,0 +1,458 @@
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+
+use regex::Regex;
+use serde_json::{json, Value};
+
+use crate::types::WorkspaceFileList;
+
+pub(crate) const GREP_MAX_LINE_CHARS: usize = 400;
+/// Files listed per `list_workspace_files` call when `maxFiles` is not set.
+pub(crate) const DEFAULT_MAX_LISTED_FILES: usize = 50_000;
+/// Upper bound for `maxFiles`, whatever the client asks for.
+pub(crate) const MAX_LISTED_FILES: usize = 500_000;
+
+pub(crate) struct GrepMatch {
+    pub(crate) line: usize,
+    pub(crate) column: usize,
+    pub(crate) text: String,
+}
+
+/// Resolves a workspace-relative (or absolute) file path, rejecting anything
+/// that escapes the workspace root once symlinks are followed.
+pub(crate) fn resolve_workspace_file(workspace_path: &str, path: &str) -> Result<PathBuf, String> {
+    let path = path.trim();
+    if path.is_empty() {
+        return Err("File path is required.".to_string());
+    }
+    let root = PathBuf::from(workspace_path);
+    let candidate = if Path::new(path).is_absolute() {
+        PathBuf::from(path)
+    } else {
+        root.join(path)
+    };
+    let root = root.canonicalize().map_err(|err| err.to_string())?;
+    let candidate = candidate
+        .canonicalize()
+        .map_err(|_| format!("File not found: {path}"))?;
+    if !candidate.starts_with(&root) {
+        return Err(format!("File must be inside the workspace: {path}"));
+    }
+    if !candidate.is_file() {
+        return Err(format!("Not a file: {path}"));
+    }
+    Ok(candidate)
+}
+
+/// Reads lines `start_line..=end_line` (1-based) of a file while counting the
+/// total, without holding the rest of the file in memory.
+pub(crate) fn read_file_window(
+    path: &Path,
+    start_line: Option<usize>,
+    end_line: Option<usize>,
+) -> Result<Value, String> {
+    let start = start_line.unwrap_or(1);
+    if start == 0 {
+        return Err("startLine must be at least 1.".to_string());
+    }
+    if let Some(end) = end_line {
+        if end < start {
+            return Err("endLine must not be before startLine.".to_string());
+        }
+    }
+
+    let file = File::open(path).map_err(|err| err.to_string())?;
+    let mut reader = BufReader::new(file);
+    let mut buffer = Vec::new();
+    let mut content = String::new();
+    let mut total_lines = 0usize;
+    loop {
+        buffer.clear();
+        let read = reader
+            .read_until(b'\n', &mut buffer)
+            .map_err(|err| err.to_string())?;
+        if read == 0 {
+            break;
+        }
+        total_lines += 1;
+        let in_window =
+            total_lines >= start && end_line.is_none_or(|end| total_lines <= end);
+        if in_window {
+            content.push_str(&String::from_utf8_lossy(&buffer));
+        }
+    }
+
+    let last_line = end_line.unwrap_or(total_lines).min(total_lines);
+    Ok(json!({
+        "content": content,
+        "startLine": start,
+        "endLine": last_line.max(start.saturating_sub(1)),
+        "totalLines": total_lines,
+    }))
+}
+
+/// Returns up to `max_matches` regex matches in a file as 1-based line and
+/// character columns. Binary files (a NUL byte in the first block) are skipped.
+pub(crate) fn grep_file(path: &Path, regex: &Regex, max_matches: usize) -> Vec<GrepMatch> {
+    let Ok(file) = File::open(path) else {
+        return Vec::new();
+    };
+    let mut reader = BufReader::new(file);
+    if let Ok(head) = reader.fill_buf() {
+        if head.contains(&0) {
+            return Vec::new();
+        }
+    }
+    let mut matches = Vec::new();
+    let mut buffer = Vec::new();
+    let mut line_number = 0usize;
+    while matches.len() < max_matches {
+        buffer.clear();
+        match reader.read_until(b'\n', &mut buffer) {
+            Ok(0) | Err(_) => break,
+            Ok(_) => {}
+        }
+        line_number += 1;
+        let line = String::from_utf8_lossy(&buffer);
+        let line = line.trim_end_matches(['\n', '\r']);
+        for found in regex.find_iter(line) {
+            if matches.len() >= max_matches {
+                break;
+            }
+            matches.push(GrepMatch {
+                line: line_number,
+                column: line[..found.start()].chars().count() + 1,
+                text: line.chars().take(GREP_MAX_LINE_CHARS).collect(),
+            });
+        }
+    }
+    matches
+}
+
+fn is_binary(contents: &[u8]) -> bool {
+    contents.iter().take(8000).any(|byte| *byte == 0)
+}
+
+pub(crate) struct FileReplacement {
+    pub(crate) replacements: usize,
+    pub(crate) diff: String,
+}
+
+/// Applies `regex` -> `replacement` (with `$1`-style expansion) to one file.
+/// Returns `None` for binary, non-UTF-8 or unchanged files; the file is only
+/// written when `dry_run` is false.
+pub(crate) fn replace_in_file(
+    path: &Path,
+    display_path: &str,
+    regex: &Regex,
+    replacement: &str,
+    dry_run: bool,
+) -> Result<Option<FileReplacement>, String> {
+    let bytes = std::fs::read(path).map_err(|err| err.to_string())?;
+    if is_binary(&bytes) {
+        return Ok(None);
+    }
+    let Ok(before) = String::from_utf8(bytes) else {
+        return Ok(None);
+    };
+    let replacements = regex.find_iter(&before).count();
+    if replacements == 0 {
+        return Ok(None);
+    }
+    let after = regex.replace_all(&before, replacement);
+    if after == before {
+        return Ok(None);
+    }
+    let diff = single_hunk_diff(display_path, &before, &after);
+    if !dry_run {
+        std::fs::write(path, after.as_bytes()).map_err(|err| err.to_string())?;
+    }
+    Ok(Some(FileReplacement { replacements, diff }))
+}
+
+/// Renders a unified diff with one hunk spanning the changed lines, which is
+/// enough for previewing a replacement without a full diff algorithm.
+fn single_hunk_diff(path: &str, before: &str, after: &str) -> String {
+    let old: Vec<&str> = before.lines().collect();
+    let new: Vec<&str> = after.lines().collect();
+    let prefix = old
+        .iter()
+        .zip(new.iter())
+        .take_while(|(left, right)| left == right)
+        .count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(left, right)| left == right)
+        .count();
+    let removed = &old[prefix..old.len() - suffix];
+    let added = &new[prefix..new.len() - suffix];
+
+    let mut diff = format!(
+        "--- a/{path}\n+++ b/{path}\n@@ -{},{} +{},{} @@\n",
+        prefix + 1,
+        removed.len(),
+        prefix + 1,
+        added.len()
+    );
+    for line in removed {
+        diff.push('-');
+        diff.push_str(line);
+        diff.push('\n');
+    }
+    for line in added {
+        diff.push('+');
+        diff.push_str(line);
+        diff.push('\n');
+    }
+    diff
+}
+
+struct TreeNode {
+    name: String,
+    path: String,
+    is_dir: bool,
+    children: Vec<TreeNode>,
+}
+
+fn insert_tree_entry(nodes: &mut Vec<TreeNode>, parent: &str, components: &[&str], is_dir: bool) {
+    let Some((name, rest)) = components.split_first() else {
+        return;
+    };
+    let path = if parent.is_empty() {
+        name.to_string()
+    } else {
+        format!("{parent}/{name}")
+    };
+    let index = match nodes.iter().position(|node| node.name == *name) {
+        Some(index) => index,
+        None => {
+            nodes.push(TreeNode {
+                name: name.to_string(),
+                path: path.clone(),
+                is_dir: is_dir || !rest.is_empty(),
+                children: Vec::new(),
+            });
+            nodes.len() - 1
+        }
+    };
+    insert_tree_entry(&mut nodes[index].children, &path, rest, is_dir);
+}
+
+fn tree_node_to_value(node: &TreeNode, depth: usize, max_depth: Option<usize>) -> Value {
+    if !node.is_dir {
+        return json!({ "name": node.name, "path": node.path, "type": "file" });
+    }
+    // Directories at the depth limit are returned unexpanded (`children: null`)
+    // so the client can fetch them on demand.
+    let children = if max_depth.is_some_and(|max| depth >= max) {
+        Value::Null
+    } else {
+        Value::Array(
+            node.children
+                .iter()
+                .map(|child| tree_node_to_value(child, depth + 1, max_depth))
+                .collect(),
+        )
+    };
+    json!({
+        "name": node.name,
+        "path": node.path,
+        "type": "directory",
+        "children": children,
+    })
+}
+
+fn sort_tree(nodes: &mut [TreeNode]) {
+    nodes.sort_by(|left, right| {
+        right
+            .is_dir
+            .cmp(&left.is_dir)
+            .then_with(|| left.name.cmp(&right.name))
+    });
+    for node in nodes {
+        sort_tree(&mut node.children);
+    }
+}
+
+pub(crate) fn listed_files_limit(requested: Option<usize>) -> usize {
+    requested
+        .unwrap_or(DEFAULT_MAX_LISTED_FILES)
+        .clamp(1, MAX_LISTED_FILES)
+}
+
+/// Takes up to `max_files` paths and sorts them. `truncated` is set only when
+/// the walk had more files left, so a listing that exactly hits the cap is
+/// still reported as complete.
+pub(crate) fn collect_file_list(
+    paths: impl IntoIterator<Item = String>,
+    max_files: usize,
+) -> WorkspaceFileList {
+    let mut paths = paths.into_iter();
+    let mut files: Vec<String> = paths.by_ref().take(max_files).collect();
+    let truncated = paths.next().is_some();
+    files.sort();
+    WorkspaceFileList { files, truncated }
+}
+
+/// Nests `/`-separated `(path, is_dir)` entries into `{name, path, type,
+/// children}` nodes, directories first. `max_depth` counts levels below the
+/// listing root; deeper directories are left unexpanded.
+pub(crate) fn build_file_tree(entries: &[(String, bool)], max_depth: Option<usize>) -> Vec<Value> {
+    let mut roots: Vec<TreeNode> = Vec::new();
+    for (path, is_dir) in entries {
+        let components: Vec<&str> = path.split('/').filter(|part| !part.is_empty()).collect();
+        insert_tree_entry(&mut roots, "", &components, *is_dir);
+    }
+    sort_tree(&mut roots);
+    roots
+        .iter()
+        .map(|node| tree_node_to_value(node, 1, max_depth))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_workspace() -> PathBuf {
+        let root = std::env::temp_dir()
+            .join(format!("codex-monitor-files-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&root).expect("create workspace");
+        root
+    }
+
+    #[test]
+    fn read_file_window_returns_requested_lines() {
+        let root = temp_workspace();
+        let file = root.join("big.txt");
+        let lines: Vec<String> = (1..=100).map(|index| format!("line {index}")).collect();
+        std::fs::write(&file, lines.join("\n") + "\n").expect("write file");
+
+        let window = read_file_window(&file, Some(10), Some(12)).expect("window");
+        assert_eq!(window["content"], "line 10\nline 11\nline 12\n");
+        assert_eq!(window["totalLines"], 100);
+        assert_eq!(window["endLine"], 12);
+
+        let tail = read_file_window(&file, Some(99), Some(500)).expect("tail");
+        assert_eq!(tail["content"], "line 99\nline 100\n");
+        assert_eq!(tail["endLine"], 100);
+
+        assert!(read_file_window(&file, Some(0), None).is_err());
+        assert!(read_file_window(&file, Some(5), Some(4)).is_err());
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn grep_file_reports_lines_and_columns() {
+        let root = temp_workspace();
+        let file = root.join("src.rs");
+        std::fs::write(&file, "fn main() {}\nlet todo = 1; // TODO\nnone\n").expect("write file");
+
+        let regex = regex::RegexBuilder::new("todo")
+            .case_insensitive(true)
+            .build()
+            .expect("regex");
+        let matches = grep_file(&file, &regex, 10);
+        assert_eq!(matches.len(), 2);
+        assert_eq!((matches[0].line, matches[0].column), (2, 5));
+        assert_eq!((matches[1].line, matches[1].column), (2, 18));
+        assert_eq!(matches[0].text, "let todo = 1; // TODO");
+        assert_eq!(grep_file(&file, &regex, 1).len(), 1);
+
+        std::fs::write(root.join("bin.dat"), b"todo\0todo").expect("write binary");
+        assert!(grep_file(&root.join("bin.dat"), &regex, 10).is_empty());
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn replace_in_file_previews_without_writing() {
+        let root = temp_workspace();
+        let file = root.join("lib.rs");
+        std::fs::write(&file, "use old_name;\nfn keep() {}\nold_name();\n").expect("write file");
+        let regex = Regex::new(r"old_(\w+)").expect("regex");
+
+        let preview = replace_in_file(&file, "lib.rs", &regex, "new_$1", true)
+            .expect("preview")
+            .expect("changes");
+        assert_eq!(preview.replacements, 2);
+        assert!(preview.diff.contains("@@ -1,3 +1,3 @@"));
+        assert!(preview.diff.contains("-use old_name;\n"));
+        assert!(preview.diff.contains("+new_name();\n"));
+        assert!(std::fs::read_to_string(&file).unwrap().contains("old_name"));
+
+        replace_in_file(&file, "lib.rs", &regex, "new_$1", false).expect("apply");
+        assert_eq!(
+            std::fs::read_to_string(&file).unwrap(),
+            "use new_name;\nfn keep() {}\nnew_name();\n"
+        );
+        assert!(replace_in_file(&file, "lib.rs", &regex, "new_$1", false)
+            .expect("no-op")
+            .is_none());
+
+        std::fs::write(root.join("bin.dat"), b"old_name\0").expect("write binary");
+        assert!(replace_in_file(&root.join("bin.dat"), "bin.dat", &regex, "x", false)
+            .expect("binary")
+            .is_none());
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn build_file_tree_nests_and_limits_depth() {
+        let entries = vec![
+            ("src".to_string(), true),
+            ("src/main.rs".to_string(), false),
+            ("src/bin".to_string(), true),
+            ("src/bin/tool.rs".to_string(), false),
+            ("README.md".to_string(), false),
+        ];
+
+        let tree = build_file_tree(&entries, None);
+        assert_eq!(tree.len(), 2);
+        assert_eq!(tree[0]["name"], "src");
+        assert_eq!(tree[0]["type"], "directory");
+        assert_eq!(tree[0]["children"][0]["path"], "src/bin");
+        assert_eq!(tree[0]["children"][0]["children"][0]["path"], "src/bin/tool.rs");
+        assert_eq!(tree[0]["children"][1]["type"], "file");
+        assert_eq!(tree[1]["name"], "README.md");
+
+        let shallow = build_file_tree(&entries, Some(1));
+        assert!(shallow[0]["children"].is_null());
+    }
+
+    #[test]
+    fn collect_file_list_flags_truncation() {
+        let paths = || ["b.rs", "a.rs", "c.rs"].map(String::from);
+
+        let partial = collect_file_list(paths(), 2);
+        assert_eq!(partial.files, vec!["a.rs", "b.rs"]);
+        assert!(partial.truncated);
+
+        let exact = collect_file_list(paths(), 3);
+        assert_eq!(exact.files, vec!["a.rs", "b.rs", "c.rs"]);
+        assert!(!exact.truncated);
+
+        assert_eq!(listed_files_limit(None), DEFAULT_MAX_LISTED_FILES);
+        assert_eq!(listed_files_limit(Some(0)), 1);
+        assert_eq!(listed_files_limit(Some(usize::MAX)), MAX_LISTED_FILES);
+    }
+
+    #[test]
+    fn resolve_workspace_file_rejects_escapes() {
+        let root = temp_workspace();
+        std::fs::write(root.join("a.txt"), "a").expect("write file");
+        let root_str = root.to_string_lossy().to_string();
+
+        assert!(resolve_workspace_file(&root_str, "a.txt").is_ok());
+        assert!(resolve_workspace_file(&root_str, "../a.txt").is_err());
+        assert!(resolve_workspace_file(&root_str, "missing.txt").is_err());
+        assert!(resolve_workspace_file(&root_str, ".").is_err());
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+}
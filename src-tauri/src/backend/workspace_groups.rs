@@ -0,0 +1,156 @@
+use std::collections::HashMap;
+
+use crate::types::{WorkspaceGroup, WorkspaceInfo};
+
+/// Where a workspace's group sorts: defined groups by `sortOrder` then name,
+/// then groups known only by `groupName`, then ungrouped workspaces.
+#[derive(PartialEq, Eq, PartialOrd, Ord)]
+enum GroupRank {
+    Defined(u32, String, String),
+    Named(String),
+    Ungrouped,
+}
+
+/// Resolves each workspace's effective `groupId`/`groupName` in place.
+/// Worktrees without a group of their own take their parent's, and names of
+/// groups defined in `workspaceGroups` win over a stored `groupName`.
+pub(crate) fn resolve_workspace_groups(list: &mut [WorkspaceInfo], groups: &[WorkspaceGroup]) {
+    let parents: HashMap<String, (Option<String>, Option<String>)> = list
+        .iter()
+        .filter(|workspace| !workspace.kind.is_worktree())
+        .map(|workspace| {
+            let settings = &workspace.settings;
+            (
+                workspace.id.clone(),
+                (settings.group_id.clone(), settings.group_name.clone()),
+            )
+        })
+        .collect();
+    for workspace in list.iter_mut() {
+        let settings = &mut workspace.settings;
+        let own_group = settings.group_id.is_some() || settings.group_name.is_some();
+        if workspace.kind.is_worktree() && !own_group {
+            let parent = workspace.parent_id.as_ref().and_then(|id| parents.get(id));
+            if let Some((group_id, group_name)) = parent {
+                settings.group_id = group_id.clone();
+                settings.group_name = group_name.clone();
+            }
+        }
+        let defined = settings
+            .group_id
+            .as_ref()
+            .and_then(|id| groups.iter().find(|group| &group.id == id));
+        if let Some(group) = defined {
+            settings.group_name = Some(group.name.clone());
+        }
+    }
+}
+
+/// Orders workspaces by group (see [`GroupRank`]), then `sortOrder`, name
+/// and id.
+pub(crate) fn sort_workspaces(list: &mut [WorkspaceInfo], groups: &[WorkspaceGroup]) {
+    let rank = |workspace: &WorkspaceInfo| {
+        let settings = &workspace.settings;
+        let defined = settings
+            .group_id
+            .as_ref()
+            .and_then(|id| groups.iter().find(|group| &group.id == id));
+        if let Some(group) = defined {
+            return GroupRank::Defined(
+                group.sort_order.unwrap_or(u32::MAX),
+                group.name.clone(),
+                group.id.clone(),
+            );
+        }
+        match settings.group_name.as_deref().map(str::trim) {
+            Some(name) if !name.is_empty() => GroupRank::Named(name.to_string()),
+            _ => GroupRank::Ungrouped,
+        }
+    };
+    list.sort_by(|a, b| {
+        let a_order = a.settings.sort_order.unwrap_or(u32::MAX);
+        let b_order = b.settings.sort_order.unwrap_or(u32::MAX);
+        rank(a)
+            .cmp(&rank(b))
+            .then_with(|| a_order.cmp(&b_order))
+            .then_with(|| a.name.cmp(&b.name))
+            .then_with(|| a.id.cmp(&b.id))
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{WorkspaceKind, WorkspaceSettings};
+
+    fn workspace(id: &str, group_id: Option<&str>, sort_order: Option<u32>) -> WorkspaceInfo {
+        WorkspaceInfo {
+            id: id.to_string(),
+            name: id.to_string(),
+            path: format!("/tmp/{id}"),
+            connected: false,
+            codex_bin: None,
+            kind: WorkspaceKind::Main,
+            parent_id: None,
+            worktree: None,
+            settings: WorkspaceSettings {
+                group_id: group_id.map(str::to_string),
+                sort_order,
+                ..WorkspaceSettings::default()
+            },
+            git_root: None,
+            path_exists: true,
+            resolved_codex_bin: None,
+        }
+    }
+
+    fn group(id: &str, name: &str, sort_order: Option<u32>) -> WorkspaceGroup {
+        WorkspaceGroup {
+            id: id.to_string(),
+            name: name.to_string(),
+            sort_order,
+            copies_folder: None,
+        }
+    }
+
+    #[test]
+    fn sorts_by_group_then_order_and_name() {
+        let groups = vec![group("g-work", "Work", Some(2)), group("g-oss", "OSS", Some(1))];
+        let mut adhoc = workspace("adhoc", None, Some(0));
+        adhoc.settings.group_name = Some("Scratch".to_string());
+        let mut items = vec![
+            workspace("loose", None, Some(0)),
+            workspace("work-b", Some("g-work"), None),
+            workspace("work-a", Some("g-work"), Some(5)),
+            adhoc,
+            workspace("oss", Some("g-oss"), None),
+            workspace("stale", Some("g-deleted"), None),
+        ];
+
+        sort_workspaces(&mut items, &groups);
+
+        let ids: Vec<_> = items.iter().map(|item| item.id.as_str()).collect();
+        assert_eq!(ids, vec!["oss", "work-a", "work-b", "adhoc", "loose", "stale"]);
+    }
+
+    #[test]
+    fn worktrees_inherit_their_parents_group_unless_overridden() {
+        let groups = vec![group("g-work", "Work", None)];
+        let parent = workspace("parent", Some("g-work"), None);
+        let mut inherited = workspace("inherited", None, None);
+        inherited.kind = WorkspaceKind::Worktree;
+        inherited.parent_id = Some("parent".to_string());
+        let mut overridden = inherited.clone();
+        overridden.id = "overridden".to_string();
+        overridden.settings.group_name = Some("Experiments".to_string());
+        let mut items = vec![parent, inherited, overridden];
+
+        resolve_workspace_groups(&mut items, &groups);
+
+        assert_eq!(items[0].settings.group_name.as_deref(), Some("Work"));
+        assert_eq!(items[1].settings.group_id.as_deref(), Some("g-work"));
+        assert_eq!(items[1].settings.group_name.as_deref(), Some("Work"));
+        assert_eq!(items[2].settings.group_id, None);
+        assert_eq!(items[2].settings.group_name.as_deref(), Some("Experiments"));
+    }
+}
@@ -0,0 +1,257 @@
+use std::path::{Path, PathBuf};
+
+use crate::backend::app_server::resolve_codex_bin;
+use crate::types::{WorkspaceEntry, WorkspaceInfo};
+
+fn canonical_or_raw(path: &str) -> PathBuf {
+    let raw = PathBuf::from(path);
+    raw.canonicalize().unwrap_or(raw)
+}
+
+/// Rejects a new workspace path that equals, contains, or sits inside an
+/// existing workspace, since overlapping workspaces produce duplicated status
+/// and file listings. Callers skip this when the user explicitly forces it.
+pub(crate) fn check_nested_workspace<'a>(
+    path: &str,
+    existing: impl IntoIterator<Item = &'a WorkspaceEntry>,
+) -> Result<(), String> {
+    let candidate = canonical_or_raw(path);
+    for entry in existing {
+        let other = canonical_or_raw(&entry.path);
+        let relation = if candidate == other {
+            "is already the workspace"
+        } else if candidate.starts_with(&other) {
+            "is inside the workspace"
+        } else if other.starts_with(&candidate) {
+            "contains the workspace"
+        } else {
+            continue;
+        };
+        return Err(format!(
+            "nested_workspace: {} {relation} \"{}\" ({}). Pass force to add it anyway.",
+            display_path(&candidate),
+            entry.name,
+            display_path(&other)
+        ));
+    }
+    Ok(())
+}
+
+fn display_path(path: &Path) -> String {
+    path.to_string_lossy().to_string()
+}
+
+/// Finds the working-tree root of the repository containing `path`, which
+/// may be `path` itself or any of its parents.
+pub(crate) fn discover_git_root(path: &Path) -> Option<PathBuf> {
+    let repo = git2::Repository::discover(path).ok()?;
+    let root = repo.workdir()?;
+    Some(root.canonicalize().unwrap_or_else(|_| root.to_path_buf()))
+}
+
+/// Directory the git commands of `entry` run against: `settings.gitRoot`
+/// when set (relative to the workspace path), otherwise the repository
+/// discovered from the workspace path. A workspace outside any repository
+/// falls back to its own path so git reports the usual "not a repository".
+pub(crate) fn resolve_git_root(entry: &WorkspaceEntry) -> Result<PathBuf, String> {
+    let base = PathBuf::from(&entry.path);
+    let root = entry
+        .settings
+        .git_root
+        .as_ref()
+        .map(|value| value.trim())
+        .filter(|value| !value.is_empty());
+    let Some(root) = root else {
+        return Ok(discover_git_root(&base).unwrap_or(base));
+    };
+    let root_path = if Path::new(root).is_absolute() {
+        PathBuf::from(root)
+    } else {
+        base.join(root)
+    };
+    if root_path.is_dir() {
+        Ok(root_path)
+    } else {
+        Err(format!("Git root not found: {root}"))
+    }
+}
+
+/// The repository root reported to clients as `gitRoot`, or `None` when the
+/// workspace is not inside a repository.
+pub(crate) fn workspace_git_root(entry: &WorkspaceEntry) -> Option<String> {
+    let root = resolve_git_root(entry).ok()?;
+    root.join(".git")
+        .exists()
+        .then(|| root.to_string_lossy().to_string())
+}
+
+/// The `WorkspaceInfo` reported to clients for `entry`, with the derived
+/// `gitRoot`, `pathExists` and `resolvedCodexBin` filled in.
+pub(crate) fn workspace_info(
+    entry: &WorkspaceEntry,
+    connected: bool,
+    default_codex_bin: Option<&str>,
+) -> WorkspaceInfo {
+    WorkspaceInfo {
+        id: entry.id.clone(),
+        name: entry.name.clone(),
+        path: entry.path.clone(),
+        connected,
+        codex_bin: entry.codex_bin.clone(),
+        kind: entry.kind.clone(),
+        parent_id: entry.parent_id.clone(),
+        worktree: entry.worktree.clone(),
+        settings: entry.settings.clone(),
+        git_root: workspace_git_root(entry),
+        path_exists: Path::new(&entry.path).exists(),
+        resolved_codex_bin: resolve_codex_bin(entry.codex_bin.as_deref(), default_codex_bin),
+    }
+}
+
+/// Resolves an image path passed to `send_user_message`. Relative paths are
+/// joined onto the workspace's `attachmentsRoot` when one is set; the root
+/// must sit inside the workspace or one of `allowed_roots`, and the image
+/// must exist inside the root. Other paths are passed through unchanged.
+pub(crate) fn resolve_attachment_path(
+    entry: &WorkspaceEntry,
+    path: &str,
+    allowed_roots: &[String],
+) -> Result<String, String> {
+    let attachments_root = entry
+        .settings
+        .attachments_root
+        .as_deref()
+        .map(str::trim)
+        .filter(|value| !value.is_empty());
+    let Some(attachments_root) = attachments_root else {
+        return Ok(path.to_string());
+    };
+    if Path::new(path).is_absolute() {
+        return Ok(path.to_string());
+    }
+    let root = PathBuf::from(&entry.path)
+        .join(attachments_root)
+        .canonicalize()
+        .map_err(|_| format!("Attachments root not found: {attachments_root}"))?;
+    let permitted = std::iter::once(entry.path.as_str())
+        .chain(allowed_roots.iter().map(String::as_str))
+        .any(|allowed| root.starts_with(canonical_or_raw(allowed)));
+    if !permitted {
+        return Err(format!(
+            "Attachments root must be inside the workspace or an allowed directory: \
+             {attachments_root}"
+        ));
+    }
+    let image = root
+        .join(path)
+        .canonicalize()
+        .map_err(|_| format!("Attachment not found: {path}"))?;
+    if !image.starts_with(&root) || !image.is_file() {
+        return Err(format!("Attachment must be a file inside the attachments root: {path}"));
+    }
+    Ok(display_path(&image))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{WorkspaceKind, WorkspaceSettings};
+
+    fn entry(name: &str, path: &Path) -> WorkspaceEntry {
+        WorkspaceEntry {
+            id: name.to_string(),
+            name: name.to_string(),
+            path: path.to_string_lossy().to_string(),
+            codex_bin: None,
+            kind: WorkspaceKind::Main,
+            parent_id: None,
+            worktree: None,
+            settings: WorkspaceSettings::default(),
+        }
+    }
+
+    #[test]
+    fn nested_and_ancestor_paths_are_rejected() {
+        let root = std::env::temp_dir()
+            .join(format!("codex-monitor-nested-{}", uuid::Uuid::new_v4()));
+        let package = root.join("packages/app");
+        let sibling = std::env::temp_dir()
+            .join(format!("codex-monitor-sibling-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&package).expect("create package dir");
+        std::fs::create_dir_all(&sibling).expect("create sibling dir");
+        let monorepo = vec![entry("monorepo", &root)];
+
+        let err = check_nested_workspace(&package.to_string_lossy(), &monorepo).unwrap_err();
+        assert!(err.starts_with("nested_workspace:"), "{err}");
+        assert!(err.contains("is inside"), "{err}");
+
+        let packages = vec![entry("app", &package)];
+        let err = check_nested_workspace(&root.to_string_lossy(), &packages).unwrap_err();
+        assert!(err.contains("contains"), "{err}");
+
+        assert!(check_nested_workspace(&root.to_string_lossy(), &monorepo).is_err());
+        assert!(check_nested_workspace(&sibling.to_string_lossy(), &monorepo).is_ok());
+
+        let _ = std::fs::remove_dir_all(&root);
+        let _ = std::fs::remove_dir_all(&sibling);
+    }
+
+    #[test]
+    fn git_root_is_discovered_from_a_subdirectory() {
+        let root = std::env::temp_dir()
+            .join(format!("codex-monitor-discover-{}", uuid::Uuid::new_v4()));
+        let package = root.join("packages/app");
+        std::fs::create_dir_all(&package).expect("create package dir");
+        let outside = entry("outside", &package);
+        assert_eq!(resolve_git_root(&outside).expect("fallback"), package);
+        assert_eq!(workspace_git_root(&outside), None);
+
+        git2::Repository::init(&root).expect("init repo");
+        let canonical = root.canonicalize().expect("canonical root");
+        let app = entry("app", &package);
+        assert_eq!(resolve_git_root(&app).expect("discovered"), canonical);
+        assert_eq!(
+            workspace_git_root(&app),
+            Some(canonical.to_string_lossy().to_string())
+        );
+
+        let mut pinned = entry("pinned", &package);
+        pinned.settings.git_root = Some("missing".to_string());
+        assert!(resolve_git_root(&pinned).is_err());
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn relative_attachments_resolve_inside_the_attachments_root() {
+        let root = std::env::temp_dir()
+            .join(format!("codex-monitor-attachments-{}", uuid::Uuid::new_v4()));
+        let shots = root.join("workspace/screenshots");
+        let shared = root.join("shared");
+        std::fs::create_dir_all(&shots).expect("create screenshots dir");
+        std::fs::create_dir_all(&shared).expect("create shared dir");
+        std::fs::write(shots.join("a.png"), b"png").expect("write image");
+        std::fs::write(root.join("workspace/secret.png"), b"png").expect("write image");
+        std::fs::write(shared.join("b.png"), b"png").expect("write image");
+
+        let mut workspace = entry("ws", &root.join("workspace"));
+        assert_eq!(resolve_attachment_path(&workspace, "a.png", &[]).unwrap(), "a.png");
+
+        workspace.settings.attachments_root = Some("screenshots".to_string());
+        let resolved = resolve_attachment_path(&workspace, "a.png", &[]).expect("resolved");
+        assert_eq!(PathBuf::from(resolved), shots.join("a.png").canonicalize().unwrap());
+        assert!(resolve_attachment_path(&workspace, "../secret.png", &[]).is_err());
+        assert!(resolve_attachment_path(&workspace, "missing.png", &[]).is_err());
+        assert_eq!(
+            resolve_attachment_path(&workspace, "/tmp/x.png", &[]).unwrap(),
+            "/tmp/x.png"
+        );
+
+        workspace.settings.attachments_root = Some(shared.to_string_lossy().to_string());
+        assert!(resolve_attachment_path(&workspace, "b.png", &[]).is_err());
+        let allowed = vec![shared.to_string_lossy().to_string()];
+        assert!(resolve_attachment_path(&workspace, "b.png", &allowed).is_ok());
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+}
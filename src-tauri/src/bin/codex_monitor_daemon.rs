@@ -7,38 +7,109 @@ mod codex_home;
 mod codex_config;
 #[path = "../rules.rs"]
 mod rules;
+#[allow(dead_code)]
+#[path = "../git_utils.rs"]
+mod git_utils;
 #[path = "../storage.rs"]
 mod storage;
 #[allow(dead_code)]
 #[path = "../types.rs"]
 mod types;
+#[path = "../utils.rs"]
+mod utils;
 
 use serde_json::{json, Map, Value};
 use std::collections::HashMap;
 use std::env;
+use std::future::Future;
 use std::net::SocketAddr;
-use std::path::PathBuf;
-use std::sync::Arc;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex as StdMutex, PoisonError};
+use std::task::Poll;
+use std::time::{Duration, Instant};
 
+use globset::{Glob, GlobSet, GlobSetBuilder};
 use ignore::WalkBuilder;
-use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
-use tokio::net::{TcpListener, TcpStream};
+use regex::{Regex, RegexBuilder};
+use sha2::{Digest, Sha256};
+use tokio::io::{
+    AsyncBufRead, AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader, Lines,
+};
+use tokio::net::TcpListener;
 use tokio::process::Command;
-use tokio::sync::{broadcast, mpsc, Mutex};
+use tokio::sync::{broadcast, mpsc, oneshot, Mutex, Notify, Semaphore};
+use tokio_rustls::TlsAcceptor;
 use uuid::Uuid;
 
-use backend::app_server::{spawn_workspace_session, WorkspaceSession};
+use backend::active_turns::ActiveTurns;
+use backend::app_server::{
+    resolve_workspace_cwd, spawn_workspace_session, validate_extra_args, WorkspaceSession,
+};
+use backend::client_queues::{ClientQueues, CLIENT_QUEUE_CAPACITY};
+use backend::git_diffs::{diff_digest_for_path, for_each_file_diff};
+use backend::event_log::redact_prompts;
 use backend::events::{AppServerEvent, EventSink, TerminalOutput};
+use backend::issue_watch::{issue_poll_interval, SeenIssues};
+use backend::git_fsck::parse_fsck_output;
+use backend::git_worktrees::{parse_worktree_list, stale_worktree_ids};
+use backend::log_rotation::{LogRotation, DEFAULT_LOG_KEEP, DEFAULT_LOG_MAX_BYTES};
+use backend::message_template::{render_template, template_placeholders};
+use backend::method_stats::MethodStats;
+use backend::proxy_env::{proxy_env, set_proxy};
+use backend::request_cancel::{run_when_admitted, PendingRequests};
+use backend::rpc_framing::{ConnectionFraming, Framing};
+use backend::rpc_schema::describe_rpc;
+use backend::thread_list::{effective_thread_list_limit, normalize_thread_list_response};
+use backend::tls::load_tls_acceptor;
+use backend::turn_messages::TurnMessages;
+use backend::turn_policy::TurnPolicy;
+#[cfg(unix)]
+use backend::unix_socket;
+use backend::upstream_watch::{parse_ahead_behind, upstream_poll_interval, BehindTracker};
+use backend::workspace_files::{
+    build_file_tree, collect_file_list, grep_file, listed_files_limit, read_file_window,
+    replace_in_file, resolve_workspace_file,
+};
+use backend::workspace_groups::{resolve_workspace_groups, sort_workspaces};
+use backend::workspace_paths::{
+    check_nested_workspace, discover_git_root, resolve_attachment_path, resolve_git_root,
+    workspace_info,
+};
 use storage::{read_settings, read_workspaces, write_settings, write_workspaces};
 use types::{
-    AppSettings, WorkspaceEntry, WorkspaceInfo, WorkspaceKind, WorkspaceSettings, WorktreeInfo,
+    AppSettings, GitHubIssue, WorkspaceEntry, WorkspaceFileList, WorkspaceInfo, WorkspaceKind,
+    WorkspaceSettings, WorktreeInfo,
 };
+use utils::now_millis;
 
 const DEFAULT_LISTEN_ADDR: &str = "127.0.0.1:4732";
+const DEFAULT_SPAWN_CONCURRENCY: usize = 2;
+/// Requests of one connection that run at once; later ones wait for a slot.
+const MAX_IN_FLIGHT_REQUESTS: usize = 16;
+/// Methods handled by the connection itself rather than `handle_rpc_request`.
+const CONNECTION_METHODS: [&str; 4] = ["auth", "set_client_info", "cancel", "close"];
+/// How long a `close` waits for queued responses before dropping the socket.
+const CLOSE_FLUSH_TIMEOUT: Duration = Duration::from_secs(2);
+/// How long a TCP client gets to finish the TLS handshake when TLS is on.
+const TLS_HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+/// Event lines waiting on a connection's socket before forwarding pauses.
+const EVENT_WRITE_BUFFER: usize = 256;
+/// How often background watchers check whether a workspace is due for a poll.
+const WATCH_TICK: Duration = Duration::from_secs(30);
+/// How often connected sessions are checked for an exited app-server.
+const SESSION_WATCH_TICK: Duration = Duration::from_secs(2);
+const DEFAULT_GREP_MAX_MATCHES: usize = 200;
+const MAX_GREP_MATCHES: usize = 2000;
+const DEFAULT_GREP_MAX_MATCHES_PER_FILE: usize = 20;
 
 #[derive(Clone)]
 struct DaemonEventSink {
     tx: broadcast::Sender<DaemonEvent>,
+    /// Per-connection queues for clients that opted into lossless delivery.
+    lossless: ClientQueues<DaemonEvent>,
+    active_turns: ActiveTurns,
+    turn_messages: Arc<StdMutex<TurnMessages>>,
 }
 
 #[derive(Clone)]
@@ -46,22 +117,339 @@ enum DaemonEvent {
     AppServer(AppServerEvent),
     #[allow(dead_code)]
     TerminalOutput(TerminalOutput),
+    /// Daemon-originated notifications such as `github-issue-new`.
+    Notification {
+        method: &'static str,
+        params: Value,
+    },
+    /// A connection passed `auth`, or opened while auth is disabled.
+    ClientConnected {
+        connection_id: u64,
+        address: String,
+        timestamp: i64,
+        client: ClientInfo,
+    },
+    /// An authenticated connection ended.
+    ClientDisconnected {
+        connection_id: u64,
+        address: String,
+        timestamp: i64,
+    },
+}
+
+impl DaemonEventSink {
+    fn emit(&self, event: DaemonEvent) {
+        self.lossless.push(&event);
+        let _ = self.tx.send(event);
+    }
+
+    fn emit_notification(&self, method: &'static str, params: Value) {
+        self.emit(DaemonEvent::Notification { method, params });
+    }
+
+    fn emit_connection_changed(&self, workspace_id: &str, connected: bool, reason: &str) {
+        self.emit_notification(
+            "workspace-connection-changed",
+            json!({ "workspaceId": workspace_id, "connected": connected, "reason": reason }),
+        );
+    }
 }
 
 impl EventSink for DaemonEventSink {
     fn emit_app_server_event(&self, event: AppServerEvent) {
-        let _ = self.tx.send(DaemonEvent::AppServer(event));
+        self.active_turns.observe(&event);
+        let final_message = self
+            .turn_messages
+            .lock()
+            .ok()
+            .and_then(|mut messages| messages.observe(&event.workspace_id, &event.message));
+        self.emit(DaemonEvent::AppServer(event));
+        if let Some(params) = final_message {
+            self.emit_notification("turn-message-final", params);
+        }
     }
 
     fn emit_terminal_output(&self, event: TerminalOutput) {
-        let _ = self.tx.send(DaemonEvent::TerminalOutput(event));
+        self.emit(DaemonEvent::TerminalOutput(event));
     }
 }
 
 struct DaemonConfig {
     listen: SocketAddr,
+    socket: Option<PathBuf>,
+    tls: Option<TlsAcceptor>,
     token: Option<String>,
     data_dir: PathBuf,
+    audit_log: Option<PathBuf>,
+    max_concurrent_turns: Option<usize>,
+    auto_connect: bool,
+    spawn_concurrency: usize,
+    raw_methods: Option<Vec<String>>,
+    event_log: Option<PathBuf>,
+    log_prompts: bool,
+    log_rotation: LogRotation,
+}
+
+/// Append-only JSON-lines record of every turn and the permissions it ran
+/// with. Prompts are stored as a SHA-256 digest, never as text.
+struct AuditLog {
+    path: PathBuf,
+    rotation: LogRotation,
+    lock: Arc<StdMutex<()>>,
+}
+
+impl AuditLog {
+    fn new(path: PathBuf, rotation: LogRotation) -> Self {
+        if let Some(parent) = path.parent() {
+            let _ = std::fs::create_dir_all(parent);
+        }
+        Self {
+            path,
+            rotation,
+            lock: Arc::new(StdMutex::new(())),
+        }
+    }
+
+    async fn append(&self, record: Value) {
+        let mut line = match serde_json::to_string(&record) {
+            Ok(line) => line,
+            Err(err) => {
+                eprintln!("audit log: failed to serialize record: {err}");
+                return;
+            }
+        };
+        line.push('\n');
+        let (path, rotation, lock) = (self.path.clone(), self.rotation, Arc::clone(&self.lock));
+        // Written on a blocking task, which runs to completion even when the
+        // request that logged the turn is cancelled, so no line is cut short.
+        let write = tokio::task::spawn_blocking(move || {
+            let _guard = lock.lock().unwrap_or_else(PoisonError::into_inner);
+            if let Err(err) = rotation.rotate_if_needed(&path, line.len() as u64) {
+                eprintln!("audit log: failed to rotate {}: {err}", path.display());
+            }
+            let result = std::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&path)
+                .and_then(|mut file| std::io::Write::write_all(&mut file, line.as_bytes()));
+            if let Err(err) = result {
+                eprintln!("audit log: failed to write {}: {err}", path.display());
+            }
+        });
+        let _ = write.await;
+    }
+}
+
+/// JSON-lines record of every daemon event, rotated like the audit log.
+struct EventLog {
+    path: PathBuf,
+    rotation: LogRotation,
+    log_prompts: bool,
+}
+
+impl EventLog {
+    fn new(path: PathBuf, rotation: LogRotation, log_prompts: bool) -> Self {
+        if let Some(parent) = path.parent() {
+            let _ = std::fs::create_dir_all(parent);
+        }
+        Self {
+            path,
+            rotation,
+            log_prompts,
+        }
+    }
+
+    async fn append(&self, event: DaemonEvent) -> Result<(), String> {
+        let event = match event {
+            DaemonEvent::AppServer(mut payload) if !self.log_prompts => {
+                redact_prompts(&mut payload.message);
+                DaemonEvent::AppServer(payload)
+            }
+            event => event,
+        };
+        let mut record = event_payload(event);
+        if let Value::Object(map) = &mut record {
+            map.insert("ts".to_string(), json!(now_millis()));
+        }
+        let mut line = serde_json::to_string(&record).map_err(|err| err.to_string())?;
+        line.push('\n');
+
+        self.rotation.rotate_if_needed(&self.path, line.len() as u64)?;
+        let mut file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .await
+            .map_err(|err| err.to_string())?;
+        file.write_all(line.as_bytes())
+            .await
+            .map_err(|err| err.to_string())
+    }
+}
+
+/// Appends every broadcast event to the event log until the daemon exits.
+/// Events dropped because the writer fell behind are noted in the log.
+async fn run_event_log(log: EventLog, mut rx: broadcast::Receiver<DaemonEvent>) {
+    loop {
+        let event = match rx.recv().await {
+            Ok(event) => event,
+            Err(broadcast::error::RecvError::Lagged(skipped)) => DaemonEvent::Notification {
+                method: "event-log-lagged",
+                params: json!({ "skipped": skipped }),
+            },
+            Err(broadcast::error::RecvError::Closed) => break,
+        };
+        if let Err(err) = log.append(event).await {
+            eprintln!("event log: failed to write {}: {err}", log.path.display());
+        }
+    }
+}
+
+/// Identity a client reports during `auth` or via `set_client_info`.
+#[derive(Clone, Default)]
+struct ClientInfo {
+    name: Option<String>,
+    version: Option<String>,
+    platform: Option<String>,
+}
+
+impl ClientInfo {
+    /// Overwrites the fields present in `params` (`clientName`,
+    /// `clientVersion`, `platform`) and keeps the rest.
+    fn update_from_params(&mut self, params: &Value) {
+        if let Some(name) = parse_optional_string(params, "clientName") {
+            self.name = Some(name);
+        }
+        if let Some(version) = parse_optional_string(params, "clientVersion") {
+            self.version = Some(version);
+        }
+        if let Some(platform) = parse_optional_string(params, "platform") {
+            self.platform = Some(platform);
+        }
+    }
+
+    /// Version reported to `codex app-server` for sessions this client spawns.
+    fn app_server_version(&self) -> String {
+        self.version
+            .clone()
+            .unwrap_or_else(|| format!("daemon-{}", env!("CARGO_PKG_VERSION")))
+    }
+
+    fn to_value(&self) -> Value {
+        json!({
+            "clientName": self.name,
+            "clientVersion": self.version,
+            "platform": self.platform,
+        })
+    }
+}
+
+struct ConnectedClient {
+    addr: String,
+    disconnect: Arc<Notify>,
+    connected_at: i64,
+    authenticated_at: Option<i64>,
+    subscribed: bool,
+    lossless: bool,
+    info: ClientInfo,
+}
+
+/// Live client connections, keyed by a per-process connection id.
+#[derive(Default)]
+struct ClientRegistry {
+    next_id: AtomicU64,
+    clients: StdMutex<HashMap<u64, ConnectedClient>>,
+}
+
+impl ClientRegistry {
+    fn register(&self, addr: String, disconnect: Arc<Notify>) -> u64 {
+        let id = self.next_id.fetch_add(1, Ordering::SeqCst) + 1;
+        let client = ConnectedClient {
+            addr,
+            disconnect,
+            connected_at: now_millis(),
+            authenticated_at: None,
+            subscribed: false,
+            lossless: false,
+            info: ClientInfo::default(),
+        };
+        if let Ok(mut clients) = self.clients.lock() {
+            clients.insert(id, client);
+        }
+        id
+    }
+
+    fn update(&self, id: u64, apply: impl FnOnce(&mut ConnectedClient)) {
+        if let Ok(mut clients) = self.clients.lock() {
+            if let Some(client) = clients.get_mut(&id) {
+                apply(client);
+            }
+        }
+    }
+
+    /// Signals the connection's read loop to stop; the connection then tears
+    /// down its tasks and closes the socket.
+    fn disconnect(&self, id: u64) -> bool {
+        let Ok(clients) = self.clients.lock() else {
+            return false;
+        };
+        match clients.get(&id) {
+            Some(client) => {
+                client.disconnect.notify_one();
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn remove(&self, id: u64) {
+        if let Ok(mut clients) = self.clients.lock() {
+            clients.remove(&id);
+        }
+    }
+
+    fn list(&self) -> Vec<Value> {
+        let Ok(clients) = self.clients.lock() else {
+            return Vec::new();
+        };
+        let mut ids: Vec<&u64> = clients.keys().collect();
+        ids.sort();
+        ids.into_iter()
+            .map(|id| {
+                let client = &clients[id];
+                json!({
+                    "connectionId": id,
+                    "address": client.addr.clone(),
+                    "connectedAt": client.connected_at,
+                    "authenticatedAt": client.authenticated_at,
+                    "clientName": client.info.name,
+                    "clientVersion": client.info.version,
+                    "platform": client.info.platform,
+                    // Authenticated connections receive every event; there is
+                    // no per-connection filter yet.
+                    "eventFilter": if client.subscribed { json!("all") } else { Value::Null },
+                    "eventDelivery": event_delivery_name(client.lossless),
+                })
+            })
+            .collect()
+    }
+}
+
+struct AuditedTurn<'a> {
+    entry: &'a WorkspaceEntry,
+    client: &'a ClientInfo,
+    thread_id: &'a str,
+    cwd: &'a str,
+    policy: &'a TurnPolicy,
+    text: &'a str,
+    image_count: usize,
+}
+
+fn prompt_digest(text: &str) -> String {
+    Sha256::digest(text.as_bytes())
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
 }
 
 struct DaemonState {
@@ -72,6 +460,11 @@ struct DaemonState {
     settings_path: PathBuf,
     app_settings: Mutex<AppSettings>,
     event_sink: DaemonEventSink,
+    audit_log: Option<AuditLog>,
+    max_concurrent_turns: Option<usize>,
+    clients: ClientRegistry,
+    method_stats: StdMutex<MethodStats>,
+    raw_methods: Option<Vec<String>>,
 }
 
 impl DaemonState {
@@ -80,6 +473,7 @@ impl DaemonState {
         let settings_path = config.data_dir.join("settings.json");
         let workspaces = read_workspaces(&storage_path).unwrap_or_default();
         let app_settings = read_settings(&settings_path).unwrap_or_default();
+        set_proxy(app_settings.proxy_url.as_deref(), app_settings.no_proxy.as_deref());
         Self {
             data_dir: config.data_dir.clone(),
             workspaces: Mutex::new(workspaces),
@@ -88,10 +482,60 @@ impl DaemonState {
             settings_path,
             app_settings: Mutex::new(app_settings),
             event_sink,
+            audit_log: config
+                .audit_log
+                .clone()
+                .map(|path| AuditLog::new(path, config.log_rotation)),
+            max_concurrent_turns: config.max_concurrent_turns,
+            clients: ClientRegistry::default(),
+            method_stats: StdMutex::new(MethodStats::default()),
+            raw_methods: config.raw_methods.clone(),
         }
     }
 
-    async fn kill_session(&self, workspace_id: &str) {
+    async fn audit_turn(&self, turn: &AuditedTurn<'_>, outcome: Result<(), &str>) {
+        let AuditedTurn {
+            entry,
+            client,
+            thread_id,
+            cwd,
+            policy,
+            text,
+            image_count,
+        } = turn;
+        let Some(audit_log) = self.audit_log.as_ref() else {
+            return;
+        };
+        let (outcome, error) = match outcome {
+            Ok(()) => ("started", None),
+            Err(message) => ("failed", Some(message)),
+        };
+        audit_log
+            .append(json!({
+                "timestamp": now_millis(),
+                "workspaceId": entry.id,
+                "workspacePath": entry.path,
+                "client": client.to_value(),
+                "threadId": thread_id,
+                "cwd": cwd,
+                "accessMode": policy.access_mode,
+                "approvalPolicy": policy.approval_policy,
+                "sandbox": policy.sandbox_policy.get("type").cloned().unwrap_or(Value::Null),
+                "writableRoots": policy.writable_roots,
+                "networkAccess": policy.network_access,
+                "promptSha256": prompt_digest(text.trim()),
+                "imageCount": image_count,
+                "outcome": outcome,
+                "error": error,
+            }))
+            .await;
+    }
+
+    async fn kill_session(&self, workspace_id: &str, reason: &str) {
+        self.event_sink.active_turns.clear(workspace_id);
+        if let Ok(mut messages) = self.event_sink.turn_messages.lock() {
+            messages.clear(workspace_id);
+        }
         let session = {
             let mut sessions = self.sessions.lock().await;
             sessions.remove(workspace_id)
@@ -103,26 +547,32 @@ impl DaemonState {
 
         let mut child = session.child.lock().await;
         let _ = child.kill().await;
+        self.event_sink.emit_connection_changed(workspace_id, false, reason);
+    }
+
+    /// [`workspace_info`] against the current default codex binary.
+    async fn workspace_info(&self, entry: &WorkspaceEntry, connected: bool) -> WorkspaceInfo {
+        let default_codex_bin = self.app_settings.lock().await.codex_bin.clone();
+        workspace_info(entry, connected, default_codex_bin.as_deref())
     }
 
     async fn list_workspaces(&self) -> Vec<WorkspaceInfo> {
+        let (groups, default_codex_bin) = {
+            let settings = self.app_settings.lock().await;
+            (settings.workspace_groups.clone(), settings.codex_bin.clone())
+        };
         let workspaces = self.workspaces.lock().await;
         let sessions = self.sessions.lock().await;
         let mut result = Vec::new();
         for entry in workspaces.values() {
-            result.push(WorkspaceInfo {
-                id: entry.id.clone(),
-                name: entry.name.clone(),
-                path: entry.path.clone(),
-                connected: sessions.contains_key(&entry.id),
-                codex_bin: entry.codex_bin.clone(),
-                kind: entry.kind.clone(),
-                parent_id: entry.parent_id.clone(),
-                worktree: entry.worktree.clone(),
-                settings: entry.settings.clone(),
-            });
+            result.push(workspace_info(
+                entry,
+                sessions.contains_key(&entry.id),
+                default_codex_bin.as_deref(),
+            ));
         }
-        sort_workspaces(&mut result);
+        resolve_workspace_groups(&mut result, &groups);
+        sort_workspaces(&mut result, &groups);
         result
     }
 
@@ -134,11 +584,16 @@ impl DaemonState {
         &self,
         path: String,
         codex_bin: Option<String>,
+        force: bool,
         client_version: String,
     ) -> Result<WorkspaceInfo, String> {
         if !PathBuf::from(&path).is_dir() {
             return Err("Workspace path must be a folder.".to_string());
         }
+        if !force {
+            let workspaces = self.workspaces.lock().await;
+            check_nested_workspace(&path, workspaces.values())?;
+        }
 
         let name = PathBuf::from(&path)
             .file_name()
@@ -181,29 +636,23 @@ impl DaemonState {
 
         self.sessions.lock().await.insert(entry.id.clone(), session);
 
-        Ok(WorkspaceInfo {
-            id: entry.id,
-            name: entry.name,
-            path: entry.path,
-            connected: true,
-            codex_bin: entry.codex_bin,
-            kind: entry.kind,
-            parent_id: entry.parent_id,
-            worktree: entry.worktree,
-            settings: entry.settings,
-        })
+        Ok(self.workspace_info(&entry, true).await)
     }
 
     async fn add_worktree(
         &self,
         parent_id: String,
         branch: String,
+        base_ref: Option<String>,
         client_version: String,
     ) -> Result<WorkspaceInfo, String> {
         let branch = branch.trim().to_string();
         if branch.trim().is_empty() {
             return Err("Branch name is required.".to_string());
         }
+        let base_ref = base_ref
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty());
 
         let parent_entry = {
             let workspaces = self.workspaces.lock().await;
@@ -217,6 +666,19 @@ impl DaemonState {
             return Err("Cannot create a worktree from another worktree.".to_string());
         }
 
+        let repo_path = PathBuf::from(&parent_entry.path);
+        let branch_exists = git_branch_exists(&repo_path, &branch).await?;
+        if let Some(base_ref) = base_ref.as_deref() {
+            if !git_commit_exists(&repo_path, base_ref).await? {
+                return Err(format!("Unknown base ref: {base_ref}"));
+            }
+            if branch_exists {
+                return Err(format!(
+                    "Branch {branch} already exists; omit baseRef to check it out."
+                ));
+            }
+        }
+
         let worktree_root = self.data_dir.join("worktrees").join(&parent_entry.id);
         std::fs::create_dir_all(&worktree_root)
             .map_err(|e| format!("Failed to create worktree directory: {e}"))?;
@@ -225,9 +687,13 @@ impl DaemonState {
         let worktree_path = unique_worktree_path(&worktree_root, &safe_name)?;
         let worktree_path_string = worktree_path.to_string_lossy().to_string();
 
-        let repo_path = PathBuf::from(&parent_entry.path);
-        let branch_exists = git_branch_exists(&repo_path, &branch).await?;
-        if branch_exists {
+        if let Some(base_ref) = base_ref.as_deref() {
+            run_git_command(
+                &repo_path,
+                &["worktree", "add", "-b", &branch, &worktree_path_string, base_ref],
+            )
+            .await?;
+        } else if branch_exists {
             run_git_command(
                 &repo_path,
                 &["worktree", "add", &worktree_path_string, &branch],
@@ -236,7 +702,15 @@ impl DaemonState {
         } else if let Some(remote_ref) = git_find_remote_tracking_branch(&repo_path, &branch).await? {
             run_git_command(
                 &repo_path,
-                &["worktree", "add", "-b", &branch, &worktree_path_string, &remote_ref],
+                &[
+                    "worktree",
+                    "add",
+                    "--track",
+                    "-b",
+                    &branch,
+                    &worktree_path_string,
+                    &remote_ref,
+                ],
             )
             .await?;
         } else {
@@ -284,17 +758,7 @@ impl DaemonState {
 
         self.sessions.lock().await.insert(entry.id.clone(), session);
 
-        Ok(WorkspaceInfo {
-            id: entry.id,
-            name: entry.name,
-            path: entry.path,
-            connected: true,
-            codex_bin: entry.codex_bin,
-            kind: entry.kind,
-            parent_id: entry.parent_id,
-            worktree: entry.worktree,
-            settings: entry.settings,
-        })
+        Ok(self.workspace_info(&entry, true).await)
     }
 
     async fn remove_workspace(&self, id: String) -> Result<(), String> {
@@ -340,7 +804,7 @@ impl DaemonState {
                 }
             }
 
-            self.kill_session(&child.id).await;
+            self.kill_session(&child.id, "removed").await;
             removed_child_ids.push(child.id.clone());
         }
 
@@ -348,7 +812,7 @@ impl DaemonState {
 
         let mut ids_to_remove = removed_child_ids;
         if failures.is_empty() {
-            self.kill_session(&id).await;
+            self.kill_session(&id, "removed").await;
             ids_to_remove.push(id.clone());
         }
 
@@ -412,7 +876,7 @@ impl DaemonState {
         }
         let _ = run_git_command(&parent_path, &["worktree", "prune", "--expire", "now"]).await;
 
-        self.kill_session(&entry.id).await;
+        self.kill_session(&entry.id, "removed").await;
 
         let list = {
             let mut workspaces = self.workspaces.lock().await;
@@ -424,6 +888,48 @@ impl DaemonState {
         Ok(())
     }
 
+    /// Drops worktree entries of `parent_id` whose directory was deleted or
+    /// that git no longer tracks, and returns their ids.
+    async fn prune_worktrees(&self, parent_id: &str) -> Result<Value, String> {
+        let (parent, children) = {
+            let workspaces = self.workspaces.lock().await;
+            let parent = workspaces.get(parent_id).cloned().ok_or("workspace not found")?;
+            let children: Vec<(String, String)> = workspaces
+                .values()
+                .filter(|entry| {
+                    entry.kind.is_worktree() && entry.parent_id.as_deref() == Some(parent_id)
+                })
+                .map(|entry| (entry.id.clone(), entry.path.clone()))
+                .collect();
+            (parent, children)
+        };
+        if parent.kind.is_worktree() {
+            return Err("Prune worktrees from the parent workspace.".to_string());
+        }
+
+        let parent_path = PathBuf::from(&parent.path);
+        let output = run_git_command(&parent_path, &["worktree", "list", "--porcelain"]).await?;
+        let pruned = stale_worktree_ids(&children, &parse_worktree_list(&output));
+        if pruned.is_empty() {
+            return Ok(json!({ "pruned": pruned }));
+        }
+        let _ = run_git_command(&parent_path, &["worktree", "prune", "--expire", "now"]).await;
+
+        for id in &pruned {
+            self.kill_session(id, "removed").await;
+        }
+        let list = {
+            let mut workspaces = self.workspaces.lock().await;
+            for id in &pruned {
+                workspaces.remove(id);
+            }
+            workspaces.values().cloned().collect::<Vec<_>>()
+        };
+        write_workspaces(&self.storage_path, &list)?;
+
+        Ok(json!({ "pruned": pruned }))
+    }
+
     async fn rename_worktree(
         &self,
         id: String,
@@ -523,7 +1029,7 @@ impl DaemonState {
 
         let was_connected = self.sessions.lock().await.contains_key(&entry_snapshot.id);
         if was_connected {
-            self.kill_session(&entry_snapshot.id).await;
+            self.kill_session(&entry_snapshot.id, "restart").await;
             let default_bin = {
                 let settings = self.app_settings.lock().await;
                 settings.codex_bin.clone()
@@ -544,6 +1050,8 @@ impl DaemonState {
                         .lock()
                         .await
                         .insert(entry_snapshot.id.clone(), session);
+                    self.event_sink
+                        .emit_connection_changed(&entry_snapshot.id, true, "restart");
                 }
                 Err(error) => {
                     eprintln!(
@@ -555,17 +1063,7 @@ impl DaemonState {
         }
 
         let connected = self.sessions.lock().await.contains_key(&entry_snapshot.id);
-        Ok(WorkspaceInfo {
-            id: entry_snapshot.id,
-            name: entry_snapshot.name,
-            path: entry_snapshot.path,
-            connected,
-            codex_bin: entry_snapshot.codex_bin,
-            kind: entry_snapshot.kind,
-            parent_id: entry_snapshot.parent_id,
-            worktree: entry_snapshot.worktree,
-            settings: entry_snapshot.settings,
-        })
+        Ok(self.workspace_info(&entry_snapshot, connected).await)
     }
 
     async fn rename_worktree_upstream(
@@ -656,6 +1154,7 @@ impl DaemonState {
         id: String,
         settings: WorkspaceSettings,
     ) -> Result<WorkspaceInfo, String> {
+        validate_extra_args(&settings.extra_args)?;
         let (entry_snapshot, list) = {
             let mut workspaces = self.workspaces.lock().await;
             let entry_snapshot = match workspaces.get_mut(&id) {
@@ -671,17 +1170,7 @@ impl DaemonState {
         write_workspaces(&self.storage_path, &list)?;
 
         let connected = self.sessions.lock().await.contains_key(&id);
-        Ok(WorkspaceInfo {
-            id: entry_snapshot.id,
-            name: entry_snapshot.name,
-            path: entry_snapshot.path,
-            connected,
-            codex_bin: entry_snapshot.codex_bin,
-            kind: entry_snapshot.kind,
-            parent_id: entry_snapshot.parent_id,
-            worktree: entry_snapshot.worktree,
-            settings: entry_snapshot.settings,
-        })
+        Ok(self.workspace_info(&entry_snapshot, connected).await)
     }
 
     async fn update_workspace_codex_bin(
@@ -704,17 +1193,65 @@ impl DaemonState {
         write_workspaces(&self.storage_path, &list)?;
 
         let connected = self.sessions.lock().await.contains_key(&id);
-        Ok(WorkspaceInfo {
-            id: entry_snapshot.id,
-            name: entry_snapshot.name,
-            path: entry_snapshot.path,
-            connected,
-            codex_bin: entry_snapshot.codex_bin,
-            kind: entry_snapshot.kind,
-            parent_id: entry_snapshot.parent_id,
-            worktree: entry_snapshot.worktree,
-            settings: entry_snapshot.settings,
-        })
+        Ok(self.workspace_info(&entry_snapshot, connected).await)
+    }
+
+    async fn rename_workspace(&self, id: String, name: String) -> Result<WorkspaceInfo, String> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err("Workspace name is required.".to_string());
+        }
+        let (entry_snapshot, list) = {
+            let mut workspaces = self.workspaces.lock().await;
+            let entry_snapshot = match workspaces.get_mut(&id) {
+                Some(entry) => {
+                    entry.name = name.to_string();
+                    entry.clone()
+                }
+                None => return Err("workspace not found".to_string()),
+            };
+            let list: Vec<_> = workspaces.values().cloned().collect();
+            (entry_snapshot, list)
+        };
+        write_workspaces(&self.storage_path, &list)?;
+
+        let connected = self.sessions.lock().await.contains_key(&id);
+        Ok(self.workspace_info(&entry_snapshot, connected).await)
+    }
+
+    /// Points a moved workspace at its new folder, keeping its id and settings.
+    /// The running session is stopped so the next connect uses the new cwd.
+    async fn relocate_workspace(&self, id: String, path: String) -> Result<WorkspaceInfo, String> {
+        let path = path.trim().to_string();
+        if !PathBuf::from(&path).is_dir() {
+            return Err("Workspace path must be a folder.".to_string());
+        }
+        let kind = {
+            let workspaces = self.workspaces.lock().await;
+            let entry = workspaces.get(&id).ok_or("workspace not found")?;
+            check_nested_workspace(&path, workspaces.values().filter(|other| other.id != id))?;
+            entry.kind.clone()
+        };
+        if !kind.is_worktree() && discover_git_root(Path::new(&path)).is_none() {
+            return Err(format!("Not a git repository: {path}"));
+        }
+
+        let (entry_snapshot, list) = {
+            let mut workspaces = self.workspaces.lock().await;
+            let entry_snapshot = match workspaces.get_mut(&id) {
+                Some(entry) => {
+                    entry.path = path;
+                    entry.clone()
+                }
+                None => return Err("workspace not found".to_string()),
+            };
+            let list: Vec<_> = workspaces.values().cloned().collect();
+            (entry_snapshot, list)
+        };
+        write_workspaces(&self.storage_path, &list)?;
+        self.kill_session(&id, "relocated").await;
+
+        Ok(self.workspace_info(&entry_snapshot, false).await)
     }
 
     async fn connect_workspace(&self, id: String, client_version: String) -> Result<(), String> {
@@ -732,6 +1269,9 @@ impl DaemonState {
                 .cloned()
                 .ok_or("workspace not found")?
         };
+        if !Path::new(&entry.path).exists() {
+            return Err(format!("workspace path no longer exists: {}", entry.path));
+        }
 
         let default_bin = {
             let settings = self.app_settings.lock().await;
@@ -758,7 +1298,17 @@ impl DaemonState {
         )
         .await?;
 
-        self.sessions.lock().await.insert(id, session);
+        self.sessions.lock().await.insert(id.clone(), session);
+        self.event_sink.emit_connection_changed(&id, true, "connected");
+        Ok(())
+    }
+
+    /// Stops the workspace's app-server session but keeps the workspace.
+    async fn disconnect_workspace(&self, id: String) -> Result<(), String> {
+        if !self.workspaces.lock().await.contains_key(&id) {
+            return Err("workspace not found".to_string());
+        }
+        self.kill_session(&id, "disconnected").await;
         Ok(())
     }
 
@@ -767,6 +1317,7 @@ impl DaemonState {
         let _ = codex_config::write_steer_enabled(settings.experimental_steer_enabled);
         let _ = codex_config::write_unified_exec_enabled(settings.experimental_unified_exec_enabled);
         write_settings(&self.settings_path, &settings)?;
+        set_proxy(settings.proxy_url.as_deref(), settings.no_proxy.as_deref());
         let mut current = self.app_settings.lock().await;
         *current = settings.clone();
         Ok(settings)
@@ -780,7 +1331,11 @@ impl DaemonState {
             .ok_or("workspace not connected".to_string())
     }
 
-    async fn list_workspace_files(&self, workspace_id: String) -> Result<Vec<String>, String> {
+    async fn list_workspace_files(
+        &self,
+        workspace_id: String,
+        max_files: usize,
+    ) -> Result<WorkspaceFileList, String> {
         let entry = {
             let workspaces = self.workspaces.lock().await;
             workspaces
@@ -790,84 +1345,467 @@ impl DaemonState {
         };
 
         let root = PathBuf::from(entry.path);
-        Ok(list_workspace_files_inner(&root, 20000))
+        tokio::task::spawn_blocking(move || list_workspace_files_inner(&root, max_files))
+            .await
+            .map_err(|err| err.to_string())
     }
 
-    async fn start_thread(&self, workspace_id: String) -> Result<Value, String> {
-        let session = self.get_session(&workspace_id).await?;
-        let params = json!({
-            "cwd": session.entry.path,
-            "approvalPolicy": "on-request"
-        });
-        session.send_request("thread/start", params).await
-    }
+    async fn refresh_remote_state(&self, workspace_id: &str) -> Result<Value, String> {
+        let entry = {
+            let workspaces = self.workspaces.lock().await;
+            workspaces
+                .get(workspace_id)
+                .cloned()
+                .ok_or("workspace not found")?
+        };
 
-    async fn resume_thread(&self, workspace_id: String, thread_id: String) -> Result<Value, String> {
-        let session = self.get_session(&workspace_id).await?;
-        let params = json!({
-            "threadId": thread_id
-        });
-        session.send_request("thread/resume", params).await
+        let repo_path = PathBuf::from(&entry.path);
+        match refresh_remote_state_for_path(&repo_path).await? {
+            Some(remote) => Ok(remote.to_value()),
+            None => {
+                let branch = run_git_command(&repo_path, &["rev-parse", "--abbrev-ref", "HEAD"])
+                    .await?;
+                Ok(json!({
+                    "branch": branch,
+                    "upstream": Value::Null,
+                    "ahead": 0,
+                    "behind": 0,
+                }))
+            }
+        }
     }
 
-    async fn list_threads(
-        &self,
-        workspace_id: String,
-        cursor: Option<String>,
-        limit: Option<u32>,
-    ) -> Result<Value, String> {
-        let session = self.get_session(&workspace_id).await?;
-        let params = json!({
-            "cursor": cursor,
-            "limit": limit
-        });
-        session.send_request("thread/list", params).await
-    }
+    /// Runs `git fsck` on demand to tell repository corruption apart from
+    /// daemon errors. This reads every object, so it is never run implicitly.
+    async fn verify_git_repo(&self, workspace_id: &str) -> Result<Value, String> {
+        let entry = {
+            let workspaces = self.workspaces.lock().await;
+            workspaces
+                .get(workspace_id)
+                .cloned()
+                .ok_or("workspace not found")?
+        };
 
-    async fn archive_thread(&self, workspace_id: String, thread_id: String) -> Result<Value, String> {
-        let session = self.get_session(&workspace_id).await?;
-        let params = json!({ "threadId": thread_id });
-        session.send_request("thread/archive", params).await
+        // fsck reports problems on both streams and exits non-zero when it
+        // finds any, so the output is parsed regardless of the exit status.
+        let output = Command::new("git")
+            .envs(proxy_env())
+            .args(["fsck", "--no-progress"])
+            .current_dir(&entry.path)
+            .output()
+            .await
+            .map_err(|e| format!("Failed to run git: {e}"))?;
+        let mut text = String::from_utf8_lossy(&output.stdout).to_string();
+        text.push('\n');
+        text.push_str(&String::from_utf8_lossy(&output.stderr));
+        Ok(parse_fsck_output(&text, output.status.success()))
     }
 
-    async fn send_user_message(
-        &self,
-        workspace_id: String,
-        thread_id: String,
-        text: String,
-        model: Option<String>,
-        effort: Option<String>,
-        access_mode: Option<String>,
-        images: Option<Vec<String>>,
-        collaboration_mode: Option<Value>,
-    ) -> Result<Value, String> {
-        let session = self.get_session(&workspace_id).await?;
-        let access_mode = access_mode.unwrap_or_else(|| "current".to_string());
-        let sandbox_policy = match access_mode.as_str() {
-            "full-access" => json!({
-                "type": "dangerFullAccess"
-            }),
-            "read-only" => json!({
-                "type": "readOnly"
-            }),
-            _ => json!({
-                "type": "workspaceWrite",
-                "writableRoots": [session.entry.path],
-                "networkAccess": true
-            }),
+    async fn list_git_worktrees(&self, workspace_id: &str) -> Result<Value, String> {
+        let canonical = |path: &str| {
+            std::fs::canonicalize(path).unwrap_or_else(|_| PathBuf::from(path))
         };
-
-        let approval_policy = if access_mode == "full-access" {
-            "never"
-        } else {
-            "on-request"
+        let (entry, known) = {
+            let workspaces = self.workspaces.lock().await;
+            let entry = workspaces
+                .get(workspace_id)
+                .cloned()
+                .ok_or("workspace not found")?;
+            let known: Vec<(PathBuf, String)> = workspaces
+                .values()
+                .map(|workspace| (canonical(&workspace.path), workspace.id.clone()))
+                .collect();
+            (entry, known)
+        };
+        if entry.kind.is_worktree() {
+            return Err("List worktrees from the parent workspace.".to_string());
+        }
+
+        let output = run_git_command(
+            &PathBuf::from(&entry.path),
+            &["worktree", "list", "--porcelain"],
+        )
+        .await?;
+        let mut worktrees = parse_worktree_list(&output);
+        // Worktrees without a matching workspace were created outside CodexMonitor.
+        for worktree in &mut worktrees {
+            let path = worktree["path"].as_str().map(canonical);
+            let workspace_id = known
+                .iter()
+                .find(|(known_path, _)| Some(known_path) == path.as_ref())
+                .map(|(_, id)| id.clone());
+            worktree["workspaceId"] = json!(workspace_id);
+        }
+        Ok(json!({ "worktrees": worktrees }))
+    }
+
+    async fn resolve_file_path(&self, workspace_id: &str, path: &str) -> Result<Value, String> {
+        let entry = {
+            let workspaces = self.workspaces.lock().await;
+            workspaces
+                .get(workspace_id)
+                .cloned()
+                .ok_or("workspace not found")?
+        };
+
+        let resolved = resolve_workspace_file(&entry.path, path)?;
+        Ok(json!({
+            "path": path,
+            "absolutePath": resolved.to_string_lossy(),
+        }))
+    }
+
+    async fn read_workspace_file(
+        &self,
+        workspace_id: &str,
+        path: &str,
+        start_line: Option<usize>,
+        end_line: Option<usize>,
+    ) -> Result<Value, String> {
+        let entry = {
+            let workspaces = self.workspaces.lock().await;
+            workspaces
+                .get(workspace_id)
+                .cloned()
+                .ok_or("workspace not found")?
+        };
+
+        let file = resolve_workspace_file(&entry.path, path)?;
+        let mut window = read_file_window(&file, start_line, end_line)?;
+        if let Value::Object(map) = &mut window {
+            map.insert("path".to_string(), Value::String(path.to_string()));
+        }
+        Ok(window)
+    }
+
+    async fn grep_workspace(&self, workspace_id: &str, options: GrepOptions) -> Result<Value, String> {
+        let entry = {
+            let workspaces = self.workspaces.lock().await;
+            workspaces
+                .get(workspace_id)
+                .cloned()
+                .ok_or("workspace not found")?
+        };
+
+        let root = PathBuf::from(entry.path);
+        tokio::task::spawn_blocking(move || grep_workspace_inner(&root, &options))
+            .await
+            .map_err(|err| err.to_string())
+    }
+
+    async fn replace_in_files(
+        &self,
+        workspace_id: &str,
+        options: ReplaceOptions,
+    ) -> Result<Value, String> {
+        let entry = {
+            let workspaces = self.workspaces.lock().await;
+            workspaces
+                .get(workspace_id)
+                .cloned()
+                .ok_or("workspace not found")?
+        };
+
+        let root = PathBuf::from(entry.path);
+        tokio::task::spawn_blocking(move || replace_in_files_inner(&root, &options))
+            .await
+            .map_err(|err| err.to_string())?
+    }
+
+    async fn list_workspace_tree(
+        &self,
+        workspace_id: &str,
+        path: Option<String>,
+        max_depth: Option<usize>,
+    ) -> Result<Value, String> {
+        let entry = {
+            let workspaces = self.workspaces.lock().await;
+            workspaces
+                .get(workspace_id)
+                .cloned()
+                .ok_or("workspace not found")?
+        };
+
+        let root = PathBuf::from(&entry.path)
+            .canonicalize()
+            .map_err(|err| err.to_string())?;
+        let base = PathBuf::from(resolve_workspace_cwd(&entry.path, path.as_deref())?);
+        let max_depth = max_depth.filter(|depth| *depth > 0);
+        tokio::task::spawn_blocking(move || {
+            list_workspace_tree_inner(&root, &base, max_depth, 20000)
+        })
+        .await
+        .map_err(|err| err.to_string())
+    }
+
+    async fn start_thread(
+        &self,
+        workspace_id: String,
+        cwd: Option<String>,
+    ) -> Result<Value, String> {
+        let session = self.get_session(&workspace_id).await?;
+        let cwd = resolve_workspace_cwd(&session.entry.path, cwd.as_deref())?;
+        let params = json!({
+            "cwd": cwd,
+            "approvalPolicy": "on-request"
+        });
+        session.send_request("thread/start", params).await
+    }
+
+    async fn resume_thread(&self, workspace_id: String, thread_id: String) -> Result<Value, String> {
+        let session = self.get_session(&workspace_id).await?;
+        let params = json!({
+            "threadId": thread_id
+        });
+        session.send_request("thread/resume", params).await
+    }
+
+    async fn resume_latest_thread(&self, workspace_id: String) -> Result<Value, String> {
+        let session = self.get_session(&workspace_id).await?;
+        session.resume_latest_thread().await
+    }
+
+    async fn list_threads(
+        &self,
+        workspace_id: String,
+        cursor: Option<String>,
+        limit: Option<u32>,
+    ) -> Result<Value, String> {
+        let session = self.get_session(&workspace_id).await?;
+        let limit = effective_thread_list_limit(limit);
+        let params = json!({
+            "cursor": cursor,
+            "limit": limit
+        });
+        let response = session.send_request("thread/list", params).await?;
+        Ok(normalize_thread_list_response(response, limit))
+    }
+
+    /// Expands `{{diff}}`, `{{branch}}`, `{{issue:<number>}}` and caller-provided
+    /// `{{name}}` placeholders in a prompt template.
+    async fn expand_message_template(
+        &self,
+        workspace_id: &str,
+        template: &str,
+        values: &Map<String, Value>,
+    ) -> Result<String, String> {
+        let entry = {
+            let workspaces = self.workspaces.lock().await;
+            workspaces
+                .get(workspace_id)
+                .cloned()
+                .ok_or("workspace not found")?
+        };
+        let repo_path = PathBuf::from(&entry.path);
+
+        let mut resolved: HashMap<String, String> = HashMap::new();
+        for placeholder in template_placeholders(template)? {
+            if resolved.contains_key(&placeholder) {
+                continue;
+            }
+            let (name, arg) = match placeholder.split_once(':') {
+                Some((name, arg)) => (name.trim(), Some(arg.trim())),
+                None => (placeholder.trim(), None),
+            };
+            let value = match (name, arg) {
+                (name, None) if values.contains_key(name) => match &values[name] {
+                    Value::String(value) => value.clone(),
+                    other => other.to_string(),
+                },
+                ("diff", None) => {
+                    match run_git_command(&repo_path, &["diff", "HEAD"]).await {
+                        Ok(diff) => diff,
+                        // No commits yet: fall back to the index-vs-worktree diff.
+                        Err(_) => run_git_command(&repo_path, &["diff"]).await?,
+                    }
+                }
+                ("branch", None) => {
+                    run_git_command(&repo_path, &["rev-parse", "--abbrev-ref", "HEAD"]).await?
+                }
+                ("issue", Some(number)) => {
+                    let number = number
+                        .trim_start_matches('#')
+                        .parse::<u64>()
+                        .map_err(|_| format!("Invalid issue number in {{{{{placeholder}}}}}"))?;
+                    github_issue_for_prompt(&repo_path, number).await?
+                }
+                _ => return Err(format!("Unknown template placeholder: {{{{{placeholder}}}}}")),
+            };
+            resolved.insert(placeholder, value);
+        }
+
+        render_template(template, &resolved)
+    }
+
+    async fn diff_digest(
+        &self,
+        workspace_id: &str,
+        max_bytes: Option<usize>,
+    ) -> Result<Value, String> {
+        let entry = {
+            let workspaces = self.workspaces.lock().await;
+            workspaces
+                .get(workspace_id)
+                .cloned()
+                .ok_or("workspace not found")?
+        };
+        let repo_root = resolve_git_root(&entry)?;
+        tokio::task::spawn_blocking(move || diff_digest_for_path(&repo_root, max_bytes))
+            .await
+            .map_err(|err| err.to_string())?
+    }
+
+    async fn stream_git_diffs(
+        &self,
+        workspace_id: &str,
+        stream_id: Option<String>,
+    ) -> Result<Value, String> {
+        let entry = {
+            let workspaces = self.workspaces.lock().await;
+            workspaces
+                .get(workspace_id)
+                .cloned()
+                .ok_or("workspace not found")?
+        };
+        let repo_root = resolve_git_root(&entry)?;
+        let stream_id = stream_id.unwrap_or_else(|| Uuid::new_v4().to_string());
+        // The same files `get_git_diffs` returns, untracked ones included,
+        // sent as each patch is built so only one is held in memory.
+        let event_sink = self.event_sink.clone();
+        let workspace_id = workspace_id.to_string();
+        let event_stream_id = stream_id.clone();
+        let (files, additions, deletions) = tokio::task::spawn_blocking(move || {
+            let (mut files, mut additions, mut deletions) = (0usize, 0usize, 0usize);
+            for_each_file_diff(&repo_root, |file| {
+                files += 1;
+                additions += file.additions;
+                deletions += file.deletions;
+                event_sink.emit_notification(
+                    "git-diff-file",
+                    json!({
+                        "workspaceId": workspace_id,
+                        "streamId": event_stream_id,
+                        "path": file.path,
+                        "diff": file.diff,
+                        "additions": file.additions,
+                        "deletions": file.deletions,
+                        "renamedFrom": file.renamed_from,
+                        "isBinary": file.is_binary,
+                    }),
+                );
+            })
+            .map(|()| (files, additions, deletions))
+        })
+        .await
+        .map_err(|err| err.to_string())??;
+        Ok(json!({
+            "streamId": stream_id,
+            "files": files,
+            "additions": additions,
+            "deletions": deletions,
+        }))
+    }
+
+    async fn workspace_health(&self, workspace_id: String) -> Result<Value, String> {
+        {
+            let workspaces = self.workspaces.lock().await;
+            if !workspaces.contains_key(&workspace_id) {
+                return Err("workspace not found".to_string());
+            }
+        }
+        let session = self.sessions.lock().await.get(&workspace_id).cloned();
+        let Some(session) = session else {
+            return Ok(json!({
+                "workspaceId": workspace_id,
+                "connected": false,
+                "running": false,
+                "stderrTail": [],
+            }));
+        };
+        let (running, exit_code) = {
+            let mut child = session.child.lock().await;
+            match child.try_wait() {
+                Ok(None) => (true, None),
+                Ok(Some(status)) => (false, status.code()),
+                Err(_) => (false, None),
+            }
         };
+        let stderr_tail: Vec<String> = session.stderr_tail.lock().await.iter().cloned().collect();
+        Ok(json!({
+            "workspaceId": workspace_id,
+            "connected": true,
+            "running": running,
+            "exitCode": exit_code,
+            "stderrTail": stderr_tail,
+        }))
+    }
+
+    async fn get_active_turns(&self, workspace_id: String) -> Result<Value, String> {
+        self.get_session(&workspace_id).await?;
+        Ok(json!({ "turns": self.event_sink.active_turns.list(&workspace_id) }))
+    }
+
+    fn get_turn_message(&self, workspace_id: &str, thread_id: &str) -> Value {
+        let message = self
+            .event_sink
+            .turn_messages
+            .lock()
+            .ok()
+            .and_then(|messages| messages.get(workspace_id, thread_id));
+        json!({ "message": message })
+    }
+
+    async fn archive_thread(&self, workspace_id: String, thread_id: String) -> Result<Value, String> {
+        let session = self.get_session(&workspace_id).await?;
+        let params = json!({ "threadId": thread_id });
+        session.send_request("thread/archive", params).await
+    }
+
+    async fn send_user_message(
+        &self,
+        workspace_id: String,
+        thread_id: String,
+        text: String,
+        model: Option<String>,
+        effort: Option<String>,
+        access_mode: Option<String>,
+        images: Option<Vec<String>>,
+        collaboration_mode: Option<Value>,
+        cwd: Option<String>,
+        confirm_full_access: bool,
+        queue_when_busy: bool,
+        client: &ClientInfo,
+    ) -> Result<Value, String> {
+        let session = self.get_session(&workspace_id).await?;
+        let cwd = resolve_workspace_cwd(&session.entry.path, cwd.as_deref())?;
+        let policy_overrides = self.app_settings.lock().await.access_mode_policies.clone();
+        let policy = TurnPolicy::for_access_mode(
+            access_mode.as_deref(),
+            &session.entry.path,
+            &policy_overrides,
+        );
+        let audited = AuditedTurn {
+            entry: &session.entry,
+            client,
+            thread_id: &thread_id,
+            cwd: &cwd,
+            policy: &policy,
+            text: &text,
+            image_count: images.as_ref().map(|paths| paths.len()).unwrap_or(0),
+        };
+        if let Err(err) =
+            policy.ensure_allowed(confirm_full_access, session.entry.settings.allow_full_access)
+        {
+            self.audit_turn(&audited, Err(&err)).await;
+            return Err(err);
+        }
 
         let trimmed_text = text.trim();
         let mut input: Vec<Value> = Vec::new();
         if !trimmed_text.is_empty() {
             input.push(json!({ "type": "text", "text": trimmed_text }));
         }
+        let attachment_roots = self.app_settings.lock().await.attachment_roots.clone();
         if let Some(paths) = images {
             for path in paths {
                 let trimmed = path.trim();
@@ -880,7 +1818,9 @@ impl DaemonState {
                 {
                     input.push(json!({ "type": "image", "url": trimmed }));
                 } else {
-                    input.push(json!({ "type": "localImage", "path": trimmed }));
+                    let path =
+                        resolve_attachment_path(&session.entry, trimmed, &attachment_roots)?;
+                    input.push(json!({ "type": "localImage", "path": path }));
                 }
             }
         }
@@ -891,14 +1831,45 @@ impl DaemonState {
         let params = json!({
             "threadId": thread_id,
             "input": input,
-            "cwd": session.entry.path,
-            "approvalPolicy": approval_policy,
-            "sandboxPolicy": sandbox_policy,
+            "cwd": cwd,
+            "approvalPolicy": policy.approval_policy,
+            "sandboxPolicy": policy.sandbox_policy.clone(),
             "model": model,
             "effort": effort,
             "collaborationMode": collaboration_mode,
         });
-        session.send_request("turn/start", params).await
+        let active_turns = &self.event_sink.active_turns;
+        let reservation = match self.max_concurrent_turns {
+            Some(limit) => {
+                let reserved = active_turns
+                    .reserve(limit, queue_when_busy, &workspace_id, &thread_id)
+                    .await;
+                match reserved {
+                    Ok(reservation) => Some(reservation),
+                    Err(err) => {
+                        self.audit_turn(&audited, Err(&err)).await;
+                        return Err(err);
+                    }
+                }
+            }
+            None => None,
+        };
+        let response = session.send_request("turn/start", params).await;
+        let outcome = match &response {
+            Ok(value) => match value.get("error") {
+                Some(error) => Err(error
+                    .get("message")
+                    .and_then(|message| message.as_str())
+                    .unwrap_or("turn/start failed")),
+                None => Ok(()),
+            },
+            Err(err) => Err(err.as_str()),
+        };
+        if let (Some(reservation), Ok(())) = (reservation, outcome) {
+            reservation.hand_off();
+        }
+        self.audit_turn(&audited, outcome).await;
+        Ok(policy.attach_to_response(response?))
     }
 
     async fn turn_interrupt(
@@ -934,6 +1905,27 @@ impl DaemonState {
             .await
     }
 
+    /// Forwards an arbitrary app-server request. This skips the daemon's own
+    /// sandbox and approval handling, so it only runs for methods allowed by
+    /// `--allow-raw`.
+    async fn codex_raw(
+        &self,
+        workspace_id: String,
+        method: String,
+        params: Value,
+    ) -> Result<Value, String> {
+        let allowed = self.raw_methods.as_ref().is_some_and(|methods| {
+            methods.iter().any(|allowed| allowed == "*" || *allowed == method)
+        });
+        if !allowed {
+            return Err(format!(
+                "codex_raw is not allowed for `{method}` (start the daemon with --allow-raw)"
+            ));
+        }
+        let session = self.get_session(&workspace_id).await?;
+        session.send_request(&method, params).await
+    }
+
     async fn model_list(&self, workspace_id: String) -> Result<Value, String> {
         let session = self.get_session(&workspace_id).await?;
         session.send_request("model/list", json!({})).await
@@ -1013,17 +2005,6 @@ impl DaemonState {
     }
 }
 
-fn sort_workspaces(workspaces: &mut [WorkspaceInfo]) {
-    workspaces.sort_by(|a, b| {
-        let a_order = a.settings.sort_order.unwrap_or(u32::MAX);
-        let b_order = b.settings.sort_order.unwrap_or(u32::MAX);
-        if a_order != b_order {
-            return a_order.cmp(&b_order);
-        }
-        a.name.cmp(&b.name)
-    });
-}
-
 fn should_skip_dir(name: &str) -> bool {
     matches!(
         name,
@@ -1035,9 +2016,9 @@ fn normalize_git_path(path: &str) -> String {
     path.replace('\\', "/")
 }
 
-fn list_workspace_files_inner(root: &PathBuf, max_files: usize) -> Vec<String> {
-    let mut results = Vec::new();
-    let walker = WalkBuilder::new(root)
+fn workspace_walk_builder(root: &PathBuf) -> WalkBuilder {
+    let mut builder = WalkBuilder::new(root);
+    builder
         .hidden(false)
         .follow_links(false)
         .require_git(false)
@@ -1050,10 +2031,92 @@ fn list_workspace_files_inner(root: &PathBuf, max_files: usize) -> Vec<String> {
                 return !should_skip_dir(&name);
             }
             true
-        })
-        .build();
+        });
+    builder
+}
+
+fn workspace_file_walker(root: &PathBuf) -> ignore::Walk {
+    workspace_walk_builder(root).build()
+}
+
+/// Lists `base` (a directory inside `root`) as a nested tree, `max_depth`
+/// levels deep. Paths in the result stay relative to the workspace root.
+fn list_workspace_tree_inner(
+    root: &PathBuf,
+    base: &PathBuf,
+    max_depth: Option<usize>,
+    max_entries: usize,
+) -> Value {
+    let mut entries: Vec<(String, bool)> = Vec::new();
+    let mut truncated = false;
+    let walker = workspace_walk_builder(base).max_depth(max_depth).build();
+    for entry in walker.flatten() {
+        if entry.depth() == 0 {
+            continue;
+        }
+        let Ok(rel_path) = entry.path().strip_prefix(base) else {
+            continue;
+        };
+        let normalized = normalize_git_path(&rel_path.to_string_lossy());
+        if normalized.is_empty() {
+            continue;
+        }
+        let is_dir = entry.file_type().is_some_and(|ft| ft.is_dir());
+        entries.push((normalized, is_dir));
+        if entries.len() >= max_entries {
+            truncated = true;
+            break;
+        }
+    }
+    let base_path = base
+        .strip_prefix(root)
+        .map(|path| normalize_git_path(&path.to_string_lossy()))
+        .unwrap_or_default();
+    let mut children = build_file_tree(&entries, max_depth);
+    if !base_path.is_empty() {
+        prefix_tree_paths(&mut children, &base_path);
+    }
+    json!({
+        "path": base_path,
+        "children": children,
+        "truncated": truncated,
+    })
+}
+
+fn prefix_tree_paths(nodes: &mut [Value], prefix: &str) {
+    for node in nodes {
+        if let Some(path) = node.get("path").and_then(|value| value.as_str()) {
+            node["path"] = Value::String(format!("{prefix}/{path}"));
+        }
+        if let Some(Value::Array(children)) = node.get_mut("children") {
+            prefix_tree_paths(children, prefix);
+        }
+    }
+}
+
+fn list_workspace_files_inner(root: &PathBuf, max_files: usize) -> WorkspaceFileList {
+    let paths = workspace_file_walker(root)
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_type().is_some_and(|ft| ft.is_file()))
+        .filter_map(|entry| {
+            let rel_path = entry.path().strip_prefix(root).ok()?;
+            let normalized = normalize_git_path(&rel_path.to_string_lossy());
+            (!normalized.is_empty()).then_some(normalized)
+        });
+    collect_file_list(paths, max_files)
+}
+
+struct GrepOptions {
+    regex: Regex,
+    globs: Option<GlobSet>,
+    max_matches: usize,
+    max_matches_per_file: usize,
+}
 
-    for entry in walker {
+fn grep_workspace_inner(root: &PathBuf, options: &GrepOptions) -> Value {
+    let mut matches = Vec::new();
+    let mut truncated = false;
+    for entry in workspace_file_walker(root) {
         let entry = match entry {
             Ok(entry) => entry,
             Err(_) => continue,
@@ -1061,32 +2124,125 @@ fn list_workspace_files_inner(root: &PathBuf, max_files: usize) -> Vec<String> {
         if !entry.file_type().is_some_and(|ft| ft.is_file()) {
             continue;
         }
-        if let Ok(rel_path) = entry.path().strip_prefix(root) {
-            let normalized = normalize_git_path(&rel_path.to_string_lossy());
-            if !normalized.is_empty() {
-                results.push(normalized);
+        let Ok(rel_path) = entry.path().strip_prefix(root) else {
+            continue;
+        };
+        let normalized = normalize_git_path(&rel_path.to_string_lossy());
+        if normalized.is_empty() {
+            continue;
+        }
+        if let Some(globs) = &options.globs {
+            if !globs.is_match(&normalized) {
+                continue;
             }
         }
-        if results.len() >= max_files {
+        let remaining = options.max_matches - matches.len();
+        let per_file = options.max_matches_per_file.min(remaining);
+        for found in grep_file(entry.path(), &options.regex, per_file) {
+            matches.push(json!({
+                "path": normalized,
+                "line": found.line,
+                "column": found.column,
+                "text": found.text,
+            }));
+        }
+        if matches.len() >= options.max_matches {
+            truncated = true;
             break;
         }
     }
+    json!({ "matches": matches, "truncated": truncated })
+}
 
-    results.sort();
-    results
+enum ReplaceTargets {
+    Paths(Vec<String>),
+    Globs(GlobSet),
 }
 
-async fn run_git_command(repo_path: &PathBuf, args: &[&str]) -> Result<String, String> {
-    let output = Command::new("git")
-        .args(args)
-        .current_dir(repo_path)
-        .output()
-        .await
-        .map_err(|e| format!("Failed to run git: {e}"))?;
-    if output.status.success() {
-        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
-    } else {
-        let stderr = String::from_utf8_lossy(&output.stderr);
+struct ReplaceOptions {
+    regex: Regex,
+    replacement: String,
+    targets: ReplaceTargets,
+    dry_run: bool,
+}
+
+fn replace_in_files_inner(root: &PathBuf, options: &ReplaceOptions) -> Result<Value, String> {
+    let root_str = root.to_string_lossy().to_string();
+    let mut targets: Vec<(PathBuf, String)> = Vec::new();
+    match &options.targets {
+        ReplaceTargets::Paths(paths) => {
+            for path in paths {
+                let resolved = resolve_workspace_file(&root_str, path)?;
+                targets.push((resolved, normalize_git_path(path)));
+            }
+        }
+        ReplaceTargets::Globs(globs) => {
+            for entry in workspace_file_walker(root).flatten() {
+                if !entry.file_type().is_some_and(|ft| ft.is_file()) {
+                    continue;
+                }
+                let Ok(rel_path) = entry.path().strip_prefix(root) else {
+                    continue;
+                };
+                let normalized = normalize_git_path(&rel_path.to_string_lossy());
+                if !normalized.is_empty() && globs.is_match(&normalized) {
+                    targets.push((entry.path().to_path_buf(), normalized));
+                }
+            }
+        }
+    }
+
+    let mut files = Vec::new();
+    let mut total_replacements = 0usize;
+    for (path, display_path) in targets {
+        match replace_in_file(
+            &path,
+            &display_path,
+            &options.regex,
+            &options.replacement,
+            options.dry_run,
+        ) {
+            Ok(Some(change)) => {
+                total_replacements += change.replacements;
+                let mut file = json!({
+                    "path": display_path,
+                    "replacements": change.replacements,
+                    "status": if options.dry_run { "preview" } else { "replaced" },
+                });
+                if options.dry_run {
+                    file["diff"] = Value::String(change.diff);
+                }
+                files.push(file);
+            }
+            Ok(None) => {}
+            Err(err) => files.push(json!({
+                "path": display_path,
+                "replacements": 0,
+                "status": "error",
+                "error": err,
+            })),
+        }
+    }
+
+    Ok(json!({
+        "dryRun": options.dry_run,
+        "files": files,
+        "totalReplacements": total_replacements,
+    }))
+}
+
+async fn run_git_command(repo_path: &PathBuf, args: &[&str]) -> Result<String, String> {
+    let output = Command::new("git")
+        .envs(proxy_env())
+        .args(args)
+        .current_dir(repo_path)
+        .output()
+        .await
+        .map_err(|e| format!("Failed to run git: {e}"))?;
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    } else {
+        let stderr = String::from_utf8_lossy(&output.stderr);
         let stdout = String::from_utf8_lossy(&output.stdout);
         let detail = if stderr.trim().is_empty() {
             stdout.trim()
@@ -1101,12 +2257,36 @@ async fn run_git_command(repo_path: &PathBuf, args: &[&str]) -> Result<String, S
     }
 }
 
+async fn github_issue_for_prompt(repo_path: &PathBuf, number: u64) -> Result<String, String> {
+    let number = number.to_string();
+    let output = Command::new("gh")
+        .envs(proxy_env())
+        .args(["issue", "view", &number, "--json", "number,title,body"])
+        .current_dir(repo_path)
+        .output()
+        .await
+        .map_err(|e| format!("Failed to run gh: {e}"))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let detail = stderr.trim();
+        if detail.is_empty() {
+            return Err("GitHub CLI command failed.".to_string());
+        }
+        return Err(detail.to_string());
+    }
+    let issue: Value = serde_json::from_slice(&output.stdout).map_err(|e| e.to_string())?;
+    let title = issue.get("title").and_then(|value| value.as_str()).unwrap_or("");
+    let body = issue.get("body").and_then(|value| value.as_str()).unwrap_or("");
+    Ok(format!("#{number} {title}\n\n{body}").trim_end().to_string())
+}
+
 fn is_missing_worktree_error(error: &str) -> bool {
     error.contains("is not a working tree")
 }
 
 async fn git_branch_exists(repo_path: &PathBuf, branch: &str) -> Result<bool, String> {
     let status = Command::new("git")
+        .envs(proxy_env())
         .args(["show-ref", "--verify", &format!("refs/heads/{branch}")])
         .current_dir(repo_path)
         .status()
@@ -1115,8 +2295,25 @@ async fn git_branch_exists(repo_path: &PathBuf, branch: &str) -> Result<bool, St
     Ok(status.success())
 }
 
+/// Whether `rev` names a commit (SHA, tag, branch or remote branch).
+async fn git_commit_exists(repo_path: &PathBuf, rev: &str) -> Result<bool, String> {
+    if rev.starts_with('-') {
+        return Ok(false);
+    }
+    let status = Command::new("git")
+        .envs(proxy_env())
+        .args(["rev-parse", "--verify", "--quiet", &format!("{rev}^{{commit}}")])
+        .current_dir(repo_path)
+        .stdout(std::process::Stdio::null())
+        .status()
+        .await
+        .map_err(|e| format!("Failed to run git: {e}"))?;
+    Ok(status.success())
+}
+
 async fn git_remote_exists(repo_path: &PathBuf, remote: &str) -> Result<bool, String> {
     let status = Command::new("git")
+        .envs(proxy_env())
         .args(["remote", "get-url", remote])
         .current_dir(repo_path)
         .status()
@@ -1131,6 +2328,7 @@ async fn git_remote_branch_exists_live(
     branch: &str,
 ) -> Result<bool, String> {
     let output = Command::new("git")
+        .envs(proxy_env())
         .args([
             "ls-remote",
             "--heads",
@@ -1161,6 +2359,7 @@ async fn git_remote_branch_exists_live(
 
 async fn git_remote_branch_exists(repo_path: &PathBuf, remote: &str, branch: &str) -> Result<bool, String> {
     let status = Command::new("git")
+        .envs(proxy_env())
         .args([
             "show-ref",
             "--verify",
@@ -1330,8 +2529,8 @@ fn default_data_dir() -> PathBuf {
 fn usage() -> String {
     format!(
         "\
-USAGE:\n  codex-monitor-daemon [--listen <addr>] [--data-dir <path>] [--token <token> | --insecure-no-auth]\n\n\
-OPTIONS:\n  --listen <addr>        Bind address (default: {DEFAULT_LISTEN_ADDR})\n  --data-dir <path>      Data dir holding workspaces.json/settings.json\n  --token <token>        Shared token required by clients\n  --insecure-no-auth      Disable auth (dev only)\n  -h, --help             Show this help\n"
+USAGE:\n  codex-monitor-daemon [--listen <addr> | --socket <path>] [--tls-cert <path> --tls-key <path>] [--data-dir <path>] [--token <token> | --insecure-no-auth] [--audit-log <path>] [--event-log <path> [--log-prompts]] [--log-max-bytes <n>] [--log-keep <n>] [--max-concurrent-turns <n>] [--auto-connect [--spawn-concurrency <n>]] [--allow-raw <methods>]\n\n\
+OPTIONS:\n  --listen <addr>        Bind address (default: {DEFAULT_LISTEN_ADDR})\n  --socket <path>        Listen on a Unix socket instead (owner-only; --token optional)\n  --tls-cert <path>      PEM certificate chain; TCP clients must connect over TLS\n  --tls-key <path>       PEM private key for --tls-cert\n  --data-dir <path>      Data dir holding workspaces.json/settings.json\n  --token <token>        Shared token required by clients\n  --insecure-no-auth      Disable auth (dev only)\n  --audit-log <path>     Append a JSON-lines audit record for every turn\n  --event-log <path>     Append every daemon event as JSON lines\n  --log-prompts          Keep prompt text in the event log (redacted by default)\n  --log-max-bytes <n>    Rotate the audit and event logs past this size (default: {DEFAULT_LOG_MAX_BYTES}, 0 disables)\n  --log-keep <n>         Rotated files kept per log (default: {DEFAULT_LOG_KEEP})\n  --max-concurrent-turns <n>  Cap running turns across all workspaces\n  --auto-connect         Connect every saved workspace at startup\n  --spawn-concurrency <n>  Sessions spawned in parallel by --auto-connect (default: {DEFAULT_SPAWN_CONCURRENCY})\n  --allow-raw <methods>  Enable codex_raw for comma-separated app-server methods (`*` for any)\n  -h, --help             Show this help\n"
     )
 }
 
@@ -1344,7 +2543,18 @@ fn parse_args() -> Result<DaemonConfig, String> {
         .map(|value| value.trim().to_string())
         .filter(|value| !value.is_empty());
     let mut insecure_no_auth = false;
+    let mut socket: Option<PathBuf> = None;
+    let mut tls_cert: Option<PathBuf> = None;
+    let mut tls_key: Option<PathBuf> = None;
     let mut data_dir: Option<PathBuf> = None;
+    let mut audit_log: Option<PathBuf> = None;
+    let mut max_concurrent_turns: Option<usize> = None;
+    let mut auto_connect = false;
+    let mut spawn_concurrency = DEFAULT_SPAWN_CONCURRENCY;
+    let mut raw_methods: Option<Vec<String>> = None;
+    let mut event_log: Option<PathBuf> = None;
+    let mut log_prompts = false;
+    let mut log_rotation = LogRotation::default();
 
     let mut args = env::args().skip(1);
     while let Some(arg) = args.next() {
@@ -1357,6 +2567,33 @@ fn parse_args() -> Result<DaemonConfig, String> {
                 let value = args.next().ok_or("--listen requires a value")?;
                 listen = value.parse::<SocketAddr>().map_err(|err| err.to_string())?;
             }
+            "--socket" => {
+                let value = args.next().ok_or("--socket requires a value")?;
+                let trimmed = value.trim();
+                if trimmed.is_empty() {
+                    return Err("--socket requires a non-empty value".to_string());
+                }
+                if !cfg!(unix) {
+                    return Err("--socket is only supported on Unix".to_string());
+                }
+                socket = Some(PathBuf::from(trimmed));
+            }
+            "--tls-cert" => {
+                let value = args.next().ok_or("--tls-cert requires a value")?;
+                let trimmed = value.trim();
+                if trimmed.is_empty() {
+                    return Err("--tls-cert requires a non-empty value".to_string());
+                }
+                tls_cert = Some(PathBuf::from(trimmed));
+            }
+            "--tls-key" => {
+                let value = args.next().ok_or("--tls-key requires a value")?;
+                let trimmed = value.trim();
+                if trimmed.is_empty() {
+                    return Err("--tls-key requires a non-empty value".to_string());
+                }
+                tls_key = Some(PathBuf::from(trimmed));
+            }
             "--token" => {
                 let value = args.next().ok_or("--token requires a value")?;
                 let trimmed = value.trim();
@@ -1373,6 +2610,73 @@ fn parse_args() -> Result<DaemonConfig, String> {
                 }
                 data_dir = Some(PathBuf::from(trimmed));
             }
+            "--audit-log" => {
+                let value = args.next().ok_or("--audit-log requires a value")?;
+                let trimmed = value.trim();
+                if trimmed.is_empty() {
+                    return Err("--audit-log requires a non-empty value".to_string());
+                }
+                audit_log = Some(PathBuf::from(trimmed));
+            }
+            "--event-log" => {
+                let value = args.next().ok_or("--event-log requires a value")?;
+                let trimmed = value.trim();
+                if trimmed.is_empty() {
+                    return Err("--event-log requires a non-empty value".to_string());
+                }
+                event_log = Some(PathBuf::from(trimmed));
+            }
+            "--log-prompts" => {
+                log_prompts = true;
+            }
+            "--log-max-bytes" => {
+                let value = args.next().ok_or("--log-max-bytes requires a value")?;
+                log_rotation.max_bytes = value
+                    .trim()
+                    .parse::<u64>()
+                    .map_err(|_| "--log-max-bytes requires a non-negative integer")?;
+            }
+            "--log-keep" => {
+                let value = args.next().ok_or("--log-keep requires a value")?;
+                log_rotation.keep = value
+                    .trim()
+                    .parse::<usize>()
+                    .map_err(|_| "--log-keep requires a non-negative integer")?;
+            }
+            "--max-concurrent-turns" => {
+                let value = args.next().ok_or("--max-concurrent-turns requires a value")?;
+                let limit = value
+                    .trim()
+                    .parse::<usize>()
+                    .ok()
+                    .filter(|limit| *limit > 0)
+                    .ok_or("--max-concurrent-turns requires a positive integer")?;
+                max_concurrent_turns = Some(limit);
+            }
+            "--auto-connect" => {
+                auto_connect = true;
+            }
+            "--spawn-concurrency" => {
+                let value = args.next().ok_or("--spawn-concurrency requires a value")?;
+                spawn_concurrency = value
+                    .trim()
+                    .parse::<usize>()
+                    .ok()
+                    .filter(|limit| *limit > 0)
+                    .ok_or("--spawn-concurrency requires a positive integer")?;
+            }
+            "--allow-raw" => {
+                let value = args.next().ok_or("--allow-raw requires a value")?;
+                let methods: Vec<String> = value
+                    .split(',')
+                    .map(|method| method.trim().to_string())
+                    .filter(|method| !method.is_empty())
+                    .collect();
+                if methods.is_empty() {
+                    return Err("--allow-raw requires `*` or a list of methods".to_string());
+                }
+                raw_methods = Some(methods);
+            }
             "--insecure-no-auth" => {
                 insecure_no_auth = true;
                 token = None;
@@ -1381,7 +2685,19 @@ fn parse_args() -> Result<DaemonConfig, String> {
         }
     }
 
-    if token.is_none() && !insecure_no_auth {
+    let tls = match (tls_cert, tls_key) {
+        (Some(cert), Some(key)) => {
+            if socket.is_some() {
+                return Err("--tls-cert cannot be combined with --socket".to_string());
+            }
+            Some(load_tls_acceptor(&cert, &key)?)
+        }
+        (None, None) => None,
+        _ => return Err("--tls-cert and --tls-key must be used together".to_string()),
+    };
+
+    // A Unix socket is guarded by its file permissions, so the token is optional there.
+    if token.is_none() && !insecure_no_auth && socket.is_none() {
         return Err(
             "Missing --token (or set CODEX_MONITOR_DAEMON_TOKEN). Use --insecure-no-auth for local dev only."
                 .to_string(),
@@ -1390,31 +2706,46 @@ fn parse_args() -> Result<DaemonConfig, String> {
 
     Ok(DaemonConfig {
         listen,
+        socket,
+        tls,
         token,
         data_dir: data_dir.unwrap_or_else(default_data_dir),
+        audit_log,
+        max_concurrent_turns,
+        auto_connect,
+        spawn_concurrency,
+        raw_methods,
+        event_log,
+        log_prompts,
+        log_rotation,
     })
 }
 
-fn build_error_response(id: Option<u64>, message: &str) -> Option<String> {
+/// Returns the request `id` to echo back verbatim. Numbers and strings are
+/// accepted; a missing or null id means no response is sent.
+fn parse_request_id(message: &Value) -> Option<Value> {
+    match message.get("id")? {
+        id @ (Value::Number(_) | Value::String(_)) => Some(id.clone()),
+        _ => None,
+    }
+}
+
+fn build_error_response(framing: Framing, id: Option<&Value>, message: &str) -> Option<String> {
     let id = id?;
-    Some(
-        serde_json::to_string(&json!({
-            "id": id,
-            "error": { "message": message }
-        }))
-        .unwrap_or_else(|_| "{\"id\":0,\"error\":{\"message\":\"serialization failed\"}}".to_string()),
-    )
+    Some(serde_json::to_string(&framing.error(id, message)).unwrap_or_else(|_| {
+        "{\"id\":0,\"error\":{\"message\":\"serialization failed\"}}".to_string()
+    }))
 }
 
-fn build_result_response(id: Option<u64>, result: Value) -> Option<String> {
+fn build_result_response(framing: Framing, id: Option<&Value>, result: Value) -> Option<String> {
     let id = id?;
-    Some(serde_json::to_string(&json!({ "id": id, "result": result })).unwrap_or_else(|_| {
+    Some(serde_json::to_string(&framing.result(id, result)).unwrap_or_else(|_| {
         "{\"id\":0,\"error\":{\"message\":\"serialization failed\"}}".to_string()
     }))
 }
 
-fn build_event_notification(event: DaemonEvent) -> Option<String> {
-    let payload = match event {
+fn event_payload(event: DaemonEvent) -> Value {
+    match event {
         DaemonEvent::AppServer(payload) => json!({
             "method": "app-server-event",
             "params": payload,
@@ -1423,8 +2754,42 @@ fn build_event_notification(event: DaemonEvent) -> Option<String> {
             "method": "terminal-output",
             "params": payload,
         }),
-    };
-    serde_json::to_string(&payload).ok()
+        DaemonEvent::Notification { method, params } => json!({
+            "method": method,
+            "params": params,
+        }),
+        DaemonEvent::ClientConnected {
+            connection_id,
+            address,
+            timestamp,
+            client,
+        } => {
+            let mut params = client.to_value();
+            params["connectionId"] = json!(connection_id);
+            params["address"] = json!(address);
+            params["timestamp"] = json!(timestamp);
+            json!({
+                "method": "client-connected",
+                "params": params,
+            })
+        }
+        DaemonEvent::ClientDisconnected {
+            connection_id,
+            address,
+            timestamp,
+        } => json!({
+            "method": "client-disconnected",
+            "params": {
+                "connectionId": connection_id,
+                "address": address,
+                "timestamp": timestamp,
+            },
+        }),
+    }
+}
+
+fn build_event_notification(event: DaemonEvent, framing: Framing) -> Option<String> {
+    serde_json::to_string(&framing.notification(event_payload(event))).ok()
 }
 
 fn parse_auth_token(params: &Value) -> Option<String> {
@@ -1459,6 +2824,13 @@ fn parse_optional_string(value: &Value, key: &str) -> Option<String> {
     }
 }
 
+fn parse_optional_bool(value: &Value, key: &str) -> Option<bool> {
+    match value {
+        Value::Object(map) => map.get(key).and_then(|value| value.as_bool()),
+        _ => None,
+    }
+}
+
 fn parse_optional_u32(value: &Value, key: &str) -> Option<u32> {
     match value {
         Value::Object(map) => map.get(key).and_then(|value| value.as_u64()).and_then(|v| {
@@ -1495,14 +2867,69 @@ fn parse_optional_value(value: &Value, key: &str) -> Option<Value> {
     }
 }
 
+fn parse_optional_glob_set(value: &Value, key: &str) -> Result<Option<GlobSet>, String> {
+    let Some(patterns) = parse_optional_string_array(value, key) else {
+        return Ok(None);
+    };
+    if patterns.is_empty() {
+        return Ok(None);
+    }
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        let glob = Glob::new(&pattern).map_err(|err| format!("Invalid glob {pattern}: {err}"))?;
+        builder.add(glob);
+    }
+    builder.build().map(Some).map_err(|err| err.to_string())
+}
+
+async fn send_user_message_from_params(
+    state: &DaemonState,
+    params: &Value,
+    text: String,
+    client: &ClientInfo,
+) -> Result<Value, String> {
+    let workspace_id = parse_string(params, "workspaceId")?;
+    let thread_id = parse_string(params, "threadId")?;
+    let model = parse_optional_string(params, "model");
+    let effort = parse_optional_string(params, "effort");
+    let access_mode = parse_optional_string(params, "accessMode");
+    let images = parse_optional_string_array(params, "images");
+    let collaboration_mode = parse_optional_value(params, "collaborationMode");
+    let cwd = parse_optional_string(params, "cwd");
+    let confirm_full_access = parse_optional_bool(params, "confirmFullAccess");
+    let queue_when_busy = match parse_optional_string(params, "whenBusy").as_deref() {
+        None | Some("reject") => false,
+        Some("queue") => true,
+        Some(other) => return Err(format!("Invalid whenBusy: {other} (expected queue or reject)")),
+    };
+    state
+        .send_user_message(
+            workspace_id,
+            thread_id,
+            text,
+            model,
+            effort,
+            access_mode,
+            images,
+            collaboration_mode,
+            cwd,
+            confirm_full_access.unwrap_or(false),
+            queue_when_busy,
+            client,
+        )
+        .await
+}
+
 async fn handle_rpc_request(
     state: &DaemonState,
     method: &str,
     params: Value,
-    client_version: String,
+    client: &ClientInfo,
 ) -> Result<Value, String> {
+    let client_version = client.app_server_version();
     match method {
         "ping" => Ok(json!({ "ok": true })),
+        "describe_rpc" => Ok(describe_rpc()),
         "list_workspaces" => {
             let workspaces = state.list_workspaces().await;
             serde_json::to_value(workspaces).map_err(|err| err.to_string())
@@ -1515,14 +2942,18 @@ async fn handle_rpc_request(
         "add_workspace" => {
             let path = parse_string(&params, "path")?;
             let codex_bin = parse_optional_string(&params, "codex_bin");
-            let workspace = state.add_workspace(path, codex_bin, client_version).await?;
+            let force = parse_optional_bool(&params, "force").unwrap_or(false);
+            let workspace = state
+                .add_workspace(path, codex_bin, force, client_version)
+                .await?;
             serde_json::to_value(workspace).map_err(|err| err.to_string())
         }
         "add_worktree" => {
             let parent_id = parse_string(&params, "parentId")?;
             let branch = parse_string(&params, "branch")?;
+            let base_ref = parse_optional_string(&params, "baseRef");
             let workspace = state
-                .add_worktree(parent_id, branch, client_version)
+                .add_worktree(parent_id, branch, base_ref, client_version)
                 .await?;
             serde_json::to_value(workspace).map_err(|err| err.to_string())
         }
@@ -1531,6 +2962,11 @@ async fn handle_rpc_request(
             state.connect_workspace(id, client_version).await?;
             Ok(json!({ "ok": true }))
         }
+        "disconnect_workspace" => {
+            let id = parse_string(&params, "id")?;
+            state.disconnect_workspace(id).await?;
+            Ok(json!({ "ok": true }))
+        }
         "remove_workspace" => {
             let id = parse_string(&params, "id")?;
             state.remove_workspace(id).await?;
@@ -1541,6 +2977,10 @@ async fn handle_rpc_request(
             state.remove_worktree(id).await?;
             Ok(json!({ "ok": true }))
         }
+        "prune_worktrees" => {
+            let parent_id = parse_string(&params, "parentId")?;
+            state.prune_worktrees(&parent_id).await
+        }
         "rename_worktree" => {
             let id = parse_string(&params, "id")?;
             let branch = parse_string(&params, "branch")?;
@@ -1573,11 +3013,123 @@ async fn handle_rpc_request(
             let workspace = state.update_workspace_codex_bin(id, codex_bin).await?;
             serde_json::to_value(workspace).map_err(|err| err.to_string())
         }
+        "rename_workspace" => {
+            let id = parse_string(&params, "id")?;
+            let name = parse_string(&params, "name")?;
+            let workspace = state.rename_workspace(id, name).await?;
+            serde_json::to_value(workspace).map_err(|err| err.to_string())
+        }
+        "relocate_workspace" => {
+            let id = parse_string(&params, "id")?;
+            let path = parse_string(&params, "path")?;
+            let workspace = state.relocate_workspace(id, path).await?;
+            serde_json::to_value(workspace).map_err(|err| err.to_string())
+        }
         "list_workspace_files" => {
             let workspace_id = parse_string(&params, "workspaceId")?;
-            let files = state.list_workspace_files(workspace_id).await?;
+            let max_files = parse_optional_u32(&params, "maxFiles").map(|value| value as usize);
+            let files = state
+                .list_workspace_files(workspace_id, listed_files_limit(max_files))
+                .await?;
             serde_json::to_value(files).map_err(|err| err.to_string())
         }
+        "get_diff_digest" => {
+            let workspace_id = parse_string(&params, "workspaceId")?;
+            let max_bytes = parse_optional_u32(&params, "maxBytes").map(|value| value as usize);
+            state.diff_digest(&workspace_id, max_bytes).await
+        }
+        "stream_git_diffs" => {
+            let workspace_id = parse_string(&params, "workspaceId")?;
+            let stream_id = parse_optional_string(&params, "streamId");
+            state.stream_git_diffs(&workspace_id, stream_id).await
+        }
+        "read_workspace_file" => {
+            let workspace_id = parse_string(&params, "workspaceId")?;
+            let path = parse_string(&params, "path")?;
+            let start_line = parse_optional_u32(&params, "startLine").map(|value| value as usize);
+            let end_line = parse_optional_u32(&params, "endLine").map(|value| value as usize);
+            state
+                .read_workspace_file(&workspace_id, &path, start_line, end_line)
+                .await
+        }
+        "grep_workspace" => {
+            let workspace_id = parse_string(&params, "workspaceId")?;
+            let pattern = parse_string(&params, "pattern")?;
+            let regex = RegexBuilder::new(&pattern)
+                .case_insensitive(parse_optional_bool(&params, "caseInsensitive").unwrap_or(false))
+                .build()
+                .map_err(|err| format!("Invalid pattern: {err}"))?;
+            let globs = parse_optional_glob_set(&params, "globs")?;
+            let max_matches = parse_optional_u32(&params, "maxMatches")
+                .map(|value| value as usize)
+                .unwrap_or(DEFAULT_GREP_MAX_MATCHES)
+                .clamp(1, MAX_GREP_MATCHES);
+            let max_matches_per_file = parse_optional_u32(&params, "maxMatchesPerFile")
+                .map(|value| value as usize)
+                .unwrap_or(DEFAULT_GREP_MAX_MATCHES_PER_FILE)
+                .max(1);
+            let options = GrepOptions {
+                regex,
+                globs,
+                max_matches,
+                max_matches_per_file,
+            };
+            state.grep_workspace(&workspace_id, options).await
+        }
+        "replace_in_files" => {
+            let workspace_id = parse_string(&params, "workspaceId")?;
+            let pattern = parse_string(&params, "pattern")?;
+            let regex = Regex::new(&pattern).map_err(|err| format!("Invalid pattern: {err}"))?;
+            let replacement = parse_string(&params, "replacement")?;
+            let targets = match parse_optional_string_array(&params, "paths") {
+                Some(paths) if !paths.is_empty() => ReplaceTargets::Paths(paths),
+                _ => match parse_optional_glob_set(&params, "globs")? {
+                    Some(globs) => ReplaceTargets::Globs(globs),
+                    None => return Err("replace_in_files requires paths or globs".to_string()),
+                },
+            };
+            let options = ReplaceOptions {
+                regex,
+                replacement,
+                targets,
+                dry_run: parse_optional_bool(&params, "dryRun").unwrap_or(true),
+            };
+            state.replace_in_files(&workspace_id, options).await
+        }
+        "list_workspace_tree" => {
+            let workspace_id = parse_string(&params, "workspaceId")?;
+            let path = parse_optional_string(&params, "path");
+            let max_depth = parse_optional_u32(&params, "maxDepth").map(|value| value as usize);
+            state.list_workspace_tree(&workspace_id, path, max_depth).await
+        }
+        "resolve_file_path" => {
+            let workspace_id = parse_string(&params, "workspaceId")?;
+            let path = parse_string(&params, "path")?;
+            state.resolve_file_path(&workspace_id, &path).await
+        }
+        "refresh_remote_state" => {
+            let workspace_id = parse_string(&params, "workspaceId")?;
+            state.refresh_remote_state(&workspace_id).await
+        }
+        "verify_git_repo" => {
+            let workspace_id = parse_string(&params, "workspaceId")?;
+            state.verify_git_repo(&workspace_id).await
+        }
+        "list_git_worktrees" => {
+            let workspace_id = parse_string(&params, "workspaceId")?;
+            state.list_git_worktrees(&workspace_id).await
+        }
+        "get_codex_project_config" => {
+            let workspace_id = parse_string(&params, "workspaceId")?;
+            let entry = {
+                let workspaces = state.workspaces.lock().await;
+                workspaces
+                    .get(&workspace_id)
+                    .cloned()
+                    .ok_or("workspace not found")?
+            };
+            codex_config::read_project_config(&PathBuf::from(&entry.path))
+        }
         "get_app_settings" => {
             let mut settings = state.app_settings.lock().await.clone();
             if let Ok(Some(collab_enabled)) = codex_config::read_collab_enabled() {
@@ -1603,45 +3155,86 @@ async fn handle_rpc_request(
         }
         "start_thread" => {
             let workspace_id = parse_string(&params, "workspaceId")?;
-            state.start_thread(workspace_id).await
+            let cwd = parse_optional_string(&params, "cwd");
+            state.start_thread(workspace_id, cwd).await
         }
         "resume_thread" => {
             let workspace_id = parse_string(&params, "workspaceId")?;
             let thread_id = parse_string(&params, "threadId")?;
             state.resume_thread(workspace_id, thread_id).await
         }
+        "resume_latest_thread" => {
+            let workspace_id = parse_string(&params, "workspaceId")?;
+            state.resume_latest_thread(workspace_id).await
+        }
         "list_threads" => {
             let workspace_id = parse_string(&params, "workspaceId")?;
             let cursor = parse_optional_string(&params, "cursor");
             let limit = parse_optional_u32(&params, "limit");
             state.list_threads(workspace_id, cursor, limit).await
         }
+        "workspace_health" => {
+            let workspace_id = parse_string(&params, "workspaceId")?;
+            state.workspace_health(workspace_id).await
+        }
+        "admin_list_clients" => Ok(json!({ "clients": state.clients.list() })),
+        "admin_method_stats" => {
+            let methods = state
+                .method_stats
+                .lock()
+                .map(|stats| stats.snapshot())
+                .unwrap_or_default();
+            Ok(json!({ "methods": methods }))
+        }
+        "admin_disconnect_client" => {
+            let connection_id = params
+                .get("connectionId")
+                .and_then(|value| value.as_u64())
+                .ok_or("missing or invalid `connectionId`")?;
+            if !state.clients.disconnect(connection_id) {
+                return Err(format!("client not found: {connection_id}"));
+            }
+            Ok(json!({ "ok": true }))
+        }
+        "get_active_turns" => {
+            let workspace_id = parse_string(&params, "workspaceId")?;
+            state.get_active_turns(workspace_id).await
+        }
+        "get_turn_message" => {
+            let workspace_id = parse_string(&params, "workspaceId")?;
+            let thread_id = parse_string(&params, "threadId")?;
+            Ok(state.get_turn_message(&workspace_id, &thread_id))
+        }
         "archive_thread" => {
             let workspace_id = parse_string(&params, "workspaceId")?;
             let thread_id = parse_string(&params, "threadId")?;
             state.archive_thread(workspace_id, thread_id).await
         }
         "send_user_message" => {
-            let workspace_id = parse_string(&params, "workspaceId")?;
-            let thread_id = parse_string(&params, "threadId")?;
             let text = parse_string(&params, "text")?;
-            let model = parse_optional_string(&params, "model");
-            let effort = parse_optional_string(&params, "effort");
-            let access_mode = parse_optional_string(&params, "accessMode");
-            let images = parse_optional_string_array(&params, "images");
-            let collaboration_mode = parse_optional_value(&params, "collaborationMode");
-            state
-                .send_user_message(
-                    workspace_id,
-                    thread_id,
-                    text,
-                    model,
-                    effort,
-                    access_mode,
-                    images,
-                    collaboration_mode,
-                )
-                .await
+            send_user_message_from_params(state, &params, text, client).await
+        }
+        "send_templated_message" => {
+            let workspace_id = parse_string(&params, "workspaceId")?;
+            let template = parse_string(&params, "template")?;
+            let values = match &params {
+                Value::Object(map) => match map.get("params") {
+                    Some(Value::Object(values)) => values.clone(),
+                    _ => Map::new(),
+                },
+                _ => Map::new(),
+            };
+            let text = state
+                .expand_message_template(&workspace_id, &template, &values)
+                .await?;
+            let response = send_user_message_from_params(state, &params, text.clone(), client).await?;
+            Ok(match response {
+                Value::Object(mut map) => {
+                    map.insert("expandedText".to_string(), Value::String(text));
+                    Value::Object(map)
+                }
+                other => other,
+            })
         }
         "turn_interrupt" => {
             let workspace_id = parse_string(&params, "workspaceId")?;
@@ -1660,6 +3253,12 @@ async fn handle_rpc_request(
             let delivery = parse_optional_string(&params, "delivery");
             state.start_review(workspace_id, thread_id, target, delivery).await
         }
+        "codex_raw" => {
+            let workspace_id = parse_string(&params, "workspaceId")?;
+            let method = parse_string(&params, "method")?;
+            let raw_params = parse_optional_value(&params, "params").unwrap_or(Value::Null);
+            state.codex_raw(workspace_id, method, raw_params).await
+        }
         "model_list" => {
             let workspace_id = parse_string(&params, "workspaceId")?;
             state.model_list(workspace_id).await
@@ -1699,7 +3298,8 @@ async fn handle_rpc_request(
 
 async fn forward_events(
     mut rx: broadcast::Receiver<DaemonEvent>,
-    out_tx_events: mpsc::UnboundedSender<String>,
+    out_tx_events: mpsc::Sender<String>,
+    framing: ConnectionFraming,
 ) {
     loop {
         let event = match rx.recv().await {
@@ -1708,28 +3308,276 @@ async fn forward_events(
             Err(broadcast::error::RecvError::Closed) => break,
         };
 
-        let Some(payload) = build_event_notification(event) else {
+        let Some(payload) = build_event_notification(event, framing.get()) else {
             continue;
         };
 
-        if out_tx_events.send(payload).is_err() {
+        if out_tx_events.send(payload).await.is_err() {
             break;
         }
     }
 }
 
-async fn handle_client(
-    socket: TcpStream,
+/// Forwards a lossless client's dedicated queue. Waiting on the socket only
+/// backs up this client's queue; if it fills anyway the queue is closed, and
+/// the client is told and disconnected rather than silently missing events.
+async fn forward_lossless_events(
+    mut rx: mpsc::Receiver<DaemonEvent>,
+    out_tx_events: mpsc::Sender<String>,
+    disconnect: Arc<Notify>,
+    framing: ConnectionFraming,
+) {
+    while let Some(event) = rx.recv().await {
+        let Some(payload) = build_event_notification(event, framing.get()) else {
+            continue;
+        };
+        if out_tx_events.send(payload).await.is_err() {
+            return;
+        }
+    }
+    let overflow = DaemonEvent::Notification {
+        method: "event-queue-overflow",
+        params: json!({ "capacity": CLIENT_QUEUE_CAPACITY }),
+    };
+    if let Some(payload) = build_event_notification(overflow, framing.get()) {
+        let _ = out_tx_events.send(payload).await;
+    }
+    disconnect.notify_one();
+}
+
+fn event_delivery_name(lossless: bool) -> &'static str {
+    if lossless {
+        "lossless"
+    } else {
+        "broadcast"
+    }
+}
+
+/// Reads `eventDelivery` from `auth`/`set_client_info` params.
+fn parse_event_delivery(params: &Value) -> Result<Option<bool>, String> {
+    match parse_optional_string(params, "eventDelivery").as_deref() {
+        None => Ok(None),
+        Some("broadcast") => Ok(Some(false)),
+        Some("lossless") => Ok(Some(true)),
+        Some(other) => Err(format!(
+            "invalid eventDelivery: {other} (expected broadcast or lossless)"
+        )),
+    }
+}
+
+/// The task feeding events to one connection, from either the shared
+/// broadcast (events are skipped if the client lags behind) or the
+/// connection's own lossless queue.
+struct EventForwarding {
+    connection_id: u64,
+    events: broadcast::Sender<DaemonEvent>,
+    queues: ClientQueues<DaemonEvent>,
+    out_tx_events: mpsc::Sender<String>,
+    disconnect: Arc<Notify>,
+    framing: ConnectionFraming,
+    task: Option<tokio::task::JoinHandle<()>>,
+    lossless: bool,
+}
+
+impl EventForwarding {
+    fn start(&mut self, lossless: bool) {
+        if self.task.is_some() && self.lossless == lossless {
+            return;
+        }
+        self.stop();
+        self.lossless = lossless;
+        let out_tx_events = self.out_tx_events.clone();
+        let task = if lossless {
+            let rx = self.queues.register(self.connection_id, CLIENT_QUEUE_CAPACITY);
+            tokio::spawn(forward_lossless_events(
+                rx,
+                out_tx_events,
+                Arc::clone(&self.disconnect),
+                self.framing.clone(),
+            ))
+        } else {
+            tokio::spawn(forward_events(
+                self.events.subscribe(),
+                out_tx_events,
+                self.framing.clone(),
+            ))
+        };
+        self.task = Some(task);
+    }
+
+    fn stop(&mut self) {
+        // Abort before closing the queue so the forwarder does not mistake
+        // the close for an overflow.
+        if let Some(task) = self.task.take() {
+            task.abort();
+        }
+        self.queues.remove(self.connection_id);
+    }
+}
+
+/// Reads the next request line, or returns `None` once the connection is
+/// closed or an admin disconnects it.
+async fn next_line_or_disconnect<R: AsyncBufRead + Unpin>(
+    lines: &mut Lines<R>,
+    disconnect: &Notify,
+) -> Option<String> {
+    let disconnected = disconnect.notified();
+    tokio::pin!(disconnected);
+    let next_line = lines.next_line();
+    tokio::pin!(next_line);
+    std::future::poll_fn(|cx| {
+        if disconnected.as_mut().poll(cx).is_ready() {
+            return Poll::Ready(None);
+        }
+        next_line.as_mut().poll(cx).map(|line| line.ok().flatten())
+    })
+    .await
+}
+
+/// A request read from a connection, registered for `cancel` when it has an
+/// `id`.
+struct PendingRequest {
+    method: String,
+    params: Value,
+    id: Option<Value>,
+    registration: Option<(u64, oneshot::Receiver<()>)>,
+}
+
+impl PendingRequest {
+    fn new(message: &Value, pending: &PendingRequests) -> Self {
+        let id = parse_request_id(message);
+        let method = message
+            .get("method")
+            .and_then(|value| value.as_str())
+            .unwrap_or("")
+            .to_string();
+        let params = message.get("params").cloned().unwrap_or(Value::Null);
+        let registration = id.as_ref().map(|id| pending.register(id));
+        Self {
+            method,
+            params,
+            id,
+            registration,
+        }
+    }
+}
+
+/// Resolves on Ctrl-C, or on SIGTERM on Unix.
+async fn shutdown_signal() {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+        if let Ok(mut terminate) = signal(SignalKind::terminate()) {
+            tokio::select! {
+                _ = tokio::signal::ctrl_c() => {}
+                _ = terminate.recv() => {}
+            }
+            return;
+        }
+    }
+    let _ = tokio::signal::ctrl_c().await;
+}
+
+/// Dispatches one request and records its latency, returning the request
+/// `id` with the outcome.
+async fn run_request(
+    state: &DaemonState,
+    request: PendingRequest,
+    client: &ClientInfo,
+    pending: &PendingRequests,
+    slots: &Semaphore,
+) -> (Option<Value>, Result<Value, String>) {
+    let PendingRequest {
+        method,
+        params,
+        id,
+        registration,
+    } = request;
+    let started = Instant::now();
+    let (ticket, cancelled) = registration.unzip();
+    // Dropping the request future on cancel stops its work at the next await
+    // point, so handlers undo shared state in guards (see `TurnReservation`)
+    // rather than in calls after an await.
+    let request = handle_rpc_request(state, &method, params, client);
+    let result = run_when_admitted(slots, cancelled, request).await;
+    if let (Some(id), Some(ticket)) = (id.as_ref(), ticket) {
+        pending.finish(id, ticket);
+    }
+    let unknown = matches!(&result, Err(message) if message.starts_with("unknown method"));
+    let cancelled = matches!(&result, Err(message) if message == "cancelled");
+    if !unknown && !cancelled {
+        if let Ok(mut stats) = state.method_stats.lock() {
+            stats.record(&method, started.elapsed(), result.is_ok());
+        }
+    }
+    (id, result)
+}
+
+/// Runs a batch (a JSON array of requests on one line) concurrently and
+/// returns the responses in request order. Requests without an `id` get no
+/// entry, and connection-level methods cannot be batched.
+async fn run_batch(
+    state: Arc<DaemonState>,
+    requests: Vec<PendingRequest>,
+    client: ClientInfo,
+    pending: PendingRequests,
+    slots: Arc<Semaphore>,
+    framing: Framing,
+) -> Vec<Value> {
+    let mut tasks = tokio::task::JoinSet::new();
+    let mut responses: Vec<Option<Value>> = Vec::new();
+    for (index, request) in requests.into_iter().enumerate() {
+        responses.push(None);
+        if CONNECTION_METHODS.contains(&request.method.as_str()) {
+            let message = format!("`{}` cannot be batched", request.method);
+            responses[index] = request.id.map(|id| framing.error(&id, &message));
+            continue;
+        }
+        let state = Arc::clone(&state);
+        let client = client.clone();
+        let pending = pending.clone();
+        let slots = Arc::clone(&slots);
+        tasks.spawn(async move {
+            let (id, result) = run_request(&state, request, &client, &pending, &slots).await;
+            let response = id.map(|id| match result {
+                Ok(result) => framing.result(&id, result),
+                Err(message) => framing.error(&id, &message),
+            });
+            (index, response)
+        });
+    }
+    while let Some(joined) = tasks.join_next().await {
+        if let Ok((index, response)) = joined {
+            responses[index] = response;
+        }
+    }
+    responses.into_iter().flatten().collect()
+}
+
+async fn handle_client<R, W>(
+    reader: R,
+    mut writer: W,
+    addr: String,
     config: Arc<DaemonConfig>,
     state: Arc<DaemonState>,
     events: broadcast::Sender<DaemonEvent>,
-) {
-    let (reader, mut writer) = socket.into_split();
+) where
+    R: AsyncRead + Unpin + Send + 'static,
+    W: AsyncWrite + Unpin + Send + 'static,
+{
     let mut lines = BufReader::new(reader).lines();
 
     let (out_tx, mut out_rx) = mpsc::unbounded_channel::<String>();
-    let write_task = tokio::spawn(async move {
-        while let Some(message) = out_rx.recv().await {
+    // Events go through a bounded channel so a slow socket pushes back on
+    // the event forwarder instead of buffering without limit.
+    let (out_tx_events, mut out_rx_events) = mpsc::channel::<String>(EVENT_WRITE_BUFFER);
+    let mut write_task = tokio::spawn(async move {
+        loop {
+            let message = tokio::select! {
+                Some(message) = out_rx.recv() => message,
+                Some(message) = out_rx_events.recv() => message,
+                else => break,
+            };
             if writer.write_all(message.as_bytes()).await.is_err() {
                 break;
             }
@@ -1737,18 +3585,43 @@ async fn handle_client(
                 break;
             }
         }
+        let _ = writer.shutdown().await;
     });
 
     let mut authenticated = config.token.is_none();
-    let mut events_task: Option<tokio::task::JoinHandle<()>> = None;
+    let mut client = ClientInfo::default();
+    let disconnect = Arc::new(Notify::new());
+    let connection_id = state.clients.register(addr.clone(), Arc::clone(&disconnect));
+    let in_flight = Arc::new(Semaphore::new(MAX_IN_FLIGHT_REQUESTS));
+    let pending = PendingRequests::default();
+    let connection_framing = ConnectionFraming::default();
+    let mut closing = false;
+    let mut forwarding = EventForwarding {
+        connection_id,
+        events,
+        queues: state.event_sink.lossless.clone(),
+        out_tx_events,
+        disconnect: Arc::clone(&disconnect),
+        framing: connection_framing.clone(),
+        task: None,
+        lossless: false,
+    };
 
     if authenticated {
-        let rx = events.subscribe();
-        let out_tx_events = out_tx.clone();
-        events_task = Some(tokio::spawn(forward_events(rx, out_tx_events)));
+        state.event_sink.emit(DaemonEvent::ClientConnected {
+            connection_id,
+            address: addr.clone(),
+            timestamp: now_millis(),
+            client: client.clone(),
+        });
+        forwarding.start(false);
+        state.clients.update(connection_id, |connection| {
+            connection.authenticated_at = Some(connection.connected_at);
+            connection.subscribed = true;
+        });
     }
 
-    while let Ok(Some(line)) = lines.next_line().await {
+    while let Some(line) = next_line_or_disconnect(&mut lines, &disconnect).await {
         let line = line.trim();
         if line.is_empty() {
             continue;
@@ -1756,10 +3629,73 @@ async fn handle_client(
 
         let message: Value = match serde_json::from_str(line) {
             Ok(value) => value,
-            Err(_) => continue,
+            Err(err) => {
+                // The id of an unparseable line is unknown, so the error
+                // goes back with a null id.
+                let message = format!("parse error: {err}");
+                let error = connection_framing.get().error(&Value::Null, &message);
+                let _ = out_tx.send(error.to_string());
+                continue;
+            }
         };
 
-        let id = message.get("id").and_then(|value| value.as_u64());
+        if let Value::Array(batch) = message {
+            let framing = connection_framing.detect(batch.first().unwrap_or(&Value::Null));
+            if !authenticated {
+                let errors: Vec<Value> = batch
+                    .iter()
+                    .filter_map(parse_request_id)
+                    .map(|id| framing.error(&id, "unauthorized"))
+                    .collect();
+                if !errors.is_empty() {
+                    let _ = out_tx.send(Value::Array(errors).to_string());
+                }
+                continue;
+            }
+            if batch.is_empty() {
+                if framing == Framing::JsonRpc {
+                    let error = framing.error(&Value::Null, "invalid request");
+                    let _ = out_tx.send(error.to_string());
+                }
+                continue;
+            }
+            if batch.len() > MAX_IN_FLIGHT_REQUESTS {
+                let message = format!(
+                    "batch too large: {} requests (limit {MAX_IN_FLIGHT_REQUESTS})",
+                    batch.len()
+                );
+                let errors: Vec<Value> = batch
+                    .iter()
+                    .filter_map(parse_request_id)
+                    .map(|id| framing.error(&id, &message))
+                    .collect();
+                if !errors.is_empty() {
+                    let _ = out_tx.send(Value::Array(errors).to_string());
+                }
+                continue;
+            }
+            // Register every request up front so `cancel` can reach them.
+            let requests: Vec<PendingRequest> = batch
+                .iter()
+                .map(|request| PendingRequest::new(request, &pending))
+                .collect();
+            let state = Arc::clone(&state);
+            let client = client.clone();
+            let out_tx = out_tx.clone();
+            let pending = pending.clone();
+            let in_flight = Arc::clone(&in_flight);
+            tokio::spawn(async move {
+                let responses =
+                    run_batch(state, requests, client, pending, in_flight, framing).await;
+                if !responses.is_empty() {
+                    let _ = out_tx.send(Value::Array(responses).to_string());
+                }
+            });
+            continue;
+        }
+
+        let framing = connection_framing.detect(&message);
+        let id = parse_request_id(&message);
         let method = message
             .get("method")
             .and_then(|value| value.as_str())
@@ -1767,9 +3703,16 @@ async fn handle_client(
             .to_string();
         let params = message.get("params").cloned().unwrap_or(Value::Null);
 
+        if framing == Framing::JsonRpc && method.is_empty() {
+            if let Some(response) = build_error_response(framing, id.as_ref(), "invalid request") {
+                let _ = out_tx.send(response);
+            }
+            continue;
+        }
+
         if !authenticated {
             if method != "auth" {
-                if let Some(response) = build_error_response(id, "unauthorized") {
+                if let Some(response) = build_error_response(framing, id.as_ref(), "unauthorized") {
                     let _ = out_tx.send(response);
                 }
                 continue;
@@ -1778,40 +3721,417 @@ async fn handle_client(
             let expected = config.token.clone().unwrap_or_default();
             let provided = parse_auth_token(&params).unwrap_or_default();
             if expected != provided {
-                if let Some(response) = build_error_response(id, "invalid token") {
+                let response = build_error_response(framing, id.as_ref(), "invalid token");
+                if let Some(response) = response {
                     let _ = out_tx.send(response);
                 }
                 continue;
             }
 
+            let lossless = match parse_event_delivery(&params) {
+                Ok(lossless) => lossless.unwrap_or(false),
+                Err(message) => {
+                    if let Some(response) = build_error_response(framing, id.as_ref(), &message) {
+                        let _ = out_tx.send(response);
+                    }
+                    continue;
+                }
+            };
             authenticated = true;
-            if let Some(response) = build_result_response(id, json!({ "ok": true })) {
+            client.update_from_params(&params);
+            let response = build_result_response(framing, id.as_ref(), json!({ "ok": true }));
+            if let Some(response) = response {
                 let _ = out_tx.send(response);
             }
 
-            let rx = events.subscribe();
-            let out_tx_events = out_tx.clone();
-            events_task = Some(tokio::spawn(forward_events(rx, out_tx_events)));
+            let authenticated_at = now_millis();
+            // Only the reported identity is broadcast, never the token.
+            state.event_sink.emit(DaemonEvent::ClientConnected {
+                connection_id,
+                address: addr.clone(),
+                timestamp: authenticated_at,
+                client: client.clone(),
+            });
+            forwarding.start(lossless);
+            let info = client.clone();
+            state.clients.update(connection_id, |connection| {
+                connection.authenticated_at = Some(authenticated_at);
+                connection.subscribed = true;
+                connection.lossless = lossless;
+                connection.info = info;
+            });
 
             continue;
         }
 
-        let client_version = format!("daemon-{}", env!("CARGO_PKG_VERSION"));
-        let result = handle_rpc_request(&state, &method, params, client_version).await;
-        let response = match result {
-            Ok(result) => build_result_response(id, result),
-            Err(message) => build_error_response(id, &message),
-        };
-        if let Some(response) = response {
-            let _ = out_tx.send(response);
+        // Without a token there is no auth handshake, so `auth` only updates
+        // the client identity.
+        if method == "set_client_info" || method == "auth" {
+            match parse_event_delivery(&params) {
+                Ok(Some(lossless)) => forwarding.start(lossless),
+                Ok(None) => {}
+                Err(message) => {
+                    if let Some(response) = build_error_response(framing, id.as_ref(), &message) {
+                        let _ = out_tx.send(response);
+                    }
+                    continue;
+                }
+            }
+            client.update_from_params(&params);
+            let info = client.clone();
+            let lossless = forwarding.lossless;
+            state.clients.update(connection_id, |connection| {
+                connection.info = info;
+                connection.lossless = lossless;
+            });
+            let result = json!({
+                "ok": true,
+                "client": client.to_value(),
+                "eventDelivery": event_delivery_name(lossless),
+            });
+            if let Some(response) = build_result_response(framing, id.as_ref(), result) {
+                let _ = out_tx.send(response);
+            }
+            continue;
+        }
+
+        // `cancel` stops another in-flight request of this connection, which
+        // then answers with a "cancelled" error.
+        if method == "cancel" {
+            let response = match params.get("requestId") {
+                Some(request_id @ (Value::Number(_) | Value::String(_))) => {
+                    let cancelled = pending.cancel(request_id);
+                    build_result_response(framing, id.as_ref(), json!({ "cancelled": cancelled }))
+                }
+                _ => build_error_response(framing, id.as_ref(), "missing or invalid `requestId`"),
+            };
+            if let Some(response) = response {
+                let _ = out_tx.send(response);
+            }
+            continue;
         }
+
+        // `close` ends the connection on the client's request: in-flight
+        // requests are cancelled and events stop before the acknowledgement,
+        // then the socket is closed once queued responses are written. Sent
+        // without an `id` it works as a goodbye notification.
+        if method == "close" {
+            let cancelled = pending.cancel_all();
+            forwarding.stop();
+            let result = json!({ "ok": true, "cancelled": cancelled });
+            if let Some(response) = build_result_response(framing, id.as_ref(), result) {
+                let _ = out_tx.send(response);
+            }
+            closing = true;
+            break;
+        }
+
+        // Each request runs in its own task so a slow one does not hold up
+        // the rest of the connection; responses are correlated by `id`. The
+        // task waits for an in-flight slot itself, so this loop keeps reading
+        // and a `cancel` or `close` still gets through on a full connection.
+        let in_flight = Arc::clone(&in_flight);
+        let state = Arc::clone(&state);
+        let client = client.clone();
+        let out_tx = out_tx.clone();
+        let pending = pending.clone();
+        // Register before spawning so a `cancel` on the next line finds it.
+        let registration = id.as_ref().map(|id| pending.register(id));
+        tokio::spawn(async move {
+            let request = PendingRequest {
+                method,
+                params,
+                id,
+                registration,
+            };
+            let (id, result) = run_request(&state, request, &client, &pending, &in_flight).await;
+            let response = match result {
+                Ok(result) => build_result_response(framing, id.as_ref(), result),
+                Err(message) => build_error_response(framing, id.as_ref(), &message),
+            };
+            if let Some(response) = response {
+                let _ = out_tx.send(response);
+            }
+        });
     }
 
+    state.clients.remove(connection_id);
     drop(out_tx);
-    if let Some(task) = events_task {
-        task.abort();
+    forwarding.stop();
+    if authenticated {
+        state.event_sink.emit(DaemonEvent::ClientDisconnected {
+            connection_id,
+            address: addr,
+            timestamp: now_millis(),
+        });
+    }
+    if closing {
+        // Dropping the event sender lets the writer finish once the
+        // remaining responses are flushed.
+        drop(forwarding);
+        if tokio::time::timeout(CLOSE_FLUSH_TIMEOUT, &mut write_task)
+            .await
+            .is_err()
+        {
+            write_task.abort();
+        }
+    } else {
+        write_task.abort();
+    }
+}
+
+async fn fetch_github_issues(repo_path: &PathBuf) -> Result<Vec<GitHubIssue>, String> {
+    let output = Command::new("gh")
+        .envs(proxy_env())
+        .args(["issue", "list", "--limit", "50", "--json", "number,title,url,updatedAt"])
+        .current_dir(repo_path)
+        .output()
+        .await
+        .map_err(|e| format!("Failed to run gh: {e}"))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let detail = stderr.trim();
+        if detail.is_empty() {
+            return Err("GitHub CLI command failed.".to_string());
+        }
+        return Err(detail.to_string());
+    }
+    serde_json::from_slice(&output.stdout).map_err(|e| e.to_string())
+}
+
+/// Polls open issues for workspaces with `watchGithubIssues` enabled and emits
+/// `github-issue-new` for numbers not seen before.
+async fn run_github_issue_watch(state: Arc<DaemonState>) {
+    let seen_path = state.data_dir.join("github-issues-seen.json");
+    let mut seen = SeenIssues::load(&seen_path);
+    let mut last_polled: HashMap<String, Instant> = HashMap::new();
+    loop {
+        let watched: Vec<WorkspaceEntry> = {
+            let workspaces = state.workspaces.lock().await;
+            workspaces
+                .values()
+                .filter(|entry| entry.settings.watch_github_issues)
+                .cloned()
+                .collect()
+        };
+        let mut polled_any = false;
+        for entry in watched {
+            let interval = issue_poll_interval(entry.settings.github_issue_poll_seconds);
+            if last_polled
+                .get(&entry.id)
+                .is_some_and(|polled_at| polled_at.elapsed() < interval)
+            {
+                continue;
+            }
+            last_polled.insert(entry.id.clone(), Instant::now());
+            let issues = match fetch_github_issues(&PathBuf::from(&entry.path)).await {
+                Ok(issues) => issues,
+                Err(err) => {
+                    eprintln!("github issue watch failed for {}: {err}", entry.name);
+                    continue;
+                }
+            };
+            polled_any = true;
+            let numbers: Vec<u64> = issues.iter().map(|issue| issue.number).collect();
+            let fresh = seen.observe(&entry.id, &numbers);
+            for issue in issues.iter().filter(|issue| fresh.contains(&issue.number)) {
+                state.event_sink.emit_notification(
+                    "github-issue-new",
+                    json!({ "workspaceId": entry.id, "issue": issue }),
+                );
+            }
+        }
+        if polled_any {
+            if let Err(err) = seen.save(&seen_path) {
+                eprintln!("failed to persist seen github issues: {err}");
+            }
+        }
+        tokio::time::sleep(WATCH_TICK).await;
+    }
+}
+
+struct RemoteState {
+    branch: String,
+    upstream: String,
+    ahead: usize,
+    behind: usize,
+}
+
+impl RemoteState {
+    fn to_value(&self) -> Value {
+        json!({
+            "branch": self.branch,
+            "upstream": self.upstream,
+            "ahead": self.ahead,
+            "behind": self.behind,
+        })
+    }
+}
+
+/// Fetches only the current branch's upstream ref (without touching
+/// `FETCH_HEAD` or other refs) and recomputes ahead/behind. Returns `None`
+/// when the branch has no upstream.
+async fn refresh_remote_state_for_path(repo_path: &PathBuf) -> Result<Option<RemoteState>, String> {
+    let branch = run_git_command(repo_path, &["rev-parse", "--abbrev-ref", "HEAD"]).await?;
+    let Ok(upstream) = run_git_command(
+        repo_path,
+        &["rev-parse", "--abbrev-ref", "--symbolic-full-name", "@{upstream}"],
+    )
+    .await
+    else {
+        return Ok(None);
+    };
+    let remote = run_git_command(repo_path, &["config", &format!("branch.{branch}.remote")]).await?;
+    let merge = run_git_command(repo_path, &["config", &format!("branch.{branch}.merge")]).await?;
+    if remote != "." {
+        run_git_command(
+            repo_path,
+            &["fetch", "--quiet", "--no-tags", "--no-write-fetch-head", &remote, &merge],
+        )
+        .await?;
+    }
+    let counts = run_git_command(
+        repo_path,
+        &["rev-list", "--left-right", "--count", "HEAD...@{upstream}"],
+    )
+    .await?;
+    let (ahead, behind) =
+        parse_ahead_behind(&counts).ok_or_else(|| format!("Unexpected rev-list output: {counts}"))?;
+    Ok(Some(RemoteState {
+        branch,
+        upstream,
+        ahead,
+        behind,
+    }))
+}
+
+/// Connects every saved workspace at startup, at most `concurrency` spawns at
+/// a time, and reports each result as an `auto-connect-progress` event.
+async fn auto_connect_workspaces(state: Arc<DaemonState>, concurrency: usize) {
+    let mut ids: Vec<(String, String)> = {
+        let workspaces = state.workspaces.lock().await;
+        workspaces
+            .values()
+            .map(|entry| (entry.name.clone(), entry.id.clone()))
+            .collect()
+    };
+    ids.sort();
+    let total = ids.len();
+    let semaphore = Arc::new(Semaphore::new(concurrency));
+    let completed = Arc::new(AtomicUsize::new(0));
+    let client_version = ClientInfo::default().app_server_version();
+    for (_, workspace_id) in ids {
+        let state = Arc::clone(&state);
+        let semaphore = Arc::clone(&semaphore);
+        let completed = Arc::clone(&completed);
+        let client_version = client_version.clone();
+        tokio::spawn(async move {
+            let Ok(_permit) = semaphore.acquire_owned().await else {
+                return;
+            };
+            state.event_sink.emit_notification(
+                "auto-connect-progress",
+                json!({ "workspaceId": workspace_id, "status": "connecting", "total": total }),
+            );
+            let result = state
+                .connect_workspace(workspace_id.clone(), client_version)
+                .await;
+            let completed = completed.fetch_add(1, Ordering::SeqCst) + 1;
+            let (status, error) = match result {
+                Ok(()) => ("connected", None),
+                Err(err) => {
+                    eprintln!("auto-connect failed for {workspace_id}: {err}");
+                    ("failed", Some(err))
+                }
+            };
+            state.event_sink.emit_notification(
+                "auto-connect-progress",
+                json!({
+                    "workspaceId": workspace_id,
+                    "status": status,
+                    "error": error,
+                    "completed": completed,
+                    "total": total,
+                }),
+            );
+        });
+    }
+}
+
+/// Fetches connected workspaces with `watchUpstream` enabled and emits
+/// `branch-diverged` whenever the branch falls further behind its upstream.
+async fn run_upstream_watch(state: Arc<DaemonState>) {
+    let mut tracker = BehindTracker::default();
+    let mut last_polled: HashMap<String, Instant> = HashMap::new();
+    loop {
+        let connected: Vec<String> = state.sessions.lock().await.keys().cloned().collect();
+        let watched: Vec<WorkspaceEntry> = {
+            let workspaces = state.workspaces.lock().await;
+            workspaces
+                .values()
+                .filter(|entry| entry.settings.watch_upstream && connected.contains(&entry.id))
+                .cloned()
+                .collect()
+        };
+        for entry in watched {
+            let interval = upstream_poll_interval(entry.settings.upstream_poll_seconds);
+            if last_polled
+                .get(&entry.id)
+                .is_some_and(|polled_at| polled_at.elapsed() < interval)
+            {
+                continue;
+            }
+            last_polled.insert(entry.id.clone(), Instant::now());
+            let remote = match refresh_remote_state_for_path(&PathBuf::from(&entry.path)).await {
+                Ok(Some(remote)) => remote,
+                Ok(None) => continue,
+                Err(err) => {
+                    eprintln!("upstream watch fetch failed for {}: {err}", entry.name);
+                    continue;
+                }
+            };
+            if let Some(previous_behind) = tracker.observe(&entry.id, remote.behind) {
+                let mut params = remote.to_value();
+                params["workspaceId"] = json!(entry.id);
+                params["previousBehind"] = json!(previous_behind);
+                state.event_sink.emit_notification("branch-diverged", params);
+            }
+        }
+        tokio::time::sleep(WATCH_TICK).await;
+    }
+}
+
+/// Drops sessions whose app-server exited on its own and emits
+/// `workspace-connection-changed` with reason `exited`.
+async fn run_session_watch(state: Arc<DaemonState>) {
+    loop {
+        tokio::time::sleep(SESSION_WATCH_TICK).await;
+        let sessions: Vec<(String, Arc<WorkspaceSession>)> = state
+            .sessions
+            .lock()
+            .await
+            .iter()
+            .map(|(id, session)| (id.clone(), Arc::clone(session)))
+            .collect();
+        for (id, session) in sessions {
+            let exited = matches!(session.child.lock().await.try_wait(), Ok(Some(_)));
+            if !exited {
+                continue;
+            }
+            let removed = {
+                let mut sessions = state.sessions.lock().await;
+                let current = sessions.get(&id).is_some_and(|live| Arc::ptr_eq(live, &session));
+                current && sessions.remove(&id).is_some()
+            };
+            if removed {
+                state.event_sink.active_turns.clear(&id);
+                if let Ok(mut messages) = state.event_sink.turn_messages.lock() {
+                    messages.clear(&id);
+                }
+                state.event_sink.emit_connection_changed(&id, false, "exited");
+            }
+        }
     }
-    write_task.abort();
 }
 
 fn main() {
@@ -1832,31 +4152,113 @@ fn main() {
         let (events_tx, _events_rx) = broadcast::channel::<DaemonEvent>(2048);
         let event_sink = DaemonEventSink {
             tx: events_tx.clone(),
+            lossless: ClientQueues::default(),
+            active_turns: ActiveTurns::default(),
+            turn_messages: Arc::default(),
         };
         let state = Arc::new(DaemonState::load(&config, event_sink));
         let config = Arc::new(config);
+        if let Some(path) = config.event_log.clone() {
+            let log = EventLog::new(path, config.log_rotation, config.log_prompts);
+            tokio::spawn(run_event_log(log, events_tx.subscribe()));
+        }
+        tokio::spawn(run_github_issue_watch(Arc::clone(&state)));
+        tokio::spawn(run_upstream_watch(Arc::clone(&state)));
+        tokio::spawn(run_session_watch(Arc::clone(&state)));
+        if config.auto_connect {
+            tokio::spawn(auto_connect_workspaces(
+                Arc::clone(&state),
+                config.spawn_concurrency,
+            ));
+        }
+
+        let data_dir = state
+            .storage_path
+            .parent()
+            .unwrap_or(&state.storage_path)
+            .display()
+            .to_string();
+
+        #[cfg(unix)]
+        if let Some(path) = config.socket.clone() {
+            if let Err(err) = unix_socket::remove_stale_socket(&path) {
+                eprintln!("{err}");
+                std::process::exit(1);
+            }
+            let listener = unix_socket::bind_owner_only(&path).and_then(|listener| {
+                tokio::net::UnixListener::from_std(listener).map_err(|err| err.to_string())
+            });
+            let listener = match listener {
+                Ok(listener) => listener,
+                Err(err) => {
+                    eprintln!("{err}");
+                    std::process::exit(1);
+                }
+            };
+            eprintln!(
+                "codex-monitor-daemon listening on {} (data dir: {data_dir})",
+                path.display()
+            );
+            let peer = format!("unix:{}", path.display());
+            let accept_loop = async {
+                loop {
+                    match listener.accept().await {
+                        Ok((stream, _)) => {
+                            let (reader, writer) = stream.into_split();
+                            let peer = peer.clone();
+                            let config = Arc::clone(&config);
+                            let state = Arc::clone(&state);
+                            let events = events_tx.clone();
+                            tokio::spawn(async move {
+                                handle_client(reader, writer, peer, config, state, events)
+                                    .await;
+                            });
+                        }
+                        Err(_) => continue,
+                    }
+                }
+            };
+            tokio::select! {
+                _ = accept_loop => {}
+                _ = shutdown_signal() => {}
+            }
+            // Unlike a TCP port, the socket file outlives the process.
+            let _ = std::fs::remove_file(&path);
+            return;
+        }
 
         let listener = TcpListener::bind(config.listen)
             .await
             .unwrap_or_else(|err| panic!("failed to bind {}: {err}", config.listen));
         eprintln!(
-            "codex-monitor-daemon listening on {} (data dir: {})",
+            "codex-monitor-daemon listening on {}{} (data dir: {data_dir})",
             config.listen,
-            state
-                .storage_path
-                .parent()
-                .unwrap_or(&state.storage_path)
-                .display()
+            if config.tls.is_some() { " with TLS" } else { "" }
         );
 
         loop {
             match listener.accept().await {
-                Ok((socket, _addr)) => {
+                Ok((socket, addr)) => {
                     let config = Arc::clone(&config);
                     let state = Arc::clone(&state);
                     let events = events_tx.clone();
                     tokio::spawn(async move {
-                        handle_client(socket, config, state, events).await;
+                        let addr = addr.to_string();
+                        let Some(acceptor) = config.tls.clone() else {
+                            let (reader, writer) = socket.into_split();
+                            handle_client(reader, writer, addr, config, state, events).await;
+                            return;
+                        };
+                        // Plaintext clients fail the handshake and are dropped here.
+                        let handshake = acceptor.accept(socket);
+                        let stream = match tokio::time::timeout(TLS_HANDSHAKE_TIMEOUT, handshake)
+                            .await
+                        {
+                            Ok(Ok(stream)) => stream,
+                            _ => return,
+                        };
+                        let (reader, writer) = tokio::io::split(stream);
+                        handle_client(reader, writer, addr, config, state, events).await;
                     });
                 }
                 Err(_) => continue,
@@ -12,8 +12,11 @@ use tokio::time::timeout;
 pub(crate) use crate::backend::app_server::WorkspaceSession;
 use crate::backend::app_server::{
     build_codex_command_with_bin, build_codex_path_env, check_codex_installation,
-    spawn_workspace_session as spawn_workspace_session_inner,
+    resolve_workspace_cwd, spawn_workspace_session as spawn_workspace_session_inner,
 };
+use crate::backend::turn_policy::TurnPolicy;
+use crate::backend::workspace_paths::resolve_attachment_path;
+use crate::codex_config;
 use crate::codex_home::{resolve_default_codex_home, resolve_workspace_codex_home};
 use crate::event_sink::TauriEventSink;
 use crate::remote_backend;
@@ -134,6 +137,7 @@ pub(crate) async fn codex_doctor(
 #[tauri::command]
 pub(crate) async fn start_thread(
     workspace_id: String,
+    cwd: Option<String>,
     state: State<'_, AppState>,
     app: AppHandle,
 ) -> Result<Value, String> {
@@ -142,7 +146,7 @@ pub(crate) async fn start_thread(
             &*state,
             app,
             "start_thread",
-            json!({ "workspaceId": workspace_id }),
+            json!({ "workspaceId": workspace_id, "cwd": cwd }),
         )
         .await;
     }
@@ -151,8 +155,9 @@ pub(crate) async fn start_thread(
     let session = sessions
         .get(&workspace_id)
         .ok_or("workspace not connected")?;
+    let cwd = resolve_workspace_cwd(&session.entry.path, cwd.as_deref())?;
     let params = json!({
-        "cwd": session.entry.path,
+        "cwd": cwd,
         "approvalPolicy": "on-request"
     });
     session.send_request("thread/start", params).await
@@ -185,6 +190,32 @@ pub(crate) async fn resume_thread(
     session.send_request("thread/resume", params).await
 }
 
+#[tauri::command]
+pub(crate) async fn resume_latest_thread(
+    workspace_id: String,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<Value, String> {
+    if remote_backend::is_remote_mode(&*state).await {
+        return remote_backend::call_remote(
+            &*state,
+            app,
+            "resume_latest_thread",
+            json!({ "workspaceId": workspace_id }),
+        )
+        .await;
+    }
+
+    let session = {
+        let sessions = state.sessions.lock().await;
+        sessions
+            .get(&workspace_id)
+            .cloned()
+            .ok_or("workspace not connected")?
+    };
+    session.resume_latest_thread().await
+}
+
 #[tauri::command]
 pub(crate) async fn list_threads(
     workspace_id: String,
@@ -251,6 +282,9 @@ pub(crate) async fn send_user_message(
     access_mode: Option<String>,
     images: Option<Vec<String>>,
     collaboration_mode: Option<Value>,
+    cwd: Option<String>,
+    confirm_full_access: Option<bool>,
+    when_busy: Option<String>,
     state: State<'_, AppState>,
     app: AppHandle,
 ) -> Result<Value, String> {
@@ -268,41 +302,36 @@ pub(crate) async fn send_user_message(
                 "accessMode": access_mode,
                 "images": images,
                 "collaborationMode": collaboration_mode,
+                "cwd": cwd,
+                "confirmFullAccess": confirm_full_access,
+                "whenBusy": when_busy,
             }),
         )
         .await;
     }
 
+    let policy_overrides = state.app_settings.lock().await.access_mode_policies.clone();
     let sessions = state.sessions.lock().await;
     let session = sessions
         .get(&workspace_id)
         .ok_or("workspace not connected")?;
-    let access_mode = access_mode.unwrap_or_else(|| "current".to_string());
-    let sandbox_policy = match access_mode.as_str() {
-        "full-access" => json!({
-            "type": "dangerFullAccess"
-        }),
-        "read-only" => json!({
-            "type": "readOnly"
-        }),
-        _ => json!({
-            "type": "workspaceWrite",
-            "writableRoots": [session.entry.path],
-            "networkAccess": true
-        }),
-    };
-
-    let approval_policy = if access_mode == "full-access" {
-        "never"
-    } else {
-        "on-request"
-    };
+    let cwd = resolve_workspace_cwd(&session.entry.path, cwd.as_deref())?;
+    let policy = TurnPolicy::for_access_mode(
+        access_mode.as_deref(),
+        &session.entry.path,
+        &policy_overrides,
+    );
+    policy.ensure_allowed(
+        confirm_full_access.unwrap_or(false),
+        session.entry.settings.allow_full_access,
+    )?;
 
     let trimmed_text = text.trim();
     let mut input: Vec<Value> = Vec::new();
     if !trimmed_text.is_empty() {
         input.push(json!({ "type": "text", "text": trimmed_text }));
     }
+    let attachment_roots = state.app_settings.lock().await.attachment_roots.clone();
     if let Some(paths) = images {
         for path in paths {
             let trimmed = path.trim();
@@ -315,7 +344,8 @@ pub(crate) async fn send_user_message(
             {
                 input.push(json!({ "type": "image", "url": trimmed }));
             } else {
-                input.push(json!({ "type": "localImage", "path": trimmed }));
+                let path = resolve_attachment_path(&session.entry, trimmed, &attachment_roots)?;
+                input.push(json!({ "type": "localImage", "path": path }));
             }
         }
     }
@@ -326,14 +356,15 @@ pub(crate) async fn send_user_message(
     let params = json!({
         "threadId": thread_id,
         "input": input,
-        "cwd": session.entry.path,
-        "approvalPolicy": approval_policy,
-        "sandboxPolicy": sandbox_policy,
+        "cwd": cwd,
+        "approvalPolicy": policy.approval_policy,
+        "sandboxPolicy": policy.sandbox_policy.clone(),
         "model": model,
         "effort": effort,
         "collaborationMode": collaboration_mode,
     });
-    session.send_request("turn/start", params).await
+    let response = session.send_request("turn/start", params).await?;
+    Ok(policy.attach_to_response(response))
 }
 
 #[tauri::command]
@@ -429,6 +460,32 @@ pub(crate) async fn start_review(
         .await
 }
 
+#[tauri::command]
+pub(crate) async fn get_codex_project_config(
+    workspace_id: String,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<Value, String> {
+    if remote_backend::is_remote_mode(&*state).await {
+        return remote_backend::call_remote(
+            &*state,
+            app,
+            "get_codex_project_config",
+            json!({ "workspaceId": workspace_id }),
+        )
+        .await;
+    }
+
+    let entry = {
+        let workspaces = state.workspaces.lock().await;
+        workspaces
+            .get(&workspace_id)
+            .cloned()
+            .ok_or("workspace not found")?
+    };
+    codex_config::read_project_config(&PathBuf::from(&entry.path))
+}
+
 #[tauri::command]
 pub(crate) async fn model_list(
     workspace_id: String,
@@ -1,8 +1,14 @@
 use std::env;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+use serde_json::{json, Map, Value};
 
 const FEATURES_TABLE: &str = "[features]";
+const PROJECT_CONFIG_DIR: &str = ".codex";
+/// Files under a project's `.codex` dir whose contents are returned.
+const PROJECT_CONFIG_FILES: [&str; 1] = ["config.toml"];
+const PROJECT_CONFIG_MAX_BYTES: u64 = 256 * 1024;
 
 pub(crate) fn read_steer_enabled() -> Result<Option<bool>, String> {
     read_feature_flag("steer")
@@ -156,3 +162,146 @@ fn upsert_feature_flag(contents: &str, key: &str, enabled: bool) -> String {
     }
     updated
 }
+
+/// Reads a workspace's `.codex` directory: known files come back with their
+/// raw contents (and `config.toml` with a shallow parse), everything else is
+/// listed by name and size only.
+pub(crate) fn read_project_config(workspace_path: &Path) -> Result<Value, String> {
+    let dir = workspace_path.join(PROJECT_CONFIG_DIR);
+    if !dir.is_dir() {
+        return Ok(json!({
+            "dir": dir.to_string_lossy(),
+            "exists": false,
+            "files": [],
+        }));
+    }
+
+    let mut entries: Vec<_> = fs::read_dir(&dir)
+        .map_err(|err| err.to_string())?
+        .filter_map(|entry| entry.ok())
+        .collect();
+    entries.sort_by_key(|entry| entry.file_name());
+
+    let mut files = Vec::new();
+    for entry in entries {
+        let name = entry.file_name().to_string_lossy().to_string();
+        let Ok(metadata) = entry.metadata() else {
+            continue;
+        };
+        let mut file = json!({
+            "name": name,
+            "isDir": metadata.is_dir(),
+            "size": metadata.len(),
+        });
+        let known = PROJECT_CONFIG_FILES.contains(&name.as_str());
+        if known && metadata.is_file() && metadata.len() <= PROJECT_CONFIG_MAX_BYTES {
+            let contents = fs::read_to_string(entry.path()).map_err(|err| err.to_string())?;
+            if name.ends_with(".toml") {
+                file["parsed"] = parse_shallow_toml(&contents);
+            }
+            file["contents"] = Value::String(contents);
+        }
+        files.push(file);
+    }
+
+    Ok(json!({
+        "dir": dir.to_string_lossy(),
+        "exists": true,
+        "files": files,
+    }))
+}
+
+/// Best-effort TOML reading for display: `key = value` pairs grouped by
+/// `[table]` header, with strings, booleans and numbers decoded and anything
+/// else (arrays, inline tables) kept as its raw text.
+fn parse_shallow_toml(contents: &str) -> Value {
+    let mut root = Map::new();
+    let mut table: Option<String> = None;
+    for line in contents.lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        if trimmed.starts_with('[') && trimmed.ends_with(']') {
+            let name = trimmed.trim_matches(|c| c == '[' || c == ']').trim();
+            table = Some(name.to_string());
+            root.entry(name.to_string())
+                .or_insert_with(|| Value::Object(Map::new()));
+            continue;
+        }
+        let Some((key, value)) = trimmed.split_once('=') else {
+            continue;
+        };
+        let key = key.trim().trim_matches('"').to_string();
+        let value = parse_shallow_toml_value(value.trim());
+        let target = match &table {
+            Some(name) => root
+                .get_mut(name)
+                .and_then(|value| value.as_object_mut()),
+            None => Some(&mut root),
+        };
+        if let Some(target) = target {
+            target.insert(key, value);
+        }
+    }
+    Value::Object(root)
+}
+
+fn parse_shallow_toml_value(raw: &str) -> Value {
+    if let Some(quote) = raw.chars().next().filter(|c| *c == '"' || *c == '\'') {
+        if let Some(end) = raw[1..].find(quote) {
+            return Value::String(raw[1..=end].to_string());
+        }
+    }
+    let raw = raw.split(" #").next().unwrap_or(raw).trim();
+    match raw {
+        "true" => Value::Bool(true),
+        "false" => Value::Bool(false),
+        _ => {
+            if let Ok(number) = raw.parse::<i64>() {
+                return json!(number);
+            }
+            if let Ok(number) = raw.parse::<f64>() {
+                return json!(number);
+            }
+            Value::String(raw.to_string())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shallow_toml_groups_tables_and_decodes_scalars() {
+        let parsed = parse_shallow_toml(
+            "# project overrides\nmodel = \"gpt-5\" # pinned\nmodel_reasoning_effort = 'high'\n\n[features]\nsteer = true\n[sandbox_workspace_write]\nwritable_roots = [\"/tmp\"]\nmax = 3\n",
+        );
+        assert_eq!(parsed["model"], "gpt-5");
+        assert_eq!(parsed["model_reasoning_effort"], "high");
+        assert_eq!(parsed["features"]["steer"], true);
+        assert_eq!(parsed["sandbox_workspace_write"]["writable_roots"], "[\"/tmp\"]");
+        assert_eq!(parsed["sandbox_workspace_write"]["max"], 3);
+    }
+
+    #[test]
+    fn project_config_reads_known_files() {
+        let root = std::env::temp_dir()
+            .join(format!("codex-monitor-project-config-{}", uuid::Uuid::new_v4()));
+        assert_eq!(read_project_config(&root).unwrap()["exists"], false);
+
+        fs::create_dir_all(root.join(".codex")).expect("create .codex");
+        fs::write(root.join(".codex/config.toml"), "model = \"o3\"\n").expect("write config");
+        fs::write(root.join(".codex/notes.bin"), [0u8; 4]).expect("write other file");
+
+        let config = read_project_config(&root).unwrap();
+        assert_eq!(config["exists"], true);
+        assert_eq!(config["files"][0]["name"], "config.toml");
+        assert_eq!(config["files"][0]["parsed"]["model"], "o3");
+        assert_eq!(config["files"][1]["name"], "notes.bin");
+        assert!(config["files"][1].get("contents").is_none());
+
+        let _ = fs::remove_dir_all(&root);
+    }
+}
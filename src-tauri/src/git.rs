@@ -1,26 +1,49 @@
-use std::path::{Path, PathBuf};
+use std::collections::HashSet;
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
 
 use git2::{BranchType, DiffOptions, Repository, Sort, Status, StatusOptions};
 use serde_json::json;
-use tauri::State;
+use tauri::{AppHandle, Emitter, State};
 use tokio::process::Command;
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
 
+use crate::backend::git_diffs::{diff_digest_for_path, git_diffs_for_path};
+use crate::backend::proxy_env::proxy_env;
+use crate::backend::word_diff::word_diff;
+use crate::backend::workspace_paths::resolve_git_root;
 use crate::git_utils::{
     checkout_branch, commit_to_entry, diff_patch_to_string, diff_stats_for_path,
-    list_git_roots as scan_git_roots, parse_github_repo, resolve_git_root,
+    list_git_roots as scan_git_roots, parse_github_repo,
 };
 use crate::state::AppState;
 use crate::types::{
-    BranchInfo, GitCommitDiff, GitFileDiff, GitFileStatus, GitHubIssue, GitHubIssuesResponse,
-    GitHubPullRequest, GitHubPullRequestComment, GitHubPullRequestDiff,
-    GitHubPullRequestsResponse, GitLogResponse,
+    BranchInfo, GitBlameHunk, GitBlobDetails, GitCommitDetails, GitCommitDiff, GitDiffHunk,
+    GitDiffLine, GitDiffSpan, GitFileDiff, GitFileStatus, GitHubIssue, GitHubIssuesResponse,
+    GitHubPullRequest, GitHubPullRequestComment, GitHubPullRequestDiff, GitHubPullRequestsResponse,
+    GitIdentity, GitLogResponse, GitMergeResult, GitPushResult, GitRebaseResult, GitResolvedRef,
+    GitShowResult, GitStashApplyResult, GitStashEntry, GitStructuredFileDiff, GitTagDetails,
+    GitTreeDetails,
 };
 use crate::utils::normalize_git_path;
 
 const INDEX_SKIP_WORKTREE_FLAG: u16 = 0x4000;
+const DEFAULT_MAX_FILES_FOR_STATS: usize = 2000;
+/// Repositories whose status `get_git_status_batch` computes at once.
+const GIT_STATUS_BATCH_CONCURRENCY: usize = 4;
+/// Hunks with more lines than this skip word-level highlighting.
+const MAX_WORD_DIFF_HUNK_LINES: usize = 200;
 
 async fn run_git_command(repo_root: &Path, args: &[&str]) -> Result<(), String> {
+    run_git_command_output(repo_root, args).await.map(|_| ())
+}
+
+/// Like `run_git_command`, but returns stdout followed by stderr on success
+/// for commands (such as `push`) that report progress on stderr.
+async fn run_git_command_output(repo_root: &Path, args: &[&str]) -> Result<String, String> {
     let output = Command::new("git")
+        .envs(proxy_env())
         .args(args)
         .current_dir(repo_root)
         .output()
@@ -28,7 +51,9 @@ async fn run_git_command(repo_root: &Path, args: &[&str]) -> Result<(), String>
         .map_err(|e| format!("Failed to run git: {e}"))?;
 
     if output.status.success() {
-        return Ok(());
+        let mut text = String::from_utf8_lossy(&output.stdout).to_string();
+        text.push_str(&String::from_utf8_lossy(&output.stderr));
+        return Ok(text);
     }
 
     let stderr = String::from_utf8_lossy(&output.stderr);
@@ -154,6 +179,56 @@ async fn push_with_upstream(repo_root: &Path) -> Result<(), String> {
     run_git_command(repo_root, &["push"]).await
 }
 
+/// Pushes `branch` (default: the current branch) to `remote` (default: the
+/// upstream's remote, then `origin`). Without any arguments this behaves like
+/// `push_git`, and a branch with no upstream fails with git's own hint.
+async fn git_push_for_path(
+    repo_root: &Path,
+    remote: Option<String>,
+    branch: Option<String>,
+    set_upstream: bool,
+) -> Result<GitPushResult, String> {
+    // Both go to `git push` as positional arguments, where a leading `-`
+    // would be read as an option such as `--receive-pack`.
+    if let Some(remote) = remote.as_deref().filter(|value| value.starts_with('-')) {
+        return Err(format!("Invalid remote: {remote}"));
+    }
+    if let Some(branch) = branch.as_deref().filter(|value| value.starts_with('-')) {
+        return Err(format!("Invalid branch name: {branch}"));
+    }
+    let upstream = upstream_remote_and_branch(repo_root)?;
+    let mut args = vec!["push".to_string()];
+    if set_upstream {
+        args.push("-u".to_string());
+    }
+    let remote = match (remote, branch, &upstream) {
+        (None, None, Some((upstream_remote, upstream_branch))) if !set_upstream => {
+            args.push(upstream_remote.clone());
+            args.push(format!("HEAD:{upstream_branch}"));
+            Some(upstream_remote.clone())
+        }
+        (None, None, None) if !set_upstream => None,
+        (remote, branch, upstream) => {
+            let remote = remote
+                .or_else(|| upstream.as_ref().map(|(remote, _)| remote.clone()))
+                .unwrap_or_else(|| "origin".to_string());
+            args.push(remote.clone());
+            args.push(branch.unwrap_or_else(|| "HEAD".to_string()));
+            Some(remote)
+        }
+    };
+    let arg_refs: Vec<&str> = args.iter().map(String::as_str).collect();
+    let output = run_git_command_output(repo_root, &arg_refs).await?;
+    let created_remote_branch = output
+        .lines()
+        .any(|line| line.trim_start().starts_with("* [new branch]"));
+    Ok(GitPushResult {
+        remote,
+        created_remote_branch,
+        upstream_set: set_upstream,
+    })
+}
+
 fn status_for_index(status: Status) -> Option<&'static str> {
     if status.contains(Status::INDEX_NEW) {
         Some("A")
@@ -197,6 +272,29 @@ fn status_for_delta(status: git2::Delta) -> &'static str {
     }
 }
 
+fn head_is_unborn(repo: &Repository) -> bool {
+    matches!(repo.head(), Err(err) if err.code() == git2::ErrorCode::UnbornBranch)
+}
+
+fn unborn_branch_name(repo: &Repository) -> Option<String> {
+    let head = repo.find_reference("HEAD").ok()?;
+    let target = head.symbolic_target()?;
+    Some(target.strip_prefix("refs/heads/").unwrap_or(target).to_string())
+}
+
+fn rename_paths(delta: Option<git2::DiffDelta>) -> Option<(String, String)> {
+    let delta = delta?;
+    if delta.status() != git2::Delta::Renamed {
+        return None;
+    }
+    let old_path = normalize_git_path(delta.old_file().path()?.to_string_lossy().as_ref());
+    let new_path = normalize_git_path(delta.new_file().path()?.to_string_lossy().as_ref());
+    if old_path.is_empty() || new_path.is_empty() || old_path == new_path {
+        return None;
+    }
+    Some((old_path, new_path))
+}
+
 fn build_combined_diff(diff: &git2::Diff) -> String {
     let mut combined_diff = String::new();
     for (index, delta) in diff.deltas().enumerate() {
@@ -230,6 +328,39 @@ fn build_combined_diff(diff: &git2::Diff) -> String {
     combined_diff
 }
 
+fn commit_diffs_from_diff(diff: &git2::Diff) -> Vec<GitCommitDiff> {
+    let mut results = Vec::new();
+    for (index, delta) in diff.deltas().enumerate() {
+        let path = delta
+            .new_file()
+            .path()
+            .or_else(|| delta.old_file().path());
+        let Some(path) = path else {
+            continue;
+        };
+        let patch = match git2::Patch::from_diff(diff, index) {
+            Ok(patch) => patch,
+            Err(_) => continue,
+        };
+        let Some(mut patch) = patch else {
+            continue;
+        };
+        let content = match diff_patch_to_string(&mut patch) {
+            Ok(content) => content,
+            Err(_) => continue,
+        };
+        if content.trim().is_empty() {
+            continue;
+        }
+        results.push(GitCommitDiff {
+            path: normalize_git_path(path.to_string_lossy().as_ref()),
+            status: status_for_delta(delta.status()).to_string(),
+            diff: content,
+        });
+    }
+    results
+}
+
 fn collect_workspace_diff(repo_root: &Path) -> Result<String, String> {
     let repo = Repository::open(repo_root).map_err(|e| e.to_string())?;
     let head_tree = repo
@@ -268,6 +399,38 @@ fn collect_workspace_diff(repo_root: &Path) -> Result<String, String> {
     Ok(build_combined_diff(&diff))
 }
 
+fn is_dirty_for_path(repo_root: &Path) -> Result<bool, String> {
+    let repo = Repository::open(repo_root).map_err(|e| e.to_string())?;
+
+    // Rename detection and per-file stats are irrelevant for a yes/no answer,
+    // so keep the status scan as cheap as libgit2 allows.
+    let mut status_options = StatusOptions::new();
+    status_options
+        .include_untracked(true)
+        .recurse_untracked_dirs(false)
+        .exclude_submodules(true)
+        .include_ignored(false);
+
+    let statuses = repo
+        .statuses(Some(&mut status_options))
+        .map_err(|e| e.to_string())?;
+    let index = repo.index().ok();
+
+    Ok(statuses.iter().any(|entry| {
+        let Some(path) = entry.path() else {
+            return false;
+        };
+        if let Some(index) = index.as_ref() {
+            if let Some(entry) = index.get_path(Path::new(path), 0) {
+                if entry.flags_extended & INDEX_SKIP_WORKTREE_FLAG != 0 {
+                    return false;
+                }
+            }
+        }
+        entry.status() != Status::CURRENT
+    }))
+}
+
 fn github_repo_from_path(path: &Path) -> Result<String, String> {
     let repo = Repository::open(path).map_err(|e| e.to_string())?;
     let remotes = repo.remotes().map_err(|e| e.to_string())?;
@@ -382,24 +545,287 @@ fn parse_pr_diff(diff: &str) -> Vec<GitHubPullRequestDiff> {
 
     entries
 }
-#[tauri::command]
-pub(crate) async fn get_git_status(
-    workspace_id: String,
-    state: State<'_, AppState>,
-) -> Result<serde_json::Value, String> {
-    let workspaces = state.workspaces.lock().await;
-    let entry = workspaces
-        .get(&workspace_id)
-        .ok_or("workspace not found")?
-        .clone();
+fn normalize_status_pathspec(subdir: Option<&str>) -> Option<String> {
+    let normalized = normalize_git_path(subdir?.trim());
+    let trimmed = normalized
+        .trim_start_matches("./")
+        .trim_matches('/')
+        .to_string();
+    if trimmed.is_empty() || trimmed == "." {
+        None
+    } else {
+        Some(trimmed)
+    }
+}
 
-    let repo_root = resolve_git_root(&entry)?;
-    let repo = Repository::open(&repo_root).map_err(|e| e.to_string())?;
+fn conflicted_paths(repo_root: &Path) -> Result<Vec<String>, String> {
+    let repo = Repository::open(repo_root).map_err(|e| e.to_string())?;
+    let index = repo.index().map_err(|e| e.to_string())?;
+    if !index.has_conflicts() {
+        return Ok(Vec::new());
+    }
+    let mut paths = Vec::new();
+    for conflict in index.conflicts().map_err(|e| e.to_string())? {
+        let conflict = conflict.map_err(|e| e.to_string())?;
+        let entry = conflict.our.or(conflict.their).or(conflict.ancestor);
+        let Some(entry) = entry else {
+            continue;
+        };
+        let path = normalize_git_path(String::from_utf8_lossy(&entry.path).as_ref());
+        if !path.is_empty() && !paths.contains(&path) {
+            paths.push(path);
+        }
+    }
+    Ok(paths)
+}
+
+/// Applies `stash@{index}` and keeps the stash. Conflicts are left in the
+/// working tree and reported instead of being treated as a hard failure.
+async fn stash_apply_for_path(
+    repo_root: &Path,
+    index: usize,
+) -> Result<GitStashApplyResult, String> {
+    restore_stash(repo_root, "apply", index).await
+}
+
+/// Like `stash_apply_for_path`, but drops the stash once it applies cleanly.
+/// On conflicts git keeps the stash, so nothing is lost.
+async fn stash_pop_for_path(
+    repo_root: &Path,
+    index: usize,
+) -> Result<GitStashApplyResult, String> {
+    restore_stash(repo_root, "pop", index).await
+}
+
+async fn restore_stash(
+    repo_root: &Path,
+    action: &str,
+    index: usize,
+) -> Result<GitStashApplyResult, String> {
+    let stash_ref = format!("stash@{{{index}}}");
+    let outcome = run_git_command(repo_root, &["stash", action, stash_ref.as_str()]).await;
+    let conflicts = conflicted_paths(repo_root)?;
+    if !conflicts.is_empty() {
+        return Ok(GitStashApplyResult {
+            has_conflicts: true,
+            conflicts,
+        });
+    }
+    outcome?;
+    Ok(GitStashApplyResult {
+        has_conflicts: false,
+        conflicts: Vec::new(),
+    })
+}
+
+/// Runs `git pull`, optionally `--ff-only`. A pull that stops on conflicts
+/// fails with a `merge_conflict:` error naming the conflicted paths so the UI
+/// can offer to resolve them rather than showing raw git output.
+async fn pull_for_path(repo_root: &Path, ff_only: bool) -> Result<(), String> {
+    let mut args = vec!["pull"];
+    if ff_only {
+        args.push("--ff-only");
+    }
+    let outcome = run_git_command(repo_root, &args).await;
+    let conflicts = conflicted_paths(repo_root)?;
+    if !conflicts.is_empty() {
+        return Err(format!(
+            "merge_conflict: Pull stopped with conflicts in {}",
+            conflicts.join(", ")
+        ));
+    }
+    outcome
+}
+
+/// Merges `branch` into the current branch, or runs `git merge --abort` when
+/// `abort` is set. A merge that stops on conflicts leaves them in the working
+/// tree and reports the conflicted paths instead of failing.
+async fn merge_for_path(
+    repo_root: &Path,
+    branch: Option<&str>,
+    no_ff: bool,
+    abort: bool,
+) -> Result<GitMergeResult, String> {
+    if abort {
+        run_git_command(repo_root, &["merge", "--abort"]).await?;
+        return Ok(GitMergeResult {
+            has_conflicts: false,
+            conflicts: Vec::new(),
+        });
+    }
+    let branch = branch.map(str::trim).unwrap_or("");
+    if branch.is_empty() {
+        return Err("Branch name is required".to_string());
+    }
+    if branch.starts_with('-') {
+        return Err(format!("Invalid branch name: {branch}"));
+    }
+    let mut args = vec!["merge", "--no-edit"];
+    if no_ff {
+        args.push("--no-ff");
+    }
+    args.push(branch);
+    let outcome = run_git_command(repo_root, &args).await;
+    let conflicts = conflicted_paths(repo_root)?;
+    if !conflicts.is_empty() {
+        return Ok(GitMergeResult {
+            has_conflicts: true,
+            conflicts,
+        });
+    }
+    outcome?;
+    Ok(GitMergeResult {
+        has_conflicts: false,
+        conflicts: Vec::new(),
+    })
+}
+
+/// Reads the `current/total` step of an in-progress rebase from the state
+/// directory git keeps under `.git` (`rebase-merge` for the default backend,
+/// `rebase-apply` for the legacy one).
+fn rebase_step(git_dir: &Path) -> Option<String> {
+    let read = |dir: &str, name: &str| {
+        std::fs::read_to_string(git_dir.join(dir).join(name))
+            .ok()
+            .map(|value| value.trim().to_string())
+    };
+    let (current, total) = if git_dir.join("rebase-merge").is_dir() {
+        (read("rebase-merge", "msgnum")?, read("rebase-merge", "end")?)
+    } else if git_dir.join("rebase-apply").is_dir() {
+        (read("rebase-apply", "next")?, read("rebase-apply", "last")?)
+    } else {
+        return None;
+    };
+    Some(format!("{current}/{total}"))
+}
+
+/// Drives a rebase: `start` rebases the current branch onto `onto`, while
+/// `continue` and `abort` act on the rebase in progress. Stopping on conflicts
+/// is reported in the result (with the current step) instead of failing.
+async fn rebase_for_path(
+    repo_root: &Path,
+    action: &str,
+    onto: Option<&str>,
+) -> Result<GitRebaseResult, String> {
+    let onto = onto.map(str::trim).unwrap_or("");
+    let args: Vec<&str> = match action {
+        "start" => {
+            if onto.is_empty() {
+                return Err("onto is required to start a rebase".to_string());
+            }
+            if onto.starts_with('-') {
+                return Err(format!("Invalid ref: {onto}"));
+            }
+            vec!["rebase", onto]
+        }
+        // Keep the existing commit messages instead of waiting on an editor.
+        "continue" => vec!["-c", "core.editor=true", "rebase", "--continue"],
+        "abort" => vec!["rebase", "--abort"],
+        other => return Err(format!("Unknown rebase action: {other}")),
+    };
+    let outcome = run_git_command(repo_root, &args).await;
+    let conflicts = conflicted_paths(repo_root)?;
+    let repo = Repository::open(repo_root).map_err(|e| e.to_string())?;
+    let step = rebase_step(repo.path());
+    if conflicts.is_empty() {
+        outcome?;
+    }
+    Ok(GitRebaseResult {
+        has_conflicts: !conflicts.is_empty(),
+        conflicts,
+        in_progress: step.is_some(),
+        step,
+    })
+}
+
+async fn stash_save_for_path(repo_root: &Path, message: Option<&str>) -> Result<(), String> {
+    let mut args = vec!["stash", "push", "--include-untracked"];
+    let message = message.map(str::trim).filter(|message| !message.is_empty());
+    if let Some(message) = message {
+        args.extend(["-m", message]);
+    }
+    if !has_local_changes(repo_root)? {
+        return Err("No local changes to stash".to_string());
+    }
+    run_git_command(repo_root, &args).await
+}
+
+const STASH_LIST_FORMAT: &str = "--format=%gd%x00%ct%x00%gs";
+
+/// Parses `git stash list` in `STASH_LIST_FORMAT`. Stash subjects read
+/// `On <branch>: <message>` or `WIP on <branch>: <sha> <summary>`.
+fn parse_stash_list(output: &str) -> Vec<GitStashEntry> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.splitn(3, '\0');
+            let selector = fields.next()?;
+            let timestamp = fields.next()?.trim().parse().unwrap_or(0);
+            let subject = fields.next()?;
+            let index = selector
+                .strip_prefix("stash@{")?
+                .strip_suffix('}')?
+                .parse()
+                .ok()?;
+            let (branch, message) = match subject
+                .strip_prefix("WIP on ")
+                .or_else(|| subject.strip_prefix("On "))
+                .and_then(|rest| rest.split_once(": "))
+            {
+                Some((branch, message)) => {
+                    let branch = (branch != "(no branch)").then(|| branch.to_string());
+                    (branch, message.to_string())
+                }
+                None => (None, subject.to_string()),
+            };
+            Some(GitStashEntry {
+                index,
+                message,
+                branch,
+                timestamp,
+            })
+        })
+        .collect()
+}
+
+async fn stash_list_for_path(repo_root: &Path) -> Result<Vec<GitStashEntry>, String> {
+    let output = run_git_command_output(repo_root, &["stash", "list", STASH_LIST_FORMAT]).await?;
+    Ok(parse_stash_list(&output))
+}
+
+fn stash_drop_for_path(repo_root: &Path, index: usize) -> Result<(), String> {
+    let mut repo = Repository::open(repo_root).map_err(|e| e.to_string())?;
+    if index >= stash_count(&mut repo) {
+        return Err(format!("Stash not found: stash@{{{index}}}"));
+    }
+    repo.stash_drop(index).map_err(|e| e.to_string())
+}
+
+fn stash_count(repo: &mut Repository) -> usize {
+    let mut count = 0usize;
+    let _ = repo.stash_foreach(|_, _, _| {
+        count += 1;
+        true
+    });
+    count
+}
+
+/// Builds the status payload. Only the first `max_files_for_stats` entries get
+/// per-file line stats; the rest report `null` and are left out of the totals.
+fn git_status_for_path(
+    repo_root: &Path,
+    subdir: Option<&str>,
+    max_files_for_stats: Option<usize>,
+) -> Result<serde_json::Value, String> {
+    let max_files_for_stats = max_files_for_stats.unwrap_or(DEFAULT_MAX_FILES_FOR_STATS);
+    let mut repo = Repository::open(repo_root).map_err(|e| e.to_string())?;
+    let stash_count = stash_count(&mut repo);
 
     let branch_name = repo
         .head()
         .ok()
         .and_then(|head| head.shorthand().map(|s| s.to_string()))
+        .or_else(|| unborn_branch_name(&repo))
         .unwrap_or_else(|| "unknown".to_string());
 
     let mut status_options = StatusOptions::new();
@@ -409,6 +835,9 @@ pub(crate) async fn get_git_status(
         .renames_head_to_index(true)
         .renames_index_to_workdir(true)
         .include_ignored(false);
+    if let Some(pathspec) = normalize_status_pathspec(subdir) {
+        status_options.pathspec(pathspec);
+    }
 
     let statuses = repo
         .statuses(Some(&mut status_options))
@@ -435,7 +864,17 @@ pub(crate) async fn get_git_status(
             }
         }
         let status = entry.status();
-        let normalized_path = normalize_git_path(path);
+        let index_rename = rename_paths(entry.head_to_index());
+        let workdir_rename = rename_paths(entry.index_to_workdir());
+        let original_path = index_rename
+            .as_ref()
+            .or(workdir_rename.as_ref())
+            .map(|(old_path, _)| old_path.clone());
+        let normalized_path = workdir_rename
+            .as_ref()
+            .or(index_rename.as_ref())
+            .map(|(_, new_path)| new_path.clone())
+            .unwrap_or_else(|| normalize_git_path(path));
         let include_index = status.intersects(
             Status::INDEX_NEW
                 | Status::INDEX_MODIFIED
@@ -450,43 +889,61 @@ pub(crate) async fn get_git_status(
                 | Status::WT_RENAMED
                 | Status::WT_TYPECHANGE,
         );
+        let index_status = status_for_index(status).map(str::to_string);
+        let worktree_status = status_for_workdir(status).map(str::to_string);
+        let compute_stats = files.len() < max_files_for_stats;
         let mut combined_additions = 0i64;
         let mut combined_deletions = 0i64;
 
         if include_index {
-            let (additions, deletions) =
+            let stats = compute_stats.then(|| {
                 diff_stats_for_path(&repo, head_tree.as_ref(), path, true, false)
-                    .unwrap_or((0, 0));
+                    .unwrap_or((0, 0))
+            });
             if let Some(status_str) = status_for_index(status) {
                 staged_files.push(GitFileStatus {
-                    path: normalized_path.clone(),
+                    path: index_rename
+                        .as_ref()
+                        .map(|(_, new_path)| new_path.clone())
+                        .unwrap_or_else(|| normalize_git_path(path)),
                     status: status_str.to_string(),
-                    additions,
-                    deletions,
+                    additions: stats.map(|(additions, _)| additions),
+                    deletions: stats.map(|(_, deletions)| deletions),
+                    old_path: index_rename.as_ref().map(|(old_path, _)| old_path.clone()),
+                    index_status: index_status.clone(),
+                    worktree_status: worktree_status.clone(),
                 });
             }
-            combined_additions += additions;
-            combined_deletions += deletions;
-            total_additions += additions;
-            total_deletions += deletions;
+            if let Some((additions, deletions)) = stats {
+                combined_additions += additions;
+                combined_deletions += deletions;
+                total_additions += additions;
+                total_deletions += deletions;
+            }
         }
 
         if include_workdir {
-            let (additions, deletions) =
+            let stats = compute_stats.then(|| {
                 diff_stats_for_path(&repo, head_tree.as_ref(), path, false, true)
-                    .unwrap_or((0, 0));
+                    .unwrap_or((0, 0))
+            });
             if let Some(status_str) = status_for_workdir(status) {
                 unstaged_files.push(GitFileStatus {
                     path: normalized_path.clone(),
                     status: status_str.to_string(),
-                    additions,
-                    deletions,
+                    additions: stats.map(|(additions, _)| additions),
+                    deletions: stats.map(|(_, deletions)| deletions),
+                    old_path: workdir_rename.as_ref().map(|(old_path, _)| old_path.clone()),
+                    index_status: index_status.clone(),
+                    worktree_status: worktree_status.clone(),
                 });
             }
-            combined_additions += additions;
-            combined_deletions += deletions;
-            total_additions += additions;
-            total_deletions += deletions;
+            if let Some((additions, deletions)) = stats {
+                combined_additions += additions;
+                combined_deletions += deletions;
+                total_additions += additions;
+                total_deletions += deletions;
+            }
         }
 
         if include_index || include_workdir {
@@ -496,8 +953,11 @@ pub(crate) async fn get_git_status(
             files.push(GitFileStatus {
                 path: normalized_path,
                 status: status_str.to_string(),
-                additions: combined_additions,
-                deletions: combined_deletions,
+                additions: compute_stats.then_some(combined_additions),
+                deletions: compute_stats.then_some(combined_deletions),
+                old_path: original_path,
+                index_status,
+                worktree_status,
             });
         }
     }
@@ -509,15 +969,17 @@ pub(crate) async fn get_git_status(
         "unstagedFiles": unstaged_files,
         "totalAdditions": total_additions,
         "totalDeletions": total_deletions,
+        "stashCount": stash_count,
     }))
 }
 
 #[tauri::command]
-pub(crate) async fn stage_git_file(
+pub(crate) async fn get_git_status(
     workspace_id: String,
-    path: String,
+    subdir: Option<String>,
+    max_files_for_stats: Option<usize>,
     state: State<'_, AppState>,
-) -> Result<(), String> {
+) -> Result<serde_json::Value, String> {
     let entry = {
         let workspaces = state.workspaces.lock().await;
         workspaces
@@ -527,19 +989,117 @@ pub(crate) async fn stage_git_file(
     };
 
     let repo_root = resolve_git_root(&entry)?;
-    // If libgit2 reports a rename, we want a single UI action to stage both the
-    // old + new paths so the change actually moves to the staged section.
-    for path in action_paths_for_file(&repo_root, &path) {
-        run_git_command(&repo_root, &["add", "-A", "--", &path]).await?;
+    git_status_for_path(&repo_root, subdir.as_deref(), max_files_for_stats)
+}
+
+/// Commits HEAD is ahead of and behind its upstream, plus the upstream name.
+/// A detached HEAD or a branch without upstream reports `(0, 0, None)`.
+fn upstream_ahead_behind(repo: &Repository) -> Result<(usize, usize, Option<String>), String> {
+    let Ok(head) = repo.head() else {
+        return Ok((0, 0, None));
+    };
+    let Some(branch_name) = head.shorthand().filter(|_| head.is_branch()) else {
+        return Ok((0, 0, None));
+    };
+    let Ok(branch) = repo.find_branch(branch_name, BranchType::Local) else {
+        return Ok((0, 0, None));
+    };
+    let Ok(upstream_branch) = branch.upstream() else {
+        return Ok((0, 0, None));
+    };
+    let upstream_ref = upstream_branch.get();
+    let upstream = upstream_ref
+        .shorthand()
+        .or_else(|| upstream_ref.name())
+        .map(str::to_string);
+    let (ahead, behind) = match (head.target(), upstream_ref.target()) {
+        (Some(head_oid), Some(upstream_oid)) => repo
+            .graph_ahead_behind(head_oid, upstream_oid)
+            .map_err(|e| e.to_string())?,
+        _ => (0, 0),
+    };
+    Ok((ahead, behind, upstream))
+}
+
+/// The `get_git_status` payload extended with `dirty`, `ahead`, `behind` and
+/// `upstream`, for dashboards summarising several repositories.
+fn git_status_summary_for_path(
+    repo_root: &Path,
+    max_files_for_stats: Option<usize>,
+) -> Result<serde_json::Value, String> {
+    let mut status = git_status_for_path(repo_root, None, max_files_for_stats)?;
+    let repo = Repository::open(repo_root).map_err(|e| e.to_string())?;
+    let (ahead, behind, upstream) = upstream_ahead_behind(&repo)?;
+    let dirty = status["files"]
+        .as_array()
+        .is_some_and(|files| !files.is_empty());
+    status["dirty"] = json!(dirty);
+    status["ahead"] = json!(ahead);
+    status["behind"] = json!(behind);
+    status["upstream"] = json!(upstream);
+    Ok(status)
+}
+
+/// Computes the status of several workspaces (all of them when `workspace_ids`
+/// is omitted) on the blocking pool, a few repositories at a time. Returns a
+/// map of workspace id to `{ status, error }` with exactly one of them set.
+#[tauri::command]
+pub(crate) async fn get_git_status_batch(
+    workspace_ids: Option<Vec<String>>,
+    max_files_for_stats: Option<usize>,
+    state: State<'_, AppState>,
+) -> Result<serde_json::Value, String> {
+    let targets: Vec<(String, Result<PathBuf, String>)> = {
+        let workspaces = state.workspaces.lock().await;
+        let ids = workspace_ids.unwrap_or_else(|| workspaces.keys().cloned().collect());
+        ids.into_iter()
+            .map(|id| {
+                let repo_root = workspaces
+                    .get(&id)
+                    .ok_or_else(|| "workspace not found".to_string())
+                    .and_then(resolve_git_root);
+                (id, repo_root)
+            })
+            .collect()
+    };
+
+    let permits = Arc::new(Semaphore::new(GIT_STATUS_BATCH_CONCURRENCY));
+    let mut tasks = JoinSet::new();
+    for (id, repo_root) in targets {
+        let permits = Arc::clone(&permits);
+        tasks.spawn(async move {
+            let result = match repo_root {
+                Ok(repo_root) => {
+                    let _permit = permits.acquire_owned().await;
+                    tokio::task::spawn_blocking(move || {
+                        git_status_summary_for_path(&repo_root, max_files_for_stats)
+                    })
+                    .await
+                    .unwrap_or_else(|err| Err(err.to_string()))
+                }
+                Err(err) => Err(err),
+            };
+            (id, result)
+        });
     }
-    Ok(())
+
+    let mut statuses = serde_json::Map::new();
+    while let Some(joined) = tasks.join_next().await {
+        let (id, result) = joined.map_err(|e| e.to_string())?;
+        let value = match result {
+            Ok(status) => json!({ "status": status, "error": null }),
+            Err(err) => json!({ "status": null, "error": err }),
+        };
+        statuses.insert(id, value);
+    }
+    Ok(serde_json::Value::Object(statuses))
 }
 
 #[tauri::command]
-pub(crate) async fn stage_git_all(
+pub(crate) async fn is_workspace_dirty(
     workspace_id: String,
     state: State<'_, AppState>,
-) -> Result<(), String> {
+) -> Result<bool, String> {
     let entry = {
         let workspaces = state.workspaces.lock().await;
         workspaces
@@ -549,15 +1109,15 @@ pub(crate) async fn stage_git_all(
     };
 
     let repo_root = resolve_git_root(&entry)?;
-    run_git_command(&repo_root, &["add", "-A"]).await
+    is_dirty_for_path(&repo_root)
 }
 
 #[tauri::command]
-pub(crate) async fn unstage_git_file(
+pub(crate) async fn git_stash_apply(
     workspace_id: String,
-    path: String,
+    index: Option<usize>,
     state: State<'_, AppState>,
-) -> Result<(), String> {
+) -> Result<GitStashApplyResult, String> {
     let entry = {
         let workspaces = state.workspaces.lock().await;
         workspaces
@@ -567,18 +1127,15 @@ pub(crate) async fn unstage_git_file(
     };
 
     let repo_root = resolve_git_root(&entry)?;
-    for path in action_paths_for_file(&repo_root, &path) {
-        run_git_command(&repo_root, &["restore", "--staged", "--", &path]).await?;
-    }
-    Ok(())
+    stash_apply_for_path(&repo_root, index.unwrap_or(0)).await
 }
 
 #[tauri::command]
-pub(crate) async fn revert_git_file(
+pub(crate) async fn git_stash_pop(
     workspace_id: String,
-    path: String,
+    index: Option<usize>,
     state: State<'_, AppState>,
-) -> Result<(), String> {
+) -> Result<GitStashApplyResult, String> {
     let entry = {
         let workspaces = state.workspaces.lock().await;
         workspaces
@@ -588,57 +1145,585 @@ pub(crate) async fn revert_git_file(
     };
 
     let repo_root = resolve_git_root(&entry)?;
-    for path in action_paths_for_file(&repo_root, &path) {
-        if run_git_command(
-            &repo_root,
-            &["restore", "--staged", "--worktree", "--", &path],
-        )
-        .await
-        .is_ok()
-        {
-            continue;
-        }
-        run_git_command(&repo_root, &["clean", "-f", "--", &path]).await?;
-    }
-    Ok(())
+    stash_pop_for_path(&repo_root, index.unwrap_or(0)).await
 }
 
 #[tauri::command]
-pub(crate) async fn revert_git_all(
+pub(crate) async fn git_stash_save(
     workspace_id: String,
+    message: Option<String>,
     state: State<'_, AppState>,
 ) -> Result<(), String> {
-    let workspaces = state.workspaces.lock().await;
-    let entry = workspaces
-        .get(&workspace_id)
-        .ok_or("workspace not found")?;
-    let repo_root = resolve_git_root(entry)?;
-    run_git_command(&repo_root, &["restore", "--staged", "--worktree", "--", "."]).await?;
-    run_git_command(&repo_root, &["clean", "-f", "-d"]).await
+    let entry = {
+        let workspaces = state.workspaces.lock().await;
+        workspaces
+            .get(&workspace_id)
+            .cloned()
+            .ok_or("workspace not found")?
+    };
+
+    let repo_root = resolve_git_root(&entry)?;
+    stash_save_for_path(&repo_root, message.as_deref()).await
 }
 
 #[tauri::command]
-pub(crate) async fn commit_git(
+pub(crate) async fn git_stash_list(
     workspace_id: String,
-    message: String,
     state: State<'_, AppState>,
-) -> Result<(), String> {
-    let workspaces = state.workspaces.lock().await;
-    let entry = workspaces
-        .get(&workspace_id)
-        .ok_or("workspace not found")?
-        .clone();
+) -> Result<Vec<GitStashEntry>, String> {
+    let entry = {
+        let workspaces = state.workspaces.lock().await;
+        workspaces
+            .get(&workspace_id)
+            .cloned()
+            .ok_or("workspace not found")?
+    };
 
     let repo_root = resolve_git_root(&entry)?;
-    run_git_command(&repo_root, &["commit", "-m", &message]).await
+    stash_list_for_path(&repo_root).await
 }
 
 #[tauri::command]
-pub(crate) async fn push_git(
+pub(crate) async fn git_stash_drop(
     workspace_id: String,
+    index: Option<usize>,
     state: State<'_, AppState>,
 ) -> Result<(), String> {
-    let workspaces = state.workspaces.lock().await;
+    let entry = {
+        let workspaces = state.workspaces.lock().await;
+        workspaces
+            .get(&workspace_id)
+            .cloned()
+            .ok_or("workspace not found")?
+    };
+
+    let repo_root = resolve_git_root(&entry)?;
+    stash_drop_for_path(&repo_root, index.unwrap_or(0))
+}
+
+#[tauri::command]
+pub(crate) async fn stage_git_file(
+    workspace_id: String,
+    path: String,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    let entry = {
+        let workspaces = state.workspaces.lock().await;
+        workspaces
+            .get(&workspace_id)
+            .cloned()
+            .ok_or("workspace not found")?
+    };
+
+    let repo_root = resolve_git_root(&entry)?;
+    stage_paths(&repo_root, &path).await
+}
+
+async fn stage_paths(repo_root: &Path, path: &str) -> Result<(), String> {
+    // If libgit2 reports a rename, we want a single UI action to stage both the
+    // old + new paths so the change actually moves to the staged section.
+    for path in action_paths_for_file(repo_root, path) {
+        run_git_command(repo_root, &["add", "-A", "--", &path]).await?;
+    }
+    Ok(())
+}
+
+async fn unstage_paths(repo_root: &Path, path: &str) -> Result<(), String> {
+    for path in action_paths_for_file(repo_root, path) {
+        run_git_command(repo_root, &["restore", "--staged", "--", &path]).await?;
+    }
+    Ok(())
+}
+
+/// Status entry for a single file, or `None` when it has no changes.
+fn file_status_for_path(
+    repo_root: &Path,
+    path: &str,
+) -> Result<Option<GitFileStatus>, String> {
+    let status = git_status_for_path(repo_root, Some(path), None)?;
+    let entry = status["files"].as_array().and_then(|files| {
+        files
+            .iter()
+            .find(|file| file["path"] == path)
+            .cloned()
+    });
+    match entry {
+        Some(entry) => serde_json::from_value(entry)
+            .map(Some)
+            .map_err(|e| e.to_string()),
+        None => Ok(None),
+    }
+}
+
+/// `stage_git_file`, returning the file's refreshed status entry.
+async fn stage_file_for_path(
+    repo_root: &Path,
+    path: &str,
+) -> Result<Option<GitFileStatus>, String> {
+    let path = normalize_git_path(path.trim());
+    let current = file_status_for_path(repo_root, &path)?
+        .ok_or_else(|| format!("No changes to stage for {path}"))?;
+    if current.worktree_status.is_none() {
+        return Ok(Some(current));
+    }
+    stage_paths(repo_root, &path).await?;
+    file_status_for_path(repo_root, &path)
+}
+
+/// `unstage_git_file`, returning the file's refreshed status entry.
+async fn unstage_file_for_path(
+    repo_root: &Path,
+    path: &str,
+) -> Result<Option<GitFileStatus>, String> {
+    let path = normalize_git_path(path.trim());
+    let current = file_status_for_path(repo_root, &path)?
+        .ok_or_else(|| format!("No changes to unstage for {path}"))?;
+    if current.index_status.is_none() {
+        return Ok(Some(current));
+    }
+    unstage_paths(repo_root, &path).await?;
+    file_status_for_path(repo_root, &path)
+}
+
+/// Stages only the chosen hunks of a file's unstaged changes, like
+/// `git add -p`. Hunk ids are those of the structured diff with `staged`
+/// set to false.
+fn stage_hunks_for_path(
+    repo_root: &Path,
+    path: &str,
+    hunk_ids: &[usize],
+) -> Result<Option<GitFileStatus>, String> {
+    let path = normalize_git_path(path.trim());
+    if hunk_ids.is_empty() {
+        return Err("No hunks selected".to_string());
+    }
+    let repo = Repository::open(repo_root).map_err(|e| e.to_string())?;
+    let diff = file_diff(&repo, &path, FileDiffScope::IndexToWorkdir)?;
+    if diff.deltas().len() == 0 {
+        return Err(format!("No changes to stage for {path}"));
+    }
+    let hunk_count = git2::Patch::from_diff(&diff, 0)
+        .map_err(|e| e.to_string())?
+        .map(|patch| patch.num_hunks())
+        .unwrap_or(0);
+    if let Some(unknown) = hunk_ids.iter().find(|id| **id >= hunk_count) {
+        return Err(format!("Unknown hunk {unknown} for {path}"));
+    }
+    let mut next_hunk = 0;
+    let mut options = git2::ApplyOptions::new();
+    options.hunk_callback(|_| {
+        let selected = hunk_ids.contains(&next_hunk);
+        next_hunk += 1;
+        selected
+    });
+    repo.apply(&diff, git2::ApplyLocation::Index, Some(&mut options))
+        .map_err(|e| e.to_string())?;
+    file_status_for_path(repo_root, &path)
+}
+
+#[tauri::command]
+pub(crate) async fn stage_file(
+    workspace_id: String,
+    path: String,
+    state: State<'_, AppState>,
+) -> Result<Option<GitFileStatus>, String> {
+    let entry = {
+        let workspaces = state.workspaces.lock().await;
+        workspaces
+            .get(&workspace_id)
+            .cloned()
+            .ok_or("workspace not found")?
+    };
+
+    let repo_root = resolve_git_root(&entry)?;
+    stage_file_for_path(&repo_root, &path).await
+}
+
+#[tauri::command]
+pub(crate) async fn unstage_file(
+    workspace_id: String,
+    path: String,
+    state: State<'_, AppState>,
+) -> Result<Option<GitFileStatus>, String> {
+    let entry = {
+        let workspaces = state.workspaces.lock().await;
+        workspaces
+            .get(&workspace_id)
+            .cloned()
+            .ok_or("workspace not found")?
+    };
+
+    let repo_root = resolve_git_root(&entry)?;
+    unstage_file_for_path(&repo_root, &path).await
+}
+
+#[tauri::command]
+pub(crate) async fn stage_hunks(
+    workspace_id: String,
+    path: String,
+    hunk_ids: Vec<usize>,
+    state: State<'_, AppState>,
+) -> Result<Option<GitFileStatus>, String> {
+    let entry = {
+        let workspaces = state.workspaces.lock().await;
+        workspaces
+            .get(&workspace_id)
+            .cloned()
+            .ok_or("workspace not found")?
+    };
+    let repo_root = resolve_git_root(&entry)?;
+    stage_hunks_for_path(&repo_root, &path, &hunk_ids)
+}
+
+#[tauri::command]
+pub(crate) async fn stage_git_all(
+    workspace_id: String,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    let entry = {
+        let workspaces = state.workspaces.lock().await;
+        workspaces
+            .get(&workspace_id)
+            .cloned()
+            .ok_or("workspace not found")?
+    };
+
+    let repo_root = resolve_git_root(&entry)?;
+    run_git_command(&repo_root, &["add", "-A"]).await
+}
+
+#[tauri::command]
+pub(crate) async fn unstage_git_file(
+    workspace_id: String,
+    path: String,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    let entry = {
+        let workspaces = state.workspaces.lock().await;
+        workspaces
+            .get(&workspace_id)
+            .cloned()
+            .ok_or("workspace not found")?
+    };
+
+    let repo_root = resolve_git_root(&entry)?;
+    unstage_paths(&repo_root, &path).await
+}
+
+#[tauri::command]
+pub(crate) async fn revert_git_file(
+    workspace_id: String,
+    path: String,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    let entry = {
+        let workspaces = state.workspaces.lock().await;
+        workspaces
+            .get(&workspace_id)
+            .cloned()
+            .ok_or("workspace not found")?
+    };
+
+    let repo_root = resolve_git_root(&entry)?;
+    for path in action_paths_for_file(&repo_root, &path) {
+        if run_git_command(
+            &repo_root,
+            &["restore", "--staged", "--worktree", "--", &path],
+        )
+        .await
+        .is_ok()
+        {
+            continue;
+        }
+        run_git_command(&repo_root, &["clean", "-f", "--", &path]).await?;
+    }
+    Ok(())
+}
+
+/// Normalizes a repo-relative path and rejects anything that could reach
+/// outside the repository: absolute paths, `..` components, or a parent
+/// directory that resolves elsewhere through a symlink.
+fn checked_relative_path(repo_root: &Path, path: &str) -> Result<String, String> {
+    let path = normalize_git_path(path.trim());
+    if path.is_empty() {
+        return Err("File path is required.".to_string());
+    }
+    let relative = Path::new(&path);
+    let escapes = relative.is_absolute()
+        || relative
+            .components()
+            .any(|component| !matches!(component, Component::Normal(_) | Component::CurDir));
+    let outside = || format!("Path must be inside the workspace: {path}");
+    if escapes {
+        return Err(outside());
+    }
+    let parent = repo_root
+        .join(relative)
+        .parent()
+        .and_then(|parent| parent.canonicalize().ok());
+    if let Some(parent) = parent {
+        let root = repo_root.canonicalize().map_err(|e| e.to_string())?;
+        if !parent.starts_with(&root) {
+            return Err(outside());
+        }
+    }
+    Ok(path)
+}
+
+/// Throws away local changes to one file. Working-tree edits are restored
+/// from the index, or from HEAD together with the index entry when `staged`
+/// is set. Files git does not know about there are deleted from disk.
+fn discard_file_changes_for_path(
+    repo_root: &Path,
+    path: &str,
+    staged: bool,
+) -> Result<serde_json::Value, String> {
+    let path = checked_relative_path(repo_root, path)?;
+    let repo = Repository::open(repo_root).map_err(|e| e.to_string())?;
+    let head = repo
+        .head()
+        .ok()
+        .and_then(|head| head.peel(git2::ObjectType::Commit).ok());
+    let head_tree = head.as_ref().and_then(|head| head.peel_to_tree().ok());
+
+    for action_path in action_paths_for_file(repo_root, &path) {
+        let relative = Path::new(&action_path);
+        let mut index = repo.index().map_err(|e| e.to_string())?;
+        let in_head = head_tree
+            .as_ref()
+            .is_some_and(|tree| tree.get_path(relative).is_ok());
+        if staged {
+            match head.as_ref().filter(|_| in_head) {
+                Some(head) => repo
+                    .reset_default(Some(head), [relative])
+                    .map_err(|e| e.to_string())?,
+                None => {
+                    if index.get_path(relative, 0).is_some() {
+                        index.remove_path(relative).map_err(|e| e.to_string())?;
+                        index.write().map_err(|e| e.to_string())?;
+                    }
+                }
+            }
+            index.read(true).map_err(|e| e.to_string())?;
+        }
+
+        let restorable = if staged {
+            in_head
+        } else {
+            index.get_path(relative, 0).is_some()
+        };
+        if !restorable {
+            let absolute = repo_root.join(relative);
+            if absolute.symlink_metadata().is_err() {
+                continue;
+            }
+            // Only untracked files are deleted; ignored ones such as `.env`
+            // are not changes git would show.
+            let status = repo.status_file(relative).unwrap_or(git2::Status::empty());
+            if !status.contains(git2::Status::WT_NEW) || status.contains(git2::Status::IGNORED) {
+                return Err("No changes to discard".to_string());
+            }
+            std::fs::remove_file(&absolute).map_err(|e| e.to_string())?;
+            continue;
+        }
+        let mut checkout = git2::build::CheckoutBuilder::new();
+        checkout
+            .force()
+            .disable_pathspec_match(true)
+            .path(&action_path);
+        if staged {
+            repo.checkout_head(Some(&mut checkout))
+        } else {
+            repo.checkout_index(Some(&mut index), Some(&mut checkout))
+        }
+        .map_err(|e| e.to_string())?;
+    }
+    git_status_for_path(repo_root, None, None)
+}
+
+#[tauri::command]
+pub(crate) async fn discard_file_changes(
+    workspace_id: String,
+    path: String,
+    staged: Option<bool>,
+    state: State<'_, AppState>,
+) -> Result<serde_json::Value, String> {
+    let entry = {
+        let workspaces = state.workspaces.lock().await;
+        workspaces
+            .get(&workspace_id)
+            .cloned()
+            .ok_or("workspace not found")?
+    };
+    let repo_root = resolve_git_root(&entry)?;
+    discard_file_changes_for_path(&repo_root, &path, staged.unwrap_or(false))
+}
+
+#[tauri::command]
+pub(crate) async fn revert_git_all(
+    workspace_id: String,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    let workspaces = state.workspaces.lock().await;
+    let entry = workspaces
+        .get(&workspace_id)
+        .ok_or("workspace not found")?;
+    let repo_root = resolve_git_root(entry)?;
+    run_git_command(&repo_root, &["restore", "--staged", "--worktree", "--", "."]).await?;
+    run_git_command(&repo_root, &["clean", "-f", "-d"]).await
+}
+
+#[tauri::command]
+pub(crate) async fn commit_git(
+    workspace_id: String,
+    message: String,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    let workspaces = state.workspaces.lock().await;
+    let entry = workspaces
+        .get(&workspace_id)
+        .ok_or("workspace not found")?
+        .clone();
+
+    let repo_root = resolve_git_root(&entry)?;
+    run_git_command(&repo_root, &["commit", "-m", &message]).await
+}
+
+/// Whether the worktree or index differ from HEAD, counting untracked but
+/// not ignored files.
+fn has_local_changes(repo_root: &Path) -> Result<bool, String> {
+    let repo = Repository::open(repo_root).map_err(|e| e.to_string())?;
+    let mut options = StatusOptions::new();
+    options.include_untracked(true).recurse_untracked_dirs(false);
+    let statuses = repo.statuses(Some(&mut options)).map_err(|e| e.to_string())?;
+    Ok(!statuses.is_empty())
+}
+
+/// Stages every change, untracked files included, and commits it with
+/// `message`, returning the new commit's SHA. Refuses when the tree is clean.
+async fn commit_all_for_path(repo_root: &Path, message: &str) -> Result<String, String> {
+    let message = message.trim();
+    if message.is_empty() {
+        return Err("Commit message is required".to_string());
+    }
+    if !has_local_changes(repo_root)? {
+        return Err("Nothing to commit".to_string());
+    }
+    run_git_command(repo_root, &["add", "-A"]).await?;
+    run_git_command(repo_root, &["commit", "-m", message]).await?;
+    let repo = Repository::open(repo_root).map_err(|e| e.to_string())?;
+    let head = repo.head().map_err(|e| e.to_string())?;
+    head.target()
+        .map(|oid| oid.to_string())
+        .ok_or_else(|| "HEAD does not point at a commit".to_string())
+}
+
+#[tauri::command]
+pub(crate) async fn commit_all(
+    workspace_id: String,
+    message: String,
+    state: State<'_, AppState>,
+) -> Result<String, String> {
+    let entry = {
+        let workspaces = state.workspaces.lock().await;
+        workspaces
+            .get(&workspace_id)
+            .cloned()
+            .ok_or("workspace not found")?
+    };
+    let repo_root = resolve_git_root(&entry)?;
+    commit_all_for_path(&repo_root, &message).await
+}
+
+fn identity_from_config(config: &git2::Config) -> GitIdentity {
+    let read = |key: &str| {
+        config
+            .get_string(key)
+            .ok()
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+    };
+    let name = read("user.name");
+    let email = read("user.email");
+    let configured = name.is_some() && email.is_some();
+    GitIdentity {
+        name,
+        email,
+        configured,
+    }
+}
+
+/// The identity commits would be made with: repo-local config, falling back
+/// to the global and system files.
+fn git_identity_for_path(repo_root: &Path) -> Result<GitIdentity, String> {
+    let repo = Repository::open(repo_root).map_err(|e| e.to_string())?;
+    let mut config = repo.config().map_err(|e| e.to_string())?;
+    let snapshot = config.snapshot().map_err(|e| e.to_string())?;
+    Ok(identity_from_config(&snapshot))
+}
+
+/// Writes `user.name`/`user.email` to the repository's own config.
+fn set_git_identity_for_path(
+    repo_root: &Path,
+    name: &str,
+    email: &str,
+) -> Result<GitIdentity, String> {
+    let (name, email) = (name.trim(), email.trim());
+    if name.is_empty() || email.is_empty() {
+        return Err("Name and email are required".to_string());
+    }
+    let repo = Repository::open(repo_root).map_err(|e| e.to_string())?;
+    let config = repo.config().map_err(|e| e.to_string())?;
+    let mut local = config
+        .open_level(git2::ConfigLevel::Local)
+        .map_err(|e| e.to_string())?;
+    local.set_str("user.name", name).map_err(|e| e.to_string())?;
+    local
+        .set_str("user.email", email)
+        .map_err(|e| e.to_string())?;
+    git_identity_for_path(repo_root)
+}
+
+#[tauri::command]
+pub(crate) async fn get_git_identity(
+    workspace_id: String,
+    state: State<'_, AppState>,
+) -> Result<GitIdentity, String> {
+    let entry = {
+        let workspaces = state.workspaces.lock().await;
+        workspaces
+            .get(&workspace_id)
+            .cloned()
+            .ok_or("workspace not found")?
+    };
+    let repo_root = resolve_git_root(&entry)?;
+    git_identity_for_path(&repo_root)
+}
+
+#[tauri::command]
+pub(crate) async fn set_git_identity(
+    workspace_id: String,
+    name: String,
+    email: String,
+    state: State<'_, AppState>,
+) -> Result<GitIdentity, String> {
+    let entry = {
+        let workspaces = state.workspaces.lock().await;
+        workspaces
+            .get(&workspace_id)
+            .cloned()
+            .ok_or("workspace not found")?
+    };
+    let repo_root = resolve_git_root(&entry)?;
+    set_git_identity_for_path(&repo_root, &name, &email)
+}
+
+#[tauri::command]
+pub(crate) async fn push_git(
+    workspace_id: String,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    let workspaces = state.workspaces.lock().await;
     let entry = workspaces
         .get(&workspace_id)
         .ok_or("workspace not found")?
@@ -663,6 +1748,108 @@ pub(crate) async fn pull_git(
     run_git_command(&repo_root, &["pull"]).await
 }
 
+#[tauri::command]
+pub(crate) async fn git_merge(
+    workspace_id: String,
+    branch: Option<String>,
+    no_ff: Option<bool>,
+    abort: Option<bool>,
+    state: State<'_, AppState>,
+) -> Result<GitMergeResult, String> {
+    let entry = {
+        let workspaces = state.workspaces.lock().await;
+        workspaces
+            .get(&workspace_id)
+            .cloned()
+            .ok_or("workspace not found")?
+    };
+
+    let repo_root = resolve_git_root(&entry)?;
+    merge_for_path(
+        &repo_root,
+        branch.as_deref(),
+        no_ff.unwrap_or(false),
+        abort.unwrap_or(false),
+    )
+    .await
+}
+
+#[tauri::command]
+pub(crate) async fn git_rebase(
+    workspace_id: String,
+    onto: Option<String>,
+    action: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<GitRebaseResult, String> {
+    let entry = {
+        let workspaces = state.workspaces.lock().await;
+        workspaces
+            .get(&workspace_id)
+            .cloned()
+            .ok_or("workspace not found")?
+    };
+
+    let repo_root = resolve_git_root(&entry)?;
+    rebase_for_path(
+        &repo_root,
+        action.as_deref().unwrap_or("start"),
+        onto.as_deref(),
+    )
+    .await
+}
+
+#[tauri::command]
+pub(crate) async fn git_fetch(
+    workspace_id: String,
+    remote: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    let entry = {
+        let workspaces = state.workspaces.lock().await;
+        workspaces
+            .get(&workspace_id)
+            .cloned()
+            .ok_or("workspace not found")?
+    };
+    let repo_root = resolve_git_root(&entry)?;
+    fetch_for_path(&repo_root, remote).await
+}
+
+async fn fetch_for_path(repo_root: &Path, remote: Option<String>) -> Result<(), String> {
+    let remote = remote
+        .map(|remote| remote.trim().to_string())
+        .filter(|remote| !remote.is_empty())
+        .unwrap_or_else(|| "origin".to_string());
+    // A leading `-` would make git read it as an option like `--upload-pack`.
+    if remote.starts_with('-') {
+        return Err(format!("Invalid remote: {remote}"));
+    }
+    run_git_command(repo_root, &["fetch", remote.as_str()]).await
+}
+
+#[tauri::command]
+pub(crate) async fn git_pull(
+    workspace_id: String,
+    ff_only: Option<bool>,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<(), String> {
+    let entry = {
+        let workspaces = state.workspaces.lock().await;
+        workspaces
+            .get(&workspace_id)
+            .cloned()
+            .ok_or("workspace not found")?
+    };
+    let repo_root = resolve_git_root(&entry)?;
+    pull_for_path(&repo_root, ff_only.unwrap_or(false)).await?;
+    let _ = app.emit(
+        "git-status-changed",
+        json!({ "workspaceId": workspace_id, "reason": "pull" }),
+    );
+    Ok(())
+}
+
 #[tauri::command]
 pub(crate) async fn sync_git(
     workspace_id: String,
@@ -713,77 +1900,339 @@ pub(crate) async fn get_workspace_diff(
     collect_workspace_diff(&repo_root)
 }
 
+/// Which two states of a file to compare.
+#[derive(Clone, Copy)]
+enum FileDiffScope {
+    /// All pending changes, as `git_diffs_for_path` reports them.
+    HeadToWorkdir,
+    /// Staged changes only.
+    HeadToIndex,
+    /// Unstaged changes only.
+    IndexToWorkdir,
+}
+
+impl FileDiffScope {
+    fn from_staged(staged: Option<bool>) -> Self {
+        match staged {
+            None => FileDiffScope::HeadToWorkdir,
+            Some(true) => FileDiffScope::HeadToIndex,
+            Some(false) => FileDiffScope::IndexToWorkdir,
+        }
+    }
+}
+
+fn file_diff<'repo>(
+    repo: &'repo Repository,
+    path: &str,
+    scope: FileDiffScope,
+) -> Result<git2::Diff<'repo>, String> {
+    let head_tree = repo
+        .head()
+        .ok()
+        .and_then(|head| head.peel_to_tree().ok());
+
+    let mut options = DiffOptions::new();
+    options
+        .pathspec(path)
+        .disable_pathspec_match(true)
+        .include_untracked(true)
+        .recurse_untracked_dirs(true)
+        .show_untracked_content(true);
+
+    match scope {
+        FileDiffScope::HeadToWorkdir => {
+            repo.diff_tree_to_workdir_with_index(head_tree.as_ref(), Some(&mut options))
+        }
+        FileDiffScope::HeadToIndex => {
+            repo.diff_tree_to_index(head_tree.as_ref(), None, Some(&mut options))
+        }
+        FileDiffScope::IndexToWorkdir => repo.diff_index_to_workdir(None, Some(&mut options)),
+    }
+    .map_err(|e| e.to_string())
+}
+
+fn file_patch_for_path<'repo>(
+    repo: &'repo Repository,
+    path: &str,
+    scope: FileDiffScope,
+) -> Result<Option<git2::Patch<'repo>>, String> {
+    let diff = file_diff(repo, path, scope)?;
+    if diff.deltas().len() == 0 {
+        return Ok(None);
+    }
+    git2::Patch::from_diff(&diff, 0).map_err(|e| e.to_string())
+}
+
+fn structured_hunks(patch: &git2::Patch) -> Result<Vec<GitDiffHunk>, String> {
+    let mut hunks = Vec::new();
+    for hunk_index in 0..patch.num_hunks() {
+        let (hunk, line_count) = patch.hunk(hunk_index).map_err(|e| e.to_string())?;
+        let mut lines: Vec<GitDiffLine> = Vec::with_capacity(line_count);
+        for line_index in 0..line_count {
+            let line = patch
+                .line_in_hunk(hunk_index, line_index)
+                .map_err(|e| e.to_string())?;
+            let kind = match line.origin() {
+                '+' => "add",
+                '-' => "del",
+                ' ' => "ctx",
+                // "\ No newline at end of file" markers annotate the line
+                // before them rather than being content.
+                '=' | '>' | '<' => {
+                    if let Some(last) = lines.last_mut() {
+                        last.no_newline = true;
+                    }
+                    continue;
+                }
+                _ => continue,
+            };
+            let content = String::from_utf8_lossy(line.content());
+            lines.push(GitDiffLine {
+                kind: kind.to_string(),
+                content: content.strip_suffix('\n').unwrap_or(&content).to_string(),
+                old_line: line.old_lineno(),
+                new_line: line.new_lineno(),
+                no_newline: false,
+                highlights: None,
+            });
+        }
+        hunks.push(GitDiffHunk {
+            id: hunk_index,
+            header: String::from_utf8_lossy(hunk.header()).trim_end().to_string(),
+            old_start: hunk.old_start(),
+            old_lines: hunk.old_lines(),
+            new_start: hunk.new_start(),
+            new_lines: hunk.new_lines(),
+            lines,
+        });
+    }
+    Ok(hunks)
+}
+
+fn to_diff_spans(spans: Vec<(usize, usize)>) -> Vec<GitDiffSpan> {
+    spans
+        .into_iter()
+        .map(|(start, end)| GitDiffSpan { start, end })
+        .collect()
+}
+
+/// Pairs each run of removed lines with the added lines that follow it and
+/// highlights the words that changed between each pair.
+fn add_word_highlights(hunk: &mut GitDiffHunk) {
+    if hunk.lines.len() > MAX_WORD_DIFF_HUNK_LINES {
+        return;
+    }
+    let lines = &mut hunk.lines;
+    let mut index = 0;
+    while index < lines.len() {
+        if lines[index].kind != "del" {
+            index += 1;
+            continue;
+        }
+        let dels_start = index;
+        while index < lines.len() && lines[index].kind == "del" {
+            index += 1;
+        }
+        let adds_start = index;
+        while index < lines.len() && lines[index].kind == "add" {
+            index += 1;
+        }
+        let pairs = (adds_start - dels_start).min(index - adds_start);
+        for offset in 0..pairs {
+            let (old_index, new_index) = (dels_start + offset, adds_start + offset);
+            let Some((old_spans, new_spans)) =
+                word_diff(&lines[old_index].content, &lines[new_index].content)
+            else {
+                continue;
+            };
+            lines[old_index].highlights = Some(to_diff_spans(old_spans));
+            lines[new_index].highlights = Some(to_diff_spans(new_spans));
+        }
+    }
+}
+
+fn structured_file_diff_for_path(
+    repo_root: &Path,
+    path: &str,
+    scope: FileDiffScope,
+    word_diff: bool,
+) -> Result<GitStructuredFileDiff, String> {
+    let repo = Repository::open(repo_root).map_err(|e| e.to_string())?;
+    let path = normalize_git_path(path);
+    let mut hunks = match file_patch_for_path(&repo, &path, scope)? {
+        Some(patch) => structured_hunks(&patch)?,
+        None => Vec::new(),
+    };
+    if word_diff {
+        hunks.iter_mut().for_each(add_word_highlights);
+    }
+    Ok(GitStructuredFileDiff { path, hunks })
+}
+
+/// Diff of one file: staged changes (HEAD against the index) when `staged`,
+/// otherwise unstaged ones (the index against the working tree).
+fn file_diff_for_path(repo_root: &Path, path: &str, staged: bool) -> Result<GitFileDiff, String> {
+    let repo = Repository::open(repo_root).map_err(|e| e.to_string())?;
+    let path = normalize_git_path(path);
+    let scope = if staged {
+        FileDiffScope::HeadToIndex
+    } else {
+        FileDiffScope::IndexToWorkdir
+    };
+    let patch = file_patch_for_path(&repo, &path, scope)?;
+    let is_binary = patch
+        .as_ref()
+        .is_some_and(|patch| patch.delta().flags().is_binary());
+    let Some(mut patch) = patch.filter(|_| !is_binary) else {
+        return Ok(GitFileDiff {
+            path,
+            diff: String::new(),
+            additions: 0,
+            deletions: 0,
+            renamed_from: None,
+            is_binary,
+        });
+    };
+    let diff = diff_patch_to_string(&mut patch).map_err(|e| e.to_string())?;
+    let (_, additions, deletions) = patch.line_stats().map_err(|e| e.to_string())?;
+    Ok(GitFileDiff {
+        path,
+        diff,
+        additions,
+        deletions,
+        renamed_from: None,
+        is_binary: false,
+    })
+}
+
 #[tauri::command]
-pub(crate) async fn get_git_diffs(
+pub(crate) async fn get_git_file_diff(
     workspace_id: String,
+    path: String,
+    staged: Option<bool>,
     state: State<'_, AppState>,
-) -> Result<Vec<GitFileDiff>, String> {
-    let workspaces = state.workspaces.lock().await;
-    let entry = workspaces
-        .get(&workspace_id)
-        .ok_or("workspace not found")?
-        .clone();
+) -> Result<GitFileDiff, String> {
+    let entry = {
+        let workspaces = state.workspaces.lock().await;
+        workspaces
+            .get(&workspace_id)
+            .cloned()
+            .ok_or("workspace not found")?
+    };
+    let repo_root = resolve_git_root(&entry)?;
+    file_diff_for_path(&repo_root, &path, staged.unwrap_or(false))
+}
+
+#[tauri::command]
+pub(crate) async fn get_git_file_diff_structured(
+    workspace_id: String,
+    path: String,
+    staged: Option<bool>,
+    word_diff: Option<bool>,
+    state: State<'_, AppState>,
+) -> Result<GitStructuredFileDiff, String> {
+    let entry = {
+        let workspaces = state.workspaces.lock().await;
+        workspaces
+            .get(&workspace_id)
+            .cloned()
+            .ok_or("workspace not found")?
+    };
+    let repo_root = resolve_git_root(&entry)?;
+    structured_file_diff_for_path(
+        &repo_root,
+        &path,
+        FileDiffScope::from_staged(staged),
+        word_diff.unwrap_or(false),
+    )
+}
 
-    let repo_root = resolve_git_root(&entry)?;
-    let repo = Repository::open(&repo_root).map_err(|e| e.to_string())?;
-    let head_tree = repo
+fn local_blame_hunk(start_line: usize, line_count: usize) -> GitBlameHunk {
+    GitBlameHunk {
+        start_line,
+        line_count,
+        sha: None,
+        author: None,
+        timestamp: None,
+        local: true,
+    }
+}
+
+/// Blames a file against HEAD, overlaying the working tree copy so lines
+/// that are not committed yet come back as local modifications.
+fn git_blame_for_path(repo_root: &Path, path: &str) -> Result<Vec<GitBlameHunk>, String> {
+    let repo = Repository::open(repo_root).map_err(|e| e.to_string())?;
+    let path = normalize_git_path(path.trim());
+    let contents = std::fs::read(repo_root.join(&path)).ok();
+    let tracked = repo
         .head()
         .ok()
-        .and_then(|head| head.peel_to_tree().ok());
-
-    let mut options = DiffOptions::new();
-    options
-        .include_untracked(true)
-        .recurse_untracked_dirs(true)
-        .show_untracked_content(true);
+        .and_then(|head| head.peel_to_tree().ok())
+        .is_some_and(|tree| tree.get_path(Path::new(&path)).is_ok());
+    if !tracked {
+        let contents = contents.ok_or_else(|| format!("File not found: {path}"))?;
+        let line_count = String::from_utf8_lossy(&contents).lines().count();
+        if line_count == 0 {
+            return Ok(Vec::new());
+        }
+        return Ok(vec![local_blame_hunk(1, line_count)]);
+    }
 
-    let diff = match head_tree.as_ref() {
-        Some(tree) => repo
-            .diff_tree_to_workdir_with_index(Some(tree), Some(&mut options))
-            .map_err(|e| e.to_string())?,
-        None => repo
-            .diff_tree_to_workdir_with_index(None, Some(&mut options))
+    let committed = repo
+        .blame_file(Path::new(&path), None)
+        .map_err(|e| e.to_string())?;
+    let blame = match &contents {
+        Some(contents) => committed
+            .blame_buffer(contents)
             .map_err(|e| e.to_string())?,
+        None => committed,
     };
-
-    let mut results = Vec::new();
-    for (index, delta) in diff.deltas().enumerate() {
-        let path = delta
-            .new_file()
-            .path()
-            .or_else(|| delta.old_file().path());
-        let Some(path) = path else {
-            continue;
-        };
-        let patch = match git2::Patch::from_diff(&diff, index) {
-            Ok(patch) => patch,
-            Err(_) => continue,
-        };
-        let Some(mut patch) = patch else {
-            continue;
-        };
-        let content = match diff_patch_to_string(&mut patch) {
-            Ok(content) => content,
-            Err(_) => continue,
-        };
-        if content.trim().is_empty() {
+    // Hunks produced by `blame_buffer` carry no signatures, so authors are
+    // read from the commits themselves.
+    let mut hunks = Vec::with_capacity(blame.len());
+    for hunk in blame.iter() {
+        let start_line = hunk.final_start_line();
+        let line_count = hunk.lines_in_hunk();
+        let oid = hunk.final_commit_id();
+        if oid.is_zero() {
+            hunks.push(local_blame_hunk(start_line, line_count));
             continue;
         }
-        results.push(GitFileDiff {
-            path: normalize_git_path(path.to_string_lossy().as_ref()),
-            diff: content,
+        let commit = repo.find_commit(oid).map_err(|e| e.to_string())?;
+        hunks.push(GitBlameHunk {
+            start_line,
+            line_count,
+            sha: Some(oid.to_string()),
+            author: commit.author().name().map(str::to_string),
+            timestamp: Some(commit.time().seconds()),
+            local: false,
         });
     }
+    Ok(hunks)
+}
 
-    Ok(results)
+#[tauri::command]
+pub(crate) async fn git_blame(
+    workspace_id: String,
+    path: String,
+    state: State<'_, AppState>,
+) -> Result<Vec<GitBlameHunk>, String> {
+    let entry = {
+        let workspaces = state.workspaces.lock().await;
+        workspaces
+            .get(&workspace_id)
+            .cloned()
+            .ok_or("workspace not found")?
+    };
+    let repo_root = resolve_git_root(&entry)?;
+    git_blame_for_path(&repo_root, &path)
 }
 
 #[tauri::command]
-pub(crate) async fn get_git_log(
+pub(crate) async fn get_git_diffs(
     workspace_id: String,
-    limit: Option<usize>,
     state: State<'_, AppState>,
-) -> Result<GitLogResponse, String> {
+) -> Result<Vec<GitFileDiff>, String> {
     let workspaces = state.workspaces.lock().await;
     let entry = workspaces
         .get(&workspace_id)
@@ -791,7 +2240,41 @@ pub(crate) async fn get_git_log(
         .clone();
 
     let repo_root = resolve_git_root(&entry)?;
-    let repo = Repository::open(&repo_root).map_err(|e| e.to_string())?;
+    git_diffs_for_path(&repo_root)
+}
+
+#[tauri::command]
+pub(crate) async fn get_diff_digest(
+    workspace_id: String,
+    max_bytes: Option<usize>,
+    state: State<'_, AppState>,
+) -> Result<serde_json::Value, String> {
+    let entry = {
+        let workspaces = state.workspaces.lock().await;
+        workspaces
+            .get(&workspace_id)
+            .ok_or("workspace not found")?
+            .clone()
+    };
+
+    let repo_root = resolve_git_root(&entry)?;
+    diff_digest_for_path(&repo_root, max_bytes)
+}
+
+fn git_log_for_path(repo_root: &Path, limit: Option<usize>) -> Result<GitLogResponse, String> {
+    let repo = Repository::open(repo_root).map_err(|e| e.to_string())?;
+    if head_is_unborn(&repo) {
+        return Ok(GitLogResponse {
+            total: 0,
+            entries: Vec::new(),
+            ahead: 0,
+            behind: 0,
+            ahead_entries: Vec::new(),
+            behind_entries: Vec::new(),
+            upstream: None,
+            has_upstream: false,
+        });
+    }
     let max_items = limit.unwrap_or(40);
     let mut revwalk = repo.revwalk().map_err(|e| e.to_string())?;
     revwalk.push_head().map_err(|e| e.to_string())?;
@@ -882,10 +2365,27 @@ pub(crate) async fn get_git_log(
         behind,
         ahead_entries,
         behind_entries,
+        has_upstream: upstream.is_some(),
         upstream,
     })
 }
 
+#[tauri::command]
+pub(crate) async fn get_git_log(
+    workspace_id: String,
+    limit: Option<usize>,
+    state: State<'_, AppState>,
+) -> Result<GitLogResponse, String> {
+    let workspaces = state.workspaces.lock().await;
+    let entry = workspaces
+        .get(&workspace_id)
+        .ok_or("workspace not found")?
+        .clone();
+
+    let repo_root = resolve_git_root(&entry)?;
+    git_log_for_path(&repo_root, limit)
+}
+
 #[tauri::command]
 pub(crate) async fn get_git_commit_diff(
     workspace_id: String,
@@ -913,37 +2413,336 @@ pub(crate) async fn get_git_commit_diff(
         .diff_tree_to_tree(parent_tree.as_ref(), Some(&commit_tree), Some(&mut options))
         .map_err(|e| e.to_string())?;
 
-    let mut results = Vec::new();
-    for (index, delta) in diff.deltas().enumerate() {
-        let path = delta
-            .new_file()
-            .path()
-            .or_else(|| delta.old_file().path());
-        let Some(path) = path else {
-            continue;
-        };
-        let patch = match git2::Patch::from_diff(&diff, index) {
-            Ok(patch) => patch,
-            Err(_) => continue,
-        };
-        let Some(mut patch) = patch else {
-            continue;
-        };
-        let content = match diff_patch_to_string(&mut patch) {
-            Ok(content) => content,
-            Err(_) => continue,
-        };
-        if content.trim().is_empty() {
-            continue;
+    Ok(commit_diffs_from_diff(&diff))
+}
+
+/// Full commit metadata plus its patch against one parent (the first by
+/// default, an empty tree for root commits).
+fn commit_details_for_path(
+    repo_root: &Path,
+    sha: &str,
+    parent_index: usize,
+) -> Result<GitCommitDetails, String> {
+    let repo = Repository::open(repo_root).map_err(|e| e.to_string())?;
+    let commit = resolve_commit(&repo, sha)?;
+    commit_details(&repo, &commit, parent_index)
+}
+
+fn commit_details(
+    repo: &Repository,
+    commit: &git2::Commit,
+    parent_index: usize,
+) -> Result<GitCommitDetails, String> {
+    let parent_tree = if commit.parent_count() == 0 {
+        None
+    } else {
+        let parent = commit.parent(parent_index).map_err(|_| {
+            format!(
+                "Commit {} has {} parent(s); parentIndex {parent_index} is out of range",
+                commit.id(),
+                commit.parent_count()
+            )
+        })?;
+        Some(parent.tree().map_err(|e| e.to_string())?)
+    };
+    let commit_tree = commit.tree().map_err(|e| e.to_string())?;
+    let mut options = DiffOptions::new();
+    let diff = repo
+        .diff_tree_to_tree(parent_tree.as_ref(), Some(&commit_tree), Some(&mut options))
+        .map_err(|e| e.to_string())?;
+
+    let author = commit.author();
+    let committer = commit.committer();
+    Ok(GitCommitDetails {
+        sha: commit.id().to_string(),
+        summary: commit.summary().unwrap_or("").to_string(),
+        message: String::from_utf8_lossy(commit.message_bytes()).to_string(),
+        author: author.name().unwrap_or("").to_string(),
+        author_email: author.email().unwrap_or("").to_string(),
+        committer: committer.name().unwrap_or("").to_string(),
+        committer_email: committer.email().unwrap_or("").to_string(),
+        timestamp: commit.time().seconds(),
+        parents: commit.parent_ids().map(|id| id.to_string()).collect(),
+        files: commit_diffs_from_diff(&diff),
+    })
+}
+
+/// Checks that `rev` resolves, returning the object it names. `shortName` is
+/// the ref shorthand (e.g. `main`, `v1.0`) when `rev` names a reference, and
+/// the abbreviated SHA otherwise.
+fn resolve_ref_for_path(repo_root: &Path, rev: &str) -> Result<GitResolvedRef, String> {
+    let repo = Repository::open(repo_root).map_err(|e| e.to_string())?;
+    let rev = rev.trim();
+    if rev.is_empty() {
+        return Err("Ref is required.".to_string());
+    }
+    let (object, reference) = repo
+        .revparse_ext(rev)
+        .map_err(|_| format!("Unknown ref: {rev}"))?;
+    let short_name = match reference.as_ref().and_then(|reference| reference.shorthand()) {
+        Some(name) => name.to_string(),
+        None => object
+            .short_id()
+            .ok()
+            .and_then(|id| id.as_str().map(str::to_string))
+            .unwrap_or_else(|| object.id().to_string()),
+    };
+    Ok(GitResolvedRef {
+        sha: object.id().to_string(),
+        kind: object.kind().map(|kind| kind.str()).unwrap_or("any").to_string(),
+        short_name,
+    })
+}
+
+#[tauri::command]
+pub(crate) async fn resolve_ref(
+    workspace_id: String,
+    rev: String,
+    state: State<'_, AppState>,
+) -> Result<GitResolvedRef, String> {
+    let entry = {
+        let workspaces = state.workspaces.lock().await;
+        workspaces
+            .get(&workspace_id)
+            .cloned()
+            .ok_or("workspace not found")?
+    };
+    let repo_root = resolve_git_root(&entry)?;
+    resolve_ref_for_path(&repo_root, &rev)
+}
+
+/// Resolves `rev` (a SHA, tag, branch or any revspec) and describes the object
+/// it names, the way `git show` does: commits come with their diff, annotated
+/// tags with their message and target, blobs and trees with a summary.
+fn show_for_path(repo_root: &Path, rev: &str) -> Result<GitShowResult, String> {
+    let repo = Repository::open(repo_root).map_err(|e| e.to_string())?;
+    let rev = rev.trim();
+    if rev.is_empty() {
+        return Err("Ref is required.".to_string());
+    }
+    let object = repo
+        .revparse_single(rev)
+        .map_err(|_| format!("Unknown ref: {rev}"))?;
+    if let Some(commit) = object.as_commit() {
+        return commit_details(&repo, commit, 0).map(GitShowResult::Commit);
+    }
+    if let Some(tag) = object.as_tag() {
+        let tagger = tag.tagger();
+        return Ok(GitShowResult::Tag(GitTagDetails {
+            sha: tag.id().to_string(),
+            name: tag.name().unwrap_or("").to_string(),
+            message: tag.message().unwrap_or("").to_string(),
+            tagger: tagger
+                .as_ref()
+                .and_then(|sig| sig.name())
+                .map(str::to_string),
+            tagger_email: tagger
+                .as_ref()
+                .and_then(|sig| sig.email())
+                .map(str::to_string),
+            timestamp: tagger.as_ref().map(|sig| sig.when().seconds()),
+            target_type: tag
+                .target_type()
+                .map(|kind| kind.str().to_string())
+                .unwrap_or_default(),
+            target_sha: tag.target_id().to_string(),
+        }));
+    }
+    if let Some(blob) = object.as_blob() {
+        return Ok(GitShowResult::Blob(GitBlobDetails {
+            sha: blob.id().to_string(),
+            size: blob.size(),
+            is_binary: blob.is_binary(),
+        }));
+    }
+    if let Some(tree) = object.as_tree() {
+        return Ok(GitShowResult::Tree(GitTreeDetails {
+            sha: tree.id().to_string(),
+            entries: tree
+                .iter()
+                .filter_map(|entry| entry.name().map(str::to_string))
+                .collect(),
+        }));
+    }
+    Err(format!("Unsupported object for ref: {rev}"))
+}
+
+#[tauri::command]
+pub(crate) async fn git_show(
+    workspace_id: String,
+    rev: String,
+    state: State<'_, AppState>,
+) -> Result<GitShowResult, String> {
+    let entry = {
+        let workspaces = state.workspaces.lock().await;
+        workspaces
+            .get(&workspace_id)
+            .cloned()
+            .ok_or("workspace not found")?
+    };
+    let repo_root = resolve_git_root(&entry)?;
+    show_for_path(&repo_root, &rev)
+}
+
+#[tauri::command]
+pub(crate) async fn get_commit_details(
+    workspace_id: String,
+    sha: String,
+    parent_index: Option<usize>,
+    state: State<'_, AppState>,
+) -> Result<GitCommitDetails, String> {
+    let entry = {
+        let workspaces = state.workspaces.lock().await;
+        workspaces
+            .get(&workspace_id)
+            .cloned()
+            .ok_or("workspace not found")?
+    };
+    let repo_root = resolve_git_root(&entry)?;
+    commit_details_for_path(&repo_root, &sha, parent_index.unwrap_or(0))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DiffRangeMode {
+    /// `base..head`: compare the two trees directly.
+    TwoDot,
+    /// `base...head`: compare head against its merge-base with base, like a PR.
+    ThreeDot,
+}
+
+fn parse_diff_range_mode(mode: Option<&str>) -> Result<DiffRangeMode, String> {
+    match mode.map(|value| value.trim()).filter(|value| !value.is_empty()) {
+        None | Some("threeDot") => Ok(DiffRangeMode::ThreeDot),
+        Some("twoDot") => Ok(DiffRangeMode::TwoDot),
+        Some(other) => Err(format!(
+            "Invalid diff mode: {other} (expected twoDot or threeDot)"
+        )),
+    }
+}
+
+fn diff_range_for_path(
+    repo_root: &Path,
+    base: &str,
+    head: &str,
+    mode: DiffRangeMode,
+) -> Result<Vec<GitCommitDiff>, String> {
+    let repo = Repository::open(repo_root).map_err(|e| e.to_string())?;
+    let head_commit = resolve_commit(&repo, head)?;
+    let base_commit = match mode {
+        DiffRangeMode::TwoDot => resolve_commit(&repo, base)?,
+        DiffRangeMode::ThreeDot => {
+            let merge_base = merge_base_for_path(repo_root, base, head)?;
+            resolve_commit(&repo, &merge_base)?
         }
-        results.push(GitCommitDiff {
-            path: normalize_git_path(path.to_string_lossy().as_ref()),
-            status: status_for_delta(delta.status()).to_string(),
-            diff: content,
-        });
+    };
+    let base_tree = base_commit.tree().map_err(|e| e.to_string())?;
+    let head_tree = head_commit.tree().map_err(|e| e.to_string())?;
+
+    let mut options = DiffOptions::new();
+    let diff = repo
+        .diff_tree_to_tree(Some(&base_tree), Some(&head_tree), Some(&mut options))
+        .map_err(|e| e.to_string())?;
+
+    Ok(commit_diffs_from_diff(&diff))
+}
+
+#[tauri::command]
+pub(crate) async fn get_git_diff_range(
+    workspace_id: String,
+    base: String,
+    head: String,
+    mode: Option<String>,
+    state: State<'_, AppState>,
+) -> Result<Vec<GitCommitDiff>, String> {
+    let mode = parse_diff_range_mode(mode.as_deref())?;
+    let entry = {
+        let workspaces = state.workspaces.lock().await;
+        workspaces
+            .get(&workspace_id)
+            .cloned()
+            .ok_or("workspace not found")?
+    };
+
+    let repo_root = resolve_git_root(&entry)?;
+    diff_range_for_path(&repo_root, &base, &head, mode)
+}
+
+fn resolve_commit<'repo>(
+    repo: &'repo Repository,
+    rev: &str,
+) -> Result<git2::Commit<'repo>, String> {
+    let rev = rev.trim();
+    if rev.is_empty() {
+        return Err("Ref is required.".to_string());
+    }
+    repo.revparse_single(rev)
+        .and_then(|object| object.peel_to_commit())
+        .map_err(|_| format!("Unknown ref: {rev}"))
+}
+
+fn merge_base_for_path(repo_root: &Path, base: &str, head: &str) -> Result<String, String> {
+    let repo = Repository::open(repo_root).map_err(|e| e.to_string())?;
+    let base_commit = resolve_commit(&repo, base)?;
+    let head_commit = resolve_commit(&repo, head)?;
+    let merge_base = repo
+        .merge_base(base_commit.id(), head_commit.id())
+        .map_err(|_| format!("No merge base between {} and {}", base.trim(), head.trim()))?;
+    Ok(merge_base.to_string())
+}
+
+#[tauri::command]
+pub(crate) async fn get_merge_base(
+    workspace_id: String,
+    base: String,
+    head: String,
+    state: State<'_, AppState>,
+) -> Result<String, String> {
+    let entry = {
+        let workspaces = state.workspaces.lock().await;
+        workspaces
+            .get(&workspace_id)
+            .cloned()
+            .ok_or("workspace not found")?
+    };
+
+    let repo_root = resolve_git_root(&entry)?;
+    merge_base_for_path(&repo_root, &base, &head)
+}
+
+/// Matches `git merge-base --is-ancestor`: a commit counts as its own
+/// ancestor.
+fn is_ancestor_for_path(
+    repo_root: &Path,
+    ancestor: &str,
+    descendant: &str,
+) -> Result<bool, String> {
+    let repo = Repository::open(repo_root).map_err(|e| e.to_string())?;
+    let ancestor_commit = resolve_commit(&repo, ancestor)?;
+    let descendant_commit = resolve_commit(&repo, descendant)?;
+    if ancestor_commit.id() == descendant_commit.id() {
+        return Ok(true);
     }
+    repo.graph_descendant_of(descendant_commit.id(), ancestor_commit.id())
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub(crate) async fn is_ancestor(
+    workspace_id: String,
+    ancestor: String,
+    descendant: String,
+    state: State<'_, AppState>,
+) -> Result<bool, String> {
+    let entry = {
+        let workspaces = state.workspaces.lock().await;
+        workspaces
+            .get(&workspace_id)
+            .cloned()
+            .ok_or("workspace not found")?
+    };
 
-    Ok(results)
+    let repo_root = resolve_git_root(&entry)?;
+    is_ancestor_for_path(&repo_root, &ancestor, &descendant)
 }
 
 #[tauri::command]
@@ -992,6 +2791,7 @@ pub(crate) async fn get_github_issues(
     let repo_name = github_repo_from_path(&repo_root)?;
 
     let output = Command::new("gh")
+        .envs(proxy_env())
         .args([
             "issue",
             "list",
@@ -1027,6 +2827,7 @@ pub(crate) async fn get_github_issues(
     let search_query = format!("repo:{repo_name} is:issue is:open");
     let search_query = search_query.replace(' ', "+");
     let total = match Command::new("gh")
+        .envs(proxy_env())
         .args([
             "api",
             &format!("/search/issues?q={search_query}"),
@@ -1062,6 +2863,7 @@ pub(crate) async fn get_github_pull_requests(
     let repo_name = github_repo_from_path(&repo_root)?;
 
     let output = Command::new("gh")
+        .envs(proxy_env())
         .args([
             "pr",
             "list",
@@ -1099,6 +2901,7 @@ pub(crate) async fn get_github_pull_requests(
     let search_query = format!("repo:{repo_name} is:pr is:open");
     let search_query = search_query.replace(' ', "+");
     let total = match Command::new("gh")
+        .envs(proxy_env())
         .args([
             "api",
             &format!("/search/issues?q={search_query}"),
@@ -1138,6 +2941,7 @@ pub(crate) async fn get_github_pull_request_diff(
     let repo_name = github_repo_from_path(&repo_root)?;
 
     let output = Command::new("gh")
+        .envs(proxy_env())
         .args([
             "pr",
             "diff",
@@ -1190,6 +2994,7 @@ pub(crate) async fn get_github_pull_request_comments(
     let jq_filter = r#"[.[] | {id, body, createdAt: .created_at, url: .html_url, author: (if .user then {login: .user.login} else null end)}]"#;
 
     let output = Command::new("gh")
+        .envs(proxy_env())
         .args(["api", &comments_endpoint, "--jq", jq_filter])
         .current_dir(&repo_root)
         .output()
@@ -1210,95 +3015,608 @@ pub(crate) async fn get_github_pull_request_comments(
         return Err(detail.to_string());
     }
 
-    let comments: Vec<GitHubPullRequestComment> =
-        serde_json::from_slice(&output.stdout).map_err(|e| e.to_string())?;
+    let comments: Vec<GitHubPullRequestComment> =
+        serde_json::from_slice(&output.stdout).map_err(|e| e.to_string())?;
+
+    Ok(comments)
+}
+
+#[tauri::command]
+pub(crate) async fn list_git_branches(
+    workspace_id: String,
+    state: State<'_, AppState>,
+) -> Result<serde_json::Value, String> {
+    let workspaces = state.workspaces.lock().await;
+    let entry = workspaces
+        .get(&workspace_id)
+        .ok_or("workspace not found")?
+        .clone();
+    let repo_root = resolve_git_root(&entry)?;
+    let repo = Repository::open(&repo_root).map_err(|e| e.to_string())?;
+    let mut branches = Vec::new();
+    let refs = repo
+        .branches(Some(BranchType::Local))
+        .map_err(|e| e.to_string())?;
+    for branch_result in refs {
+        let (branch, _) = branch_result.map_err(|e| e.to_string())?;
+        let name = branch.name().ok().flatten().unwrap_or("").to_string();
+        if name.is_empty() {
+            continue;
+        }
+        let last_commit = branch
+            .get()
+            .target()
+            .and_then(|oid| repo.find_commit(oid).ok())
+            .map(|commit| commit.time().seconds())
+            .unwrap_or(0);
+        branches.push(BranchInfo { name, last_commit });
+    }
+    branches.sort_by(|a, b| b.last_commit.cmp(&a.last_commit));
+    Ok(json!({ "branches": branches }))
+}
+
+#[tauri::command]
+pub(crate) async fn checkout_git_branch(
+    workspace_id: String,
+    name: String,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    let workspaces = state.workspaces.lock().await;
+    let entry = workspaces
+        .get(&workspace_id)
+        .ok_or("workspace not found")?
+        .clone();
+    let repo_root = resolve_git_root(&entry)?;
+    let repo = Repository::open(&repo_root).map_err(|e| e.to_string())?;
+    checkout_branch(&repo, &name).map_err(|e| e.to_string())
+}
+
+const RECENT_BRANCHES_LIMIT: usize = 10;
+
+/// Lists local branches in the order they were last checked out, newest
+/// first, based on the `checkout: moving from A to B` entries of the HEAD
+/// reflog. Deleted branches and detached checkouts are skipped.
+fn recent_branches_for_path(repo_root: &Path, limit: usize) -> Result<Vec<BranchInfo>, String> {
+    let repo = Repository::open(repo_root).map_err(|e| e.to_string())?;
+    let reflog = repo.reflog("HEAD").map_err(|e| e.to_string())?;
+    let mut seen = HashSet::new();
+    let mut branches = Vec::new();
+    for entry in reflog.iter() {
+        let Some(moves) = entry
+            .message()
+            .and_then(|message| message.strip_prefix("checkout: moving from "))
+        else {
+            continue;
+        };
+        let Some((from, to)) = moves.split_once(" to ") else {
+            continue;
+        };
+        for name in [to.trim(), from.trim()] {
+            if branches.len() >= limit {
+                return Ok(branches);
+            }
+            if !seen.insert(name.to_string()) {
+                continue;
+            }
+            let Ok(branch) = repo.find_branch(name, BranchType::Local) else {
+                continue;
+            };
+            let last_commit = branch
+                .get()
+                .peel_to_commit()
+                .map(|commit| commit.time().seconds())
+                .unwrap_or(0);
+            branches.push(BranchInfo {
+                name: name.to_string(),
+                last_commit,
+            });
+        }
+    }
+    Ok(branches)
+}
+
+#[tauri::command]
+pub(crate) async fn list_recent_branches(
+    workspace_id: String,
+    limit: Option<usize>,
+    state: State<'_, AppState>,
+) -> Result<serde_json::Value, String> {
+    let entry = {
+        let workspaces = state.workspaces.lock().await;
+        workspaces
+            .get(&workspace_id)
+            .cloned()
+            .ok_or("workspace not found")?
+    };
+    let repo_root = resolve_git_root(&entry)?;
+    let branches =
+        recent_branches_for_path(&repo_root, limit.unwrap_or(RECENT_BRANCHES_LIMIT))?;
+    Ok(json!({ "branches": branches }))
+}
+
+fn create_branch_for_path(repo_root: &Path, name: &str) -> Result<(), String> {
+    let repo = Repository::open(repo_root).map_err(|e| e.to_string())?;
+    if head_is_unborn(&repo) {
+        return Err(
+            "Cannot create a branch: this repository has no commits yet. Make an initial commit first."
+                .to_string(),
+        );
+    }
+    let head = repo.head().map_err(|e| e.to_string())?;
+    let target = head.peel_to_commit().map_err(|e| e.to_string())?;
+    repo.branch(name, &target, false)
+        .map_err(|e| e.to_string())?;
+    checkout_branch(&repo, name).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub(crate) async fn create_git_branch(
+    workspace_id: String,
+    name: String,
+    state: State<'_, AppState>,
+) -> Result<(), String> {
+    let workspaces = state.workspaces.lock().await;
+    let entry = workspaces
+        .get(&workspace_id)
+        .ok_or("workspace not found")?
+        .clone();
+    let repo_root = resolve_git_root(&entry)?;
+    create_branch_for_path(&repo_root, &name)
+}
+
+/// Recreates a local branch at `sha` (usually taken from the reflog) without
+/// checking it out. An existing branch with the same name is never moved.
+fn restore_branch_for_path(repo_root: &Path, name: &str, sha: &str) -> Result<BranchInfo, String> {
+    let repo = Repository::open(repo_root).map_err(|e| e.to_string())?;
+    if repo.find_branch(name, BranchType::Local).is_ok() {
+        return Err(format!("Branch already exists: {name}"));
+    }
+    let commit = repo
+        .revparse_single(sha)
+        .and_then(|object| object.peel_to_commit())
+        .map_err(|_| format!("Unknown commit: {sha}"))?;
+    repo.branch(name, &commit, false)
+        .map_err(|e| e.to_string())?;
+    Ok(BranchInfo {
+        name: name.to_string(),
+        last_commit: commit.time().seconds(),
+    })
+}
+
+#[tauri::command]
+pub(crate) async fn restore_branch(
+    workspace_id: String,
+    name: String,
+    sha: String,
+    state: State<'_, AppState>,
+) -> Result<BranchInfo, String> {
+    let entry = {
+        let workspaces = state.workspaces.lock().await;
+        workspaces
+            .get(&workspace_id)
+            .cloned()
+            .ok_or("workspace not found")?
+    };
+    let repo_root = resolve_git_root(&entry)?;
+    restore_branch_for_path(&repo_root, &name, &sha)
+}
+
+#[tauri::command]
+pub(crate) async fn git_push(
+    workspace_id: String,
+    remote: Option<String>,
+    branch: Option<String>,
+    set_upstream: Option<bool>,
+    state: State<'_, AppState>,
+) -> Result<GitPushResult, String> {
+    let entry = {
+        let workspaces = state.workspaces.lock().await;
+        workspaces
+            .get(&workspace_id)
+            .cloned()
+            .ok_or("workspace not found")?
+    };
+    let repo_root = resolve_git_root(&entry)?;
+    git_push_for_path(&repo_root, remote, branch, set_upstream.unwrap_or(false)).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn create_temp_repo() -> (PathBuf, Repository) {
+        let root = std::env::temp_dir().join(format!(
+            "codex-monitor-test-{}",
+            uuid::Uuid::new_v4()
+        ));
+        fs::create_dir_all(&root).expect("create temp repo root");
+        let repo = Repository::init(&root).expect("init repo");
+        (root, repo)
+    }
+
+    fn block_on<F: std::future::Future>(future: F) -> F::Output {
+        tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .expect("runtime")
+            .block_on(future)
+    }
+
+    fn commit_file(root: &Path, repo: &Repository, name: &str, contents: &str) -> git2::Oid {
+        fs::write(root.join(name), contents).expect("write file");
+        let mut index = repo.index().expect("repo index");
+        index.add_path(Path::new(name)).expect("add path");
+        index.write().expect("write index");
+        let tree_id = index.write_tree().expect("write tree");
+        let tree = repo.find_tree(tree_id).expect("find tree");
+        let sig = git2::Signature::now("Test", "test@example.com").expect("signature");
+        let parent = repo.head().ok().and_then(|head| head.peel_to_commit().ok());
+        let parents: Vec<&git2::Commit> = parent.iter().collect();
+        repo.commit(Some("HEAD"), &sig, &sig, name, &tree, &parents)
+            .expect("commit")
+    }
+
+    #[test]
+    fn is_dirty_for_path_reports_clean_and_dirty_trees() {
+        let (root, repo) = create_temp_repo();
+        commit_file(&root, &repo, "a.txt", "hello\n");
+        assert!(!is_dirty_for_path(&root).expect("clean status"));
+
+        fs::write(root.join("untracked.txt"), "new\n").expect("write untracked");
+        assert!(is_dirty_for_path(&root).expect("untracked status"));
+
+        fs::remove_file(root.join("untracked.txt")).expect("remove untracked");
+        fs::write(root.join("a.txt"), "changed\n").expect("modify tracked");
+        assert!(is_dirty_for_path(&root).expect("modified status"));
+    }
+
+    #[test]
+    fn git_status_for_path_scopes_to_subdir() {
+        let (root, repo) = create_temp_repo();
+        commit_file(&root, &repo, "root.txt", "root\n");
+        fs::create_dir_all(root.join("packages/app")).expect("create package dir");
+        fs::write(root.join("packages/app/index.ts"), "one\ntwo\n").expect("write package file");
+        fs::write(root.join("root.txt"), "root\nchanged\n").expect("modify root file");
+
+        let status =
+            git_status_for_path(&root, Some("./packages/app/"), None).expect("scoped status");
+        let files = status["files"].as_array().expect("files");
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0]["path"], "packages/app/index.ts");
+        assert_eq!(status["totalAdditions"], 2);
+
+        let status = git_status_for_path(&root, None, None).expect("full status");
+        assert_eq!(status["files"].as_array().expect("files").len(), 2);
+    }
+
+    #[test]
+    fn git_status_summary_reports_dirty_and_upstream_counts() {
+        let (root, repo) = create_temp_repo();
+        commit_file(&root, &repo, "a.txt", "hello\n");
+        let summary = git_status_summary_for_path(&root, None).expect("summary");
+        assert_eq!(summary["dirty"], false);
+        assert!(summary["upstream"].is_null());
+        assert_eq!(summary["ahead"], 0);
+
+        let head = repo.head().expect("head").peel_to_commit().expect("head commit");
+        repo.branch("base", &head, false).expect("create base");
+        let branch_name = repo.head().expect("head").shorthand().expect("name").to_string();
+        let mut branch = repo
+            .find_branch(&branch_name, BranchType::Local)
+            .expect("current branch");
+        branch.set_upstream(Some("base")).expect("set upstream");
+        commit_file(&root, &repo, "b.txt", "more\n");
+        fs::write(root.join("a.txt"), "changed\n").expect("modify tracked");
+
+        let summary = git_status_summary_for_path(&root, None).expect("summary");
+        assert_eq!(summary["dirty"], true);
+        assert_eq!(summary["upstream"], "base");
+        assert_eq!(summary["ahead"], 1);
+        assert_eq!(summary["behind"], 0);
+    }
+
+    #[test]
+    fn git_status_for_path_reports_rename_source() {
+        let (root, repo) = create_temp_repo();
+        commit_file(&root, &repo, "old.txt", "alpha\nbeta\ngamma\ndelta\n");
+        fs::rename(root.join("old.txt"), root.join("new.txt")).expect("rename file");
+        let mut index = repo.index().expect("repo index");
+        index.remove_path(Path::new("old.txt")).expect("remove old path");
+        index.add_path(Path::new("new.txt")).expect("add new path");
+        index.write().expect("write index");
+
+        let status = git_status_for_path(&root, None, None).expect("status");
+        let staged = status["stagedFiles"].as_array().expect("staged files");
+        assert_eq!(staged.len(), 1);
+        assert_eq!(staged[0]["status"], "R");
+        assert_eq!(staged[0]["path"], "new.txt");
+        assert_eq!(staged[0]["oldPath"], "old.txt");
+        assert_eq!(status["files"][0]["oldPath"], "old.txt");
+    }
+
+    #[test]
+    fn git_status_for_path_splits_index_and_worktree_status() {
+        let (root, repo) = create_temp_repo();
+        commit_file(&root, &repo, "a.txt", "one\n");
+        fs::write(root.join("a.txt"), "two\n").expect("modify file");
+        let mut index = repo.index().expect("repo index");
+        index.add_path(Path::new("a.txt")).expect("stage file");
+        index.write().expect("write index");
+        fs::write(root.join("a.txt"), "three\n").expect("modify again");
+        fs::write(root.join("new.txt"), "new\n").expect("untracked file");
+
+        let status = git_status_for_path(&root, None, None).expect("status");
+        let files = status["files"].as_array().expect("files");
+        let file = |path: &str| {
+            files
+                .iter()
+                .find(|file| file["path"] == path)
+                .expect("file entry")
+                .clone()
+        };
+        let modified = file("a.txt");
+        assert_eq!(modified["status"], "M");
+        assert_eq!(modified["indexStatus"], "M");
+        assert_eq!(modified["worktreeStatus"], "M");
+        let untracked = file("new.txt");
+        assert_eq!(untracked["indexStatus"], serde_json::Value::Null);
+        assert_eq!(untracked["worktreeStatus"], "A");
+    }
+
+    #[test]
+    fn unborn_head_is_handled_without_errors() {
+        let (root, _repo) = create_temp_repo();
+        fs::write(root.join("README.md"), "hello\n").expect("write file");
+
+        let log = git_log_for_path(&root, None).expect("empty log");
+        assert_eq!(log.total, 0);
+        assert!(log.entries.is_empty());
+
+        let status = git_status_for_path(&root, None, None).expect("status");
+        assert_eq!(status["files"].as_array().expect("files").len(), 1);
+
+        let error = create_branch_for_path(&root, "feature").expect_err("no commits yet");
+        assert!(error.contains("no commits yet"));
+    }
+
+    #[test]
+    fn worktree_status_diff_and_log_use_worktree_head_and_index() {
+        let (root, repo) = create_temp_repo();
+        commit_file(&root, &repo, "shared.txt", "base\n");
+
+        let worktree_path = std::env::temp_dir().join(format!(
+            "codex-monitor-worktree-{}",
+            uuid::Uuid::new_v4()
+        ));
+        let worktree = repo
+            .worktree("feature", &worktree_path, None)
+            .expect("create worktree");
+        let worktree_repo = Repository::open_from_worktree(&worktree).expect("open worktree");
+        commit_file(&worktree_path, &worktree_repo, "feature.txt", "feature\n");
+        fs::write(worktree_path.join("shared.txt"), "base\nworktree\n")
+            .expect("modify worktree file");
+
+        let status = git_status_for_path(&worktree_path, None, None).expect("worktree status");
+        assert_eq!(status["branchName"], "feature");
+        let files = status["files"].as_array().expect("files");
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0]["path"], "shared.txt");
+
+        let diffs = git_diffs_for_path(&worktree_path).expect("worktree diffs");
+        assert_eq!(diffs.len(), 1);
+        assert!(diffs[0].diff.contains("+worktree"));
+
+        let log = git_log_for_path(&worktree_path, None).expect("worktree log");
+        assert_eq!(log.total, 2);
+        assert!(log.entries.iter().any(|entry| entry.summary == "feature.txt"));
+
+        let main_status = git_status_for_path(&root, None, None).expect("main status");
+        assert!(main_status["files"].as_array().expect("files").is_empty());
+        let main_log = git_log_for_path(&root, None).expect("main log");
+        assert_eq!(main_log.total, 1);
+    }
+
+    #[test]
+    fn merge_base_for_path_returns_fork_point() {
+        let (root, repo) = create_temp_repo();
+        let fork = commit_file(&root, &repo, "base.txt", "base\n");
+        let fork_commit = repo.find_commit(fork).expect("fork commit");
+        repo.branch("feature", &fork_commit, false)
+            .expect("create feature branch");
+        commit_file(&root, &repo, "main.txt", "main\n");
+
+        let base = merge_base_for_path(&root, "HEAD", "feature").expect("merge base");
+        assert_eq!(base, fork.to_string());
+
+        let error = merge_base_for_path(&root, "HEAD", "missing").expect_err("unknown ref");
+        assert!(error.contains("Unknown ref"));
+    }
+
+    #[test]
+    fn is_ancestor_for_path_follows_history() {
+        let (root, repo) = create_temp_repo();
+        let fork = commit_file(&root, &repo, "base.txt", "base\n");
+        let fork_commit = repo.find_commit(fork).expect("fork commit");
+        repo.branch("feature", &fork_commit, false)
+            .expect("create feature branch");
+        commit_file(&root, &repo, "main.txt", "main\n");
+
+        assert!(is_ancestor_for_path(&root, "feature", "HEAD").expect("fork is ancestor"));
+        assert!(!is_ancestor_for_path(&root, "HEAD", "feature").expect("tip is not"));
+        assert!(is_ancestor_for_path(&root, "HEAD", "HEAD").expect("same commit"));
+        let error = is_ancestor_for_path(&root, "missing", "HEAD").expect_err("unknown ref");
+        assert!(error.contains("Unknown ref"));
+    }
+
+    #[test]
+    fn diff_range_for_path_distinguishes_two_and_three_dot() {
+        let (root, repo) = create_temp_repo();
+        let fork = commit_file(&root, &repo, "base.txt", "base\n");
+        let fork_commit = repo.find_commit(fork).expect("fork commit");
+        repo.branch("feature", &fork_commit, false)
+            .expect("create feature branch");
+        let main_tip = commit_file(&root, &repo, "main-only.txt", "main\n").to_string();
+
+        let blob = repo.blob(b"feature\n").expect("feature blob");
+        let mut builder = repo
+            .treebuilder(Some(&fork_commit.tree().expect("fork tree")))
+            .expect("tree builder");
+        builder
+            .insert("feature.txt", blob, 0o100644)
+            .expect("insert feature file");
+        let tree = repo
+            .find_tree(builder.write().expect("write tree"))
+            .expect("find tree");
+        let sig = git2::Signature::now("Test", "test@example.com").expect("signature");
+        repo.commit(
+            Some("refs/heads/feature"),
+            &sig,
+            &sig,
+            "feature",
+            &tree,
+            &[&fork_commit],
+        )
+        .expect("commit feature");
+
+        let three_dot = diff_range_for_path(&root, &main_tip, "feature", DiffRangeMode::ThreeDot)
+            .expect("three-dot diff");
+        let paths: Vec<&str> = three_dot.iter().map(|diff| diff.path.as_str()).collect();
+        assert_eq!(paths, vec!["feature.txt"]);
+
+        let two_dot = diff_range_for_path(&root, &main_tip, "feature", DiffRangeMode::TwoDot)
+            .expect("two-dot diff");
+        let deleted = two_dot
+            .iter()
+            .find(|diff| diff.path == "main-only.txt")
+            .expect("main-only change in two-dot diff");
+        assert_eq!(deleted.status, "D");
+        assert_eq!(two_dot.len(), 2);
+
+        assert!(parse_diff_range_mode(Some("fourDot")).is_err());
+        assert_eq!(parse_diff_range_mode(None), Ok(DiffRangeMode::ThreeDot));
+    }
+
+    #[test]
+    fn git_status_for_path_caps_per_file_stats() {
+        let (root, repo) = create_temp_repo();
+        commit_file(&root, &repo, "seed.txt", "seed\n");
+        for name in ["a.txt", "b.txt", "c.txt"] {
+            fs::write(root.join(name), "line\n").expect("write file");
+        }
+
+        let status = git_status_for_path(&root, None, Some(1)).expect("status");
+        let files = status["files"].as_array().expect("files");
+        assert_eq!(files.len(), 3);
+        let computed: Vec<Option<i64>> = files
+            .iter()
+            .map(|file| file["additions"].as_i64())
+            .collect();
+        assert_eq!(computed.iter().filter(|value| **value == Some(1)).count(), 1);
+        assert_eq!(computed.iter().filter(|value| value.is_none()).count(), 2);
+        assert_eq!(status["totalAdditions"], 1);
+    }
+
+    #[test]
+    fn git_status_for_path_counts_stashes() {
+        let (root, mut repo) = create_temp_repo();
+        commit_file(&root, &repo, "a.txt", "one\n");
+        let status = git_status_for_path(&root, None, None).expect("status");
+        assert_eq!(status["stashCount"], 0);
+
+        fs::write(root.join("a.txt"), "two\n").expect("modify file");
+        let sig = git2::Signature::now("Test", "test@example.com").expect("signature");
+        repo.stash_save(&sig, "wip", None).expect("stash save");
+
+        let status = git_status_for_path(&root, None, None).expect("status");
+        assert_eq!(status["stashCount"], 1);
+        assert!(status["files"].as_array().expect("files").is_empty());
+    }
+
+    #[test]
+    fn stash_apply_keeps_stash_until_dropped() {
+        let (root, mut repo) = create_temp_repo();
+        commit_file(&root, &repo, "a.txt", "one\n");
+        fs::write(root.join("a.txt"), "two\n").expect("modify file");
+        let sig = git2::Signature::now("Test", "test@example.com").expect("signature");
+        repo.stash_save(&sig, "wip", None).expect("stash save");
 
-    Ok(comments)
-}
+        let result = block_on(stash_apply_for_path(&root, 0)).expect("stash apply");
+        assert!(!result.has_conflicts);
+        assert_eq!(fs::read_to_string(root.join("a.txt")).expect("read"), "two\n");
+        assert_eq!(stash_count(&mut repo), 1);
 
-#[tauri::command]
-pub(crate) async fn list_git_branches(
-    workspace_id: String,
-    state: State<'_, AppState>,
-) -> Result<serde_json::Value, String> {
-    let workspaces = state.workspaces.lock().await;
-    let entry = workspaces
-        .get(&workspace_id)
-        .ok_or("workspace not found")?
-        .clone();
-    let repo_root = resolve_git_root(&entry)?;
-    let repo = Repository::open(&repo_root).map_err(|e| e.to_string())?;
-    let mut branches = Vec::new();
-    let refs = repo
-        .branches(Some(BranchType::Local))
-        .map_err(|e| e.to_string())?;
-    for branch_result in refs {
-        let (branch, _) = branch_result.map_err(|e| e.to_string())?;
-        let name = branch.name().ok().flatten().unwrap_or("").to_string();
-        if name.is_empty() {
-            continue;
-        }
-        let last_commit = branch
-            .get()
-            .target()
-            .and_then(|oid| repo.find_commit(oid).ok())
-            .map(|commit| commit.time().seconds())
-            .unwrap_or(0);
-        branches.push(BranchInfo { name, last_commit });
+        stash_drop_for_path(&root, 0).expect("stash drop");
+        assert_eq!(stash_count(&mut repo), 0);
+        assert!(stash_drop_for_path(&root, 0).is_err());
     }
-    branches.sort_by(|a, b| b.last_commit.cmp(&a.last_commit));
-    Ok(json!({ "branches": branches }))
-}
 
-#[tauri::command]
-pub(crate) async fn checkout_git_branch(
-    workspace_id: String,
-    name: String,
-    state: State<'_, AppState>,
-) -> Result<(), String> {
-    let workspaces = state.workspaces.lock().await;
-    let entry = workspaces
-        .get(&workspace_id)
-        .ok_or("workspace not found")?
-        .clone();
-    let repo_root = resolve_git_root(&entry)?;
-    let repo = Repository::open(&repo_root).map_err(|e| e.to_string())?;
-    checkout_branch(&repo, &name).map_err(|e| e.to_string())
-}
+    #[test]
+    fn commit_all_stages_untracked_files_and_returns_the_new_sha() {
+        let (root, repo) = create_temp_repo();
+        let mut config = repo.config().expect("config");
+        config.set_str("user.name", "Test").expect("user.name");
+        config.set_str("user.email", "test@example.com").expect("user.email");
+        commit_file(&root, &repo, "a.txt", "one\n");
+        commit_file(&root, &repo, ".gitignore", "*.log\n");
+        fs::write(root.join("debug.log"), "ignored\n").expect("ignored file");
 
-#[tauri::command]
-pub(crate) async fn create_git_branch(
-    workspace_id: String,
-    name: String,
-    state: State<'_, AppState>,
-) -> Result<(), String> {
-    let workspaces = state.workspaces.lock().await;
-    let entry = workspaces
-        .get(&workspace_id)
-        .ok_or("workspace not found")?
-        .clone();
-    let repo_root = resolve_git_root(&entry)?;
-    let repo = Repository::open(&repo_root).map_err(|e| e.to_string())?;
-    let head = repo.head().map_err(|e| e.to_string())?;
-    let target = head.peel_to_commit().map_err(|e| e.to_string())?;
-    repo.branch(&name, &target, false)
-        .map_err(|e| e.to_string())?;
-    checkout_branch(&repo, &name).map_err(|e| e.to_string())
-}
+        let err = block_on(commit_all_for_path(&root, "noop")).expect_err("clean tree");
+        assert!(err.contains("Nothing to commit"), "{err}");
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use std::fs;
+        fs::write(root.join("a.txt"), "two\n").expect("modify file");
+        fs::write(root.join("new.txt"), "new\n").expect("untracked file");
+        let err = block_on(commit_all_for_path(&root, "  ")).expect_err("empty message");
+        assert!(err.contains("message"), "{err}");
 
-    fn create_temp_repo() -> (PathBuf, Repository) {
-        let root = std::env::temp_dir().join(format!(
-            "codex-monitor-test-{}",
-            uuid::Uuid::new_v4()
-        ));
-        fs::create_dir_all(&root).expect("create temp repo root");
-        let repo = Repository::init(&root).expect("init repo");
-        (root, repo)
+        let sha = block_on(commit_all_for_path(&root, "Update a and add new")).expect("commit all");
+        let head = repo.head().unwrap().peel_to_commit().unwrap();
+        assert_eq!(sha, head.id().to_string());
+        assert_eq!(head.summary(), Some("Update a and add new"));
+        assert!(head.tree().unwrap().get_path(Path::new("new.txt")).is_ok());
+        assert!(!has_local_changes(&root).expect("status"));
+    }
+
+    #[test]
+    fn parse_stash_list_reads_branch_and_message() {
+        let output = "stash@{0}\x001700000000\x00On main: before rebase\n\
+                      stash@{1}\x001600000000\x00WIP on (no branch): abc1234 Fix: thing\n";
+        let entries = parse_stash_list(output);
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].branch.as_deref(), Some("main"));
+        assert_eq!(entries[0].message, "before rebase");
+        assert_eq!(entries[0].timestamp, 1_700_000_000);
+        assert_eq!(entries[1].index, 1);
+        assert_eq!(entries[1].branch, None);
+        assert_eq!(entries[1].message, "abc1234 Fix: thing");
+    }
+
+    #[test]
+    fn stash_save_list_and_pop_round_trip() {
+        let (root, repo) = create_temp_repo();
+        let mut config = repo.config().expect("config");
+        config.set_str("user.name", "Test").expect("user.name");
+        config.set_str("user.email", "test@example.com").expect("user.email");
+        commit_file(&root, &repo, "a.txt", "one\n");
+        let branch = repo.head().unwrap().shorthand().unwrap().to_string();
+
+        let err = block_on(stash_save_for_path(&root, None)).expect_err("nothing to stash");
+        assert!(err.contains("No local changes"));
+
+        fs::write(root.join("a.txt"), "two\n").expect("modify file");
+        block_on(stash_save_for_path(&root, Some("wip a"))).expect("stash save");
+        let entries = block_on(stash_list_for_path(&root)).expect("list");
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].message, "wip a");
+        assert_eq!(entries[0].branch.as_deref(), Some(branch.as_str()));
+
+        fs::write(root.join("a.txt"), "three\n").expect("conflicting edit");
+        commit_file(&root, &repo, "a.txt", "three\n");
+        let conflicted = block_on(stash_pop_for_path(&root, 0)).expect("pop with conflicts");
+        assert!(conflicted.has_conflicts);
+        assert_eq!(conflicted.conflicts, vec!["a.txt".to_string()]);
+        let kept = block_on(stash_list_for_path(&root)).expect("list");
+        assert_eq!(kept.len(), 1);
     }
 
     #[test]
@@ -1357,4 +3675,596 @@ mod tests {
         let paths = action_paths_for_file(&root, "b.txt");
         assert_eq!(paths, vec!["a.txt".to_string(), "b.txt".to_string()]);
     }
+
+    #[test]
+    fn diff_digest_lists_files_with_counts() {
+        let (root, repo) = create_temp_repo();
+        commit_file(&root, &repo, "a.txt", "one\n");
+        fs::write(root.join("a.txt"), "one\ntwo\n").expect("write file");
+        fs::write(root.join("new.txt"), "fresh\n").expect("write new file");
+
+        let digest = diff_digest_for_path(&root, None).expect("diff digest");
+        let text = digest["text"].as_str().expect("digest text");
+        assert!(text.contains("a.txt +1 -0"));
+        assert!(text.contains("new.txt +1 -0"));
+        assert!(text.contains("+two"));
+        assert_eq!(digest["truncated"], false);
+    }
+
+    #[test]
+    fn git_push_reports_new_remote_branches_and_missing_upstream() {
+        let (root, repo) = create_temp_repo();
+        commit_file(&root, &repo, "a.txt", "one\n");
+        let remote_root = std::env::temp_dir().join(format!(
+            "codex-monitor-remote-{}",
+            uuid::Uuid::new_v4()
+        ));
+        Repository::init_bare(&remote_root).expect("init bare remote");
+        repo.remote("origin", &remote_root.to_string_lossy())
+            .expect("add remote");
+
+        let err = block_on(git_push_for_path(&root, None, None, false))
+            .expect_err("push without upstream");
+        assert!(err.contains("upstream"), "{err}");
+        assert!(!git_log_for_path(&root, None).expect("log").has_upstream);
+
+        let first = block_on(git_push_for_path(&root, None, None, true)).expect("push with -u");
+        assert!(first.created_remote_branch);
+        assert_eq!(first.remote.as_deref(), Some("origin"));
+        assert!(upstream_remote_and_branch(&root).expect("upstream").is_some());
+
+        commit_file(&root, &repo, "b.txt", "two\n");
+        let log = git_log_for_path(&root, None).expect("log with upstream");
+        assert!(log.has_upstream);
+        assert_eq!((log.ahead, log.behind), (1, 0));
+        let second = block_on(git_push_for_path(&root, None, None, false))
+            .expect("push to upstream");
+        assert!(!second.created_remote_branch);
+
+        let err = block_on(git_push_for_path(
+            &root,
+            Some("--receive-pack=touch pwned".to_string()),
+            None,
+            false,
+        ))
+        .expect_err("option as remote");
+        assert!(err.starts_with("Invalid remote"), "{err}");
+        let err = block_on(git_push_for_path(&root, None, Some("--force".to_string()), false))
+            .expect_err("option as branch");
+        assert!(err.starts_with("Invalid branch name"), "{err}");
+        let err = block_on(fetch_for_path(&root, Some(" --upload-pack=touch pwned".to_string())))
+            .expect_err("option as fetch remote");
+        assert!(err.starts_with("Invalid remote"), "{err}");
+
+        let _ = fs::remove_dir_all(&remote_root);
+    }
+
+    #[test]
+    fn recent_branches_follow_checkout_history() {
+        let (root, repo) = create_temp_repo();
+        let head = commit_file(&root, &repo, "a.txt", "one\n");
+        let initial = repo.head().expect("head").shorthand().unwrap().to_string();
+        let commit = repo.find_commit(head).expect("commit");
+        for name in ["stale", "alpha", "beta"] {
+            repo.branch(name, &commit, false).expect("branch");
+        }
+        for name in ["alpha", "beta", "alpha", "beta"] {
+            checkout_branch(&repo, name).expect("checkout");
+        }
+        checkout_branch(&repo, &initial).expect("checkout initial");
+        checkout_branch(&repo, "beta").expect("checkout beta");
+
+        let recent = recent_branches_for_path(&root, 10).expect("recent");
+        let names: Vec<&str> = recent.iter().map(|branch| branch.name.as_str()).collect();
+        assert_eq!(names, vec!["beta", initial.as_str(), "alpha"]);
+
+        repo.find_branch("alpha", BranchType::Local)
+            .and_then(|mut branch| branch.delete())
+            .expect("delete alpha");
+        let recent = recent_branches_for_path(&root, 1).expect("recent limited");
+        assert_eq!(recent.len(), 1);
+        assert_eq!(recent[0].name, "beta");
+        let recent = recent_branches_for_path(&root, 10).expect("recent");
+        assert!(recent.iter().all(|branch| branch.name != "alpha"));
+    }
+
+    #[test]
+    fn restore_branch_recreates_deleted_branch_at_sha() {
+        let (root, repo) = create_temp_repo();
+        let first = commit_file(&root, &repo, "a.txt", "one\n");
+        commit_file(&root, &repo, "b.txt", "two\n");
+        let sha = first.to_string();
+
+        let restored = restore_branch_for_path(&root, "feature", &sha).expect("restore branch");
+        assert_eq!(restored.name, "feature");
+        let branch = repo
+            .find_branch("feature", BranchType::Local)
+            .expect("find branch");
+        assert_eq!(branch.get().target(), Some(first));
+
+        let err = restore_branch_for_path(&root, "feature", &sha).expect_err("duplicate");
+        assert!(err.contains("already exists"), "{err}");
+        let err = restore_branch_for_path(&root, "other", "deadbeef").expect_err("bad sha");
+        assert!(err.contains("Unknown commit"), "{err}");
+    }
+
+    #[test]
+    fn pull_reports_merge_conflicts_distinctly() {
+        let (root, repo) = create_temp_repo();
+        commit_file(&root, &repo, "a.txt", "base\n");
+        let clone_root = std::env::temp_dir().join(format!(
+            "codex-monitor-clone-{}",
+            uuid::Uuid::new_v4()
+        ));
+        let clone = Repository::clone(&root.to_string_lossy(), &clone_root).expect("clone");
+        let mut config = clone.config().expect("clone config");
+        config.set_str("user.name", "Test").expect("user.name");
+        config.set_str("user.email", "test@example.com").expect("user.email");
+        config.set_bool("pull.rebase", false).expect("pull.rebase");
+
+        commit_file(&root, &repo, "a.txt", "upstream\n");
+        commit_file(&clone_root, &clone, "a.txt", "local\n");
+
+        let err = block_on(pull_for_path(&clone_root, true))
+            .expect_err("ff-only pull of diverged branch");
+        assert!(!err.starts_with("merge_conflict:"), "{err}");
+
+        let err = block_on(pull_for_path(&clone_root, false)).expect_err("conflicting pull");
+        assert_eq!(err, "merge_conflict: Pull stopped with conflicts in a.txt");
+
+        let _ = fs::remove_dir_all(&clone_root);
+    }
+
+    #[test]
+    fn resolve_ref_reports_type_and_short_name() {
+        let (root, repo) = create_temp_repo();
+        let head = commit_file(&root, &repo, "a.txt", "one\n");
+        let branch = repo.head().expect("head").shorthand().unwrap().to_string();
+
+        let resolved = resolve_ref_for_path(&root, &branch).expect("branch");
+        assert_eq!(resolved.sha, head.to_string());
+        assert_eq!((resolved.kind.as_str(), resolved.short_name.as_str()), ("commit", &*branch));
+
+        let resolved = resolve_ref_for_path(&root, &head.to_string()).expect("sha");
+        assert!(head.to_string().starts_with(&resolved.short_name));
+        assert!(resolved.short_name.len() < 40);
+
+        let resolved = resolve_ref_for_path(&root, "HEAD:a.txt").expect("blob");
+        assert_eq!(resolved.kind, "blob");
+
+        let err = resolve_ref_for_path(&root, "nope").expect_err("unknown");
+        assert_eq!(err, "Unknown ref: nope");
+    }
+
+    #[test]
+    fn show_describes_commits_tags_blobs_and_trees() {
+        let (root, repo) = create_temp_repo();
+        let first = commit_file(&root, &repo, "a.txt", "one\n");
+        commit_file(&root, &repo, "b.txt", "two\n");
+        let sig = git2::Signature::now("Tagger", "tagger@example.com").expect("signature");
+        let target = repo.find_object(first, None).expect("target");
+        repo.tag("v1", &target, &sig, "Release one\n", false).expect("tag");
+
+        let GitShowResult::Commit(commit) = show_for_path(&root, "HEAD").expect("commit") else {
+            panic!("expected commit");
+        };
+        assert_eq!(commit.files.len(), 1);
+        assert_eq!(commit.files[0].path, "b.txt");
+
+        let GitShowResult::Tag(tag) = show_for_path(&root, "v1").expect("tag") else {
+            panic!("expected tag");
+        };
+        assert_eq!(tag.name, "v1");
+        assert_eq!(tag.message, "Release one\n");
+        assert_eq!(tag.tagger.as_deref(), Some("Tagger"));
+        assert_eq!((tag.target_type.as_str(), tag.target_sha), ("commit", first.to_string()));
+
+        let GitShowResult::Blob(blob) = show_for_path(&root, "HEAD:a.txt").expect("blob") else {
+            panic!("expected blob");
+        };
+        assert_eq!((blob.size, blob.is_binary), (4, false));
+
+        let GitShowResult::Tree(tree) = show_for_path(&root, "HEAD^{tree}").expect("tree") else {
+            panic!("expected tree");
+        };
+        assert_eq!(tree.entries, vec!["a.txt".to_string(), "b.txt".to_string()]);
+
+        let value = serde_json::to_value(show_for_path(&root, "v1").expect("tag")).expect("json");
+        assert_eq!(value["type"], "tag");
+        assert_eq!(value["targetType"], "commit");
+        let err = show_for_path(&root, "missing").expect_err("unknown ref");
+        assert_eq!(err, "Unknown ref: missing");
+    }
+
+    #[test]
+    fn rebase_reports_conflict_step_and_continues() {
+        let (root, repo) = create_temp_repo();
+        let mut config = repo.config().expect("config");
+        config.set_str("user.name", "Test").expect("user.name");
+        config.set_str("user.email", "test@example.com").expect("user.email");
+        let base = commit_file(&root, &repo, "a.txt", "base\n");
+        let base = repo.find_object(base, None).expect("base commit");
+        let upstream = commit_file(&root, &repo, "a.txt", "upstream\n");
+        let upstream_commit = repo.find_commit(upstream).expect("upstream commit");
+        repo.branch("upstream", &upstream_commit, false).expect("branch");
+        repo.reset(&base, git2::ResetType::Hard, None).expect("reset");
+        let local = commit_file(&root, &repo, "a.txt", "local\n");
+
+        let result = block_on(rebase_for_path(&root, "start", Some("upstream")))
+            .expect("conflicting rebase");
+        assert!(result.has_conflicts && result.in_progress);
+        assert_eq!(result.conflicts, vec!["a.txt".to_string()]);
+        assert_eq!(result.step.as_deref(), Some("1/1"));
+
+        let aborted = block_on(rebase_for_path(&root, "abort", None)).expect("abort rebase");
+        assert!(!aborted.in_progress && aborted.step.is_none());
+        let head = Repository::open(&root).expect("reopen").head().expect("head").target();
+        assert_eq!(head, Some(local));
+
+        block_on(rebase_for_path(&root, "start", Some("upstream"))).expect("conflicting rebase");
+        fs::write(root.join("a.txt"), "resolved\n").expect("resolve");
+        let fresh = Repository::open(&root).expect("reopen");
+        let mut index = fresh.index().expect("index");
+        index.add_path(Path::new("a.txt")).expect("add");
+        index.write().expect("write index");
+        let done = block_on(rebase_for_path(&root, "continue", None)).expect("continue rebase");
+        assert!(!done.has_conflicts && !done.in_progress);
+        let head = fresh.head().expect("head").peel_to_commit().expect("head commit");
+        assert_eq!(head.parent_id(0).expect("parent"), upstream);
+
+        let err = block_on(rebase_for_path(&root, "skip", None)).expect_err("unknown action");
+        assert_eq!(err, "Unknown rebase action: skip");
+    }
+
+    #[test]
+    fn merge_reports_conflicts_and_aborts() {
+        let (root, repo) = create_temp_repo();
+        let mut config = repo.config().expect("config");
+        config.set_str("user.name", "Test").expect("user.name");
+        config.set_str("user.email", "test@example.com").expect("user.email");
+        let base = commit_file(&root, &repo, "a.txt", "base\n");
+        let base = repo.find_object(base, None).expect("base commit");
+        let branches = [("feature", "a.txt", "feature\n"), ("extra", "b.txt", "extra\n")];
+        for (branch, name, contents) in branches {
+            let oid = commit_file(&root, &repo, name, contents);
+            let commit = repo.find_commit(oid).expect("branch commit");
+            repo.branch(branch, &commit, false).expect("branch");
+            repo.reset(&base, git2::ResetType::Hard, None).expect("reset");
+        }
+        commit_file(&root, &repo, "a.txt", "main\n");
+
+        let result = block_on(merge_for_path(&root, Some("feature"), false, false))
+            .expect("conflicting merge");
+        assert!(result.has_conflicts);
+        assert_eq!(result.conflicts, vec!["a.txt".to_string()]);
+
+        let aborted = block_on(merge_for_path(&root, None, false, true)).expect("abort merge");
+        assert!(!aborted.has_conflicts);
+        assert!(!is_dirty_for_path(&root).expect("dirty"));
+
+        let merged = block_on(merge_for_path(&root, Some("extra"), true, false))
+            .expect("clean merge");
+        assert!(!merged.has_conflicts);
+        let head = repo.head().expect("head").peel_to_commit().expect("head commit");
+        assert_eq!(head.parent_count(), 2);
+
+        let err = block_on(merge_for_path(&root, Some(" "), false, false))
+            .expect_err("missing branch");
+        assert_eq!(err, "Branch name is required");
+    }
+
+    #[test]
+    fn structured_file_diff_reports_hunks_and_missing_newline() {
+        let (root, repo) = create_temp_repo();
+        let original: String = (1..=20).map(|n| format!("line {n}\n")).collect();
+        commit_file(&root, &repo, "a.txt", &original);
+        commit_file(&root, &repo, "b.txt", "other\n");
+        let edited = original
+            .replace("line 2\n", "line two\n")
+            .replace("line 20\n", "line twenty");
+        fs::write(root.join("a.txt"), edited).expect("edit file");
+        fs::write(root.join("b.txt"), "changed\n").expect("edit other file");
+
+        let all = FileDiffScope::HeadToWorkdir;
+        let diff =
+            structured_file_diff_for_path(&root, "a.txt", all, false).expect("structured diff");
+        assert_eq!(diff.path, "a.txt");
+        assert_eq!(diff.hunks.len(), 2);
+        let first = &diff.hunks[0];
+        assert_eq!((first.old_start, first.new_start), (1, 1));
+        assert!(first.header.starts_with("@@ -1,"));
+        let kinds: Vec<&str> = first.lines.iter().map(|line| line.kind.as_str()).collect();
+        assert_eq!(kinds, vec!["ctx", "del", "add", "ctx", "ctx", "ctx"]);
+        assert_eq!(first.lines[1].content, "line 2");
+        assert_eq!(first.lines[2].new_line, Some(2));
+
+        let last = diff.hunks[1].lines.last().expect("last line");
+        assert_eq!(last.kind, "add");
+        assert_eq!(last.content, "line twenty");
+        assert!(last.no_newline);
+
+        assert!(first.lines[1].highlights.is_none());
+        let clean =
+            structured_file_diff_for_path(&root, "missing.txt", all, false).expect("no changes");
+        assert!(clean.hunks.is_empty());
+
+        let words = structured_file_diff_for_path(&root, "a.txt", all, true).expect("word diff");
+        let lines = &words.hunks[0].lines;
+        let spans = |line: &GitDiffLine| {
+            line.highlights
+                .as_ref()
+                .expect("highlights")
+                .iter()
+                .map(|span| (span.start, span.end))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(spans(&lines[1]), vec![(5, 6)]);
+        assert_eq!(spans(&lines[2]), vec![(5, 8)]);
+        assert!(lines[0].highlights.is_none());
+    }
+
+    #[test]
+    fn stage_and_unstage_file_return_refreshed_status() {
+        let (root, repo) = create_temp_repo();
+        commit_file(&root, &repo, "a.txt", "one\n");
+        commit_file(&root, &repo, "gone.txt", "bye\n");
+        fs::write(root.join("a.txt"), "two\n").expect("modify file");
+        fs::write(root.join("new.txt"), "new\n").expect("untracked file");
+        fs::remove_file(root.join("gone.txt")).expect("delete file");
+        fs::create_dir(root.join("docs")).expect("create dir");
+        fs::write(root.join("docs/notes.txt"), "notes\n").expect("nested file");
+
+        let staged = block_on(stage_file_for_path(&root, "a.txt"))
+            .expect("stage modified")
+            .expect("status entry");
+        assert_eq!(staged.index_status.as_deref(), Some("M"));
+        assert_eq!(staged.worktree_status, None);
+
+        let untracked = block_on(stage_file_for_path(&root, "new.txt"))
+            .expect("stage untracked")
+            .expect("status entry");
+        assert_eq!(untracked.index_status.as_deref(), Some("A"));
+
+        let deleted = block_on(stage_file_for_path(&root, "gone.txt"))
+            .expect("stage deletion")
+            .expect("status entry");
+        assert_eq!(deleted.index_status.as_deref(), Some("D"));
+
+        let unstaged = block_on(unstage_file_for_path(&root, "a.txt"))
+            .expect("unstage")
+            .expect("status entry");
+        assert_eq!(unstaged.index_status, None);
+        assert_eq!(unstaged.worktree_status.as_deref(), Some("M"));
+
+        // A directory only matches its files by pathspec; it has no entry.
+        assert!(file_status_for_path(&root, "docs").expect("status").is_none());
+
+        let err = block_on(stage_file_for_path(&root, "missing.txt")).expect_err("unknown path");
+        assert!(err.contains("No changes"), "{err}");
+    }
+
+    #[test]
+    fn file_diff_for_path_separates_staged_and_unstaged_changes() {
+        let (root, repo) = create_temp_repo();
+        commit_file(&root, &repo, "a.txt", "one\n");
+        commit_file(&root, &repo, "b.txt", "bee\n");
+        fs::write(root.join("a.txt"), "two\n").expect("modify file");
+        let mut index = repo.index().expect("repo index");
+        index.add_path(Path::new("a.txt")).expect("stage file");
+        index.write().expect("write index");
+        fs::write(root.join("a.txt"), "three\n").expect("modify again");
+        fs::write(root.join("b.txt"), "changed\n").expect("modify other file");
+
+        let staged = file_diff_for_path(&root, "a.txt", true).expect("staged diff");
+        assert!(staged.diff.contains("-one"));
+        assert!(staged.diff.contains("+two"));
+        assert!(!staged.diff.contains("b.txt"));
+
+        let unstaged = file_diff_for_path(&root, "a.txt", false).expect("unstaged diff");
+        assert!(unstaged.diff.contains("-two"));
+        assert!(unstaged.diff.contains("+three"));
+
+        let clean = file_diff_for_path(&root, "b.txt", true).expect("nothing staged");
+        assert!(clean.diff.is_empty());
+    }
+
+    #[test]
+    fn stage_hunks_stages_only_selected_hunks() {
+        let (root, repo) = create_temp_repo();
+        let original: String = (1..=20).map(|n| format!("line {n}\n")).collect();
+        commit_file(&root, &repo, "a.txt", &original);
+        let edited = original
+            .replace("line 2\n", "line two\n")
+            .replace("line 18\n", "line eighteen\n");
+        fs::write(root.join("a.txt"), &edited).expect("edit file");
+
+        let unstaged = structured_file_diff_for_path(
+            &root,
+            "a.txt",
+            FileDiffScope::IndexToWorkdir,
+            false,
+        )
+        .expect("unstaged hunks");
+        assert_eq!(unstaged.hunks.len(), 2);
+
+        let status = stage_hunks_for_path(&root, "a.txt", &[1])
+            .expect("stage hunk")
+            .expect("file still changed");
+        assert_eq!(status.index_status.as_deref(), Some("M"));
+        assert_eq!(status.worktree_status.as_deref(), Some("M"));
+
+        let staged = file_diff_for_path(&root, "a.txt", true).expect("staged diff");
+        assert!(staged.diff.contains("+line eighteen"));
+        assert!(!staged.diff.contains("+line two"));
+        let remaining = file_diff_for_path(&root, "a.txt", false).expect("unstaged diff");
+        assert!(remaining.diff.contains("+line two"));
+        assert!(!remaining.diff.contains("+line eighteen"));
+
+        let err = stage_hunks_for_path(&root, "a.txt", &[1]).expect_err("only one hunk left");
+        assert!(err.contains("Unknown hunk 1"));
+    }
+
+    #[test]
+    fn git_blame_marks_uncommitted_lines_as_local() {
+        let (root, repo) = create_temp_repo();
+        commit_file(&root, &repo, "a.txt", "one\ntwo\nthree\n");
+        let head = repo.head().unwrap().target().unwrap().to_string();
+        fs::write(root.join("a.txt"), "one\nTWO\nthree\nfour\n").expect("edit file");
+
+        let hunks = git_blame_for_path(&root, "a.txt").expect("blame");
+        let lines: Vec<(usize, usize, bool)> = hunks
+            .iter()
+            .map(|hunk| (hunk.start_line, hunk.line_count, hunk.local))
+            .collect();
+        assert_eq!(lines, vec![(1, 1, false), (2, 1, true), (3, 1, false), (4, 1, true)]);
+        assert_eq!(hunks[0].sha.as_deref(), Some(head.as_str()));
+        assert!(hunks[0].author.is_some());
+        assert!(hunks[1].sha.is_none());
+
+        fs::write(root.join("new.txt"), "a\nb\n").expect("write untracked");
+        let untracked = git_blame_for_path(&root, "new.txt").expect("blame untracked");
+        assert_eq!(untracked.len(), 1);
+        assert_eq!((untracked[0].start_line, untracked[0].line_count), (1, 2));
+        assert!(untracked[0].local);
+
+        assert!(git_blame_for_path(&root, "missing.txt").is_err());
+    }
+
+    #[test]
+    fn git_identity_prefers_repo_config() {
+        let (root, _repo) = create_temp_repo();
+        let identity =
+            set_git_identity_for_path(&root, " Ada ", "ada@example.com").expect("set identity");
+        assert_eq!(identity.name.as_deref(), Some("Ada"));
+        assert_eq!(identity.email.as_deref(), Some("ada@example.com"));
+        assert!(identity.configured);
+        assert!(set_git_identity_for_path(&root, "", "ada@example.com").is_err());
+
+        let empty_path = root.join("empty.gitconfig");
+        fs::write(&empty_path, "[user]\n\tname = Ada\n").expect("write config");
+        let config = git2::Config::open(&empty_path).expect("open config");
+        let partial = identity_from_config(&config);
+        assert_eq!(partial.name.as_deref(), Some("Ada"));
+        assert!(partial.email.is_none());
+        assert!(!partial.configured);
+    }
+
+    #[test]
+    fn commit_details_diff_against_the_chosen_parent() {
+        let (root, repo) = create_temp_repo();
+        let root_commit = commit_file(&root, &repo, "a.txt", "one\n");
+        let details =
+            commit_details_for_path(&root, &root_commit.to_string(), 0).expect("root details");
+        assert!(details.parents.is_empty());
+        assert_eq!(details.files.len(), 1);
+        assert_eq!(details.files[0].status, "A");
+
+        let base = repo.find_commit(root_commit).expect("root commit");
+        commit_file(&root, &repo, "b.txt", "side\n");
+        let main = commit_file(&root, &repo, "a.txt", "two\n");
+        let signature = git2::Signature::now("Test", "test@example.com").expect("signature");
+        let tree = repo.find_commit(main).unwrap().tree().unwrap();
+        let merge = repo
+            .commit(
+                None,
+                &signature,
+                &signature,
+                "Merge side\n\nWith a body.\n",
+                &tree,
+                &[&repo.find_commit(main).unwrap(), &base],
+            )
+            .expect("merge commit");
+
+        let first = commit_details_for_path(&root, &merge.to_string(), 0).expect("first parent");
+        assert_eq!(first.summary, "Merge side");
+        assert!(first.message.contains("With a body."));
+        assert_eq!(first.parents, vec![main.to_string(), root_commit.to_string()]);
+        assert!(first.files.is_empty());
+
+        let second = commit_details_for_path(&root, &merge.to_string(), 1).expect("second parent");
+        let paths: Vec<&str> = second.files.iter().map(|file| file.path.as_str()).collect();
+        assert_eq!(paths, vec!["a.txt", "b.txt"]);
+
+        let err = commit_details_for_path(&root, &merge.to_string(), 2).expect_err("no parent 2");
+        assert!(err.contains("out of range"));
+    }
+
+    #[test]
+    fn discard_file_changes_restores_tracked_and_deletes_untracked_files() {
+        let (root, repo) = create_temp_repo();
+        commit_file(&root, &repo, "a.txt", "one\n");
+        fs::write(root.join("a.txt"), "two\n").expect("modify file");
+        let mut index = repo.index().expect("repo index");
+        index.add_path(Path::new("a.txt")).expect("stage file");
+        index.write().expect("write index");
+        fs::write(root.join("a.txt"), "three\n").expect("modify again");
+
+        discard_file_changes_for_path(&root, "a.txt", false).expect("discard unstaged");
+        assert_eq!(fs::read_to_string(root.join("a.txt")).expect("read"), "two\n");
+
+        let status = discard_file_changes_for_path(&root, "a.txt", true).expect("discard staged");
+        assert_eq!(fs::read_to_string(root.join("a.txt")).expect("read"), "one\n");
+        assert!(status["files"].as_array().expect("files").is_empty());
+
+        fs::write(root.join("new.txt"), "scratch\n").expect("write untracked");
+        discard_file_changes_for_path(&root, "new.txt", false).expect("discard untracked");
+        assert!(!root.join("new.txt").exists());
+
+        fs::create_dir_all(root.join("scratch/deep")).expect("untracked dir");
+        fs::write(root.join("scratch/deep/notes.txt"), "notes\n").expect("write nested");
+        discard_file_changes_for_path(&root, "scratch/deep/notes.txt", false)
+            .expect("discard nested untracked");
+        assert!(!root.join("scratch/deep/notes.txt").exists());
+
+        fs::write(root.join(".gitignore"), ".env\n").expect("write gitignore");
+        fs::write(root.join(".env"), "TOKEN=secret\n").expect("write ignored");
+        let err = discard_file_changes_for_path(&root, ".env", false).expect_err("ignored file");
+        assert!(err.contains("No changes"), "{err}");
+        assert!(root.join(".env").exists());
+
+        fs::remove_file(root.join("a.txt")).expect("delete tracked file");
+        discard_file_changes_for_path(&root, "a.txt", false).expect("restore deleted");
+        assert!(root.join("a.txt").exists());
+
+        for outside in ["../escape.txt", "/etc/passwd", "sub/../../escape.txt"] {
+            let err = discard_file_changes_for_path(&root, outside, false).expect_err(outside);
+            assert!(err.contains("inside the workspace"), "{err}");
+        }
+    }
+
+    #[test]
+    fn git_diffs_report_line_stats_and_renames() {
+        let (root, repo) = create_temp_repo();
+        let body: String = (1..=10).map(|n| format!("line {n}\n")).collect();
+        commit_file(&root, &repo, "old.txt", &body);
+        commit_file(&root, &repo, "a.txt", "one\ntwo\n");
+        fs::rename(root.join("old.txt"), root.join("new.txt")).expect("rename file");
+        fs::write(root.join("a.txt"), "one\nTWO\nthree\n").expect("edit file");
+
+        let diffs = git_diffs_for_path(&root).expect("diffs");
+        assert_eq!(diffs.len(), 2, "{diffs:?}");
+        let edited = diffs.iter().find(|diff| diff.path == "a.txt").expect("a.txt");
+        assert_eq!((edited.additions, edited.deletions), (2, 1));
+        assert!(edited.renamed_from.is_none());
+        let renamed = diffs.iter().find(|diff| diff.path == "new.txt").expect("new.txt");
+        assert_eq!(renamed.renamed_from.as_deref(), Some("old.txt"));
+        assert_eq!((renamed.additions, renamed.deletions), (0, 0));
+        assert!(!renamed.is_binary);
+    }
+
+    #[test]
+    fn git_diffs_list_binary_files_without_patch_text() {
+        let (root, repo) = create_temp_repo();
+        commit_file(&root, &repo, "a.txt", "one\n");
+        fs::write(root.join("image.bin"), [0u8, 159, 146, 150, 0, 1, 2, 3]).expect("write binary");
+
+        let diffs = git_diffs_for_path(&root).expect("diffs");
+        let binary = diffs.iter().find(|diff| diff.path == "image.bin").expect("binary");
+        assert!(binary.is_binary);
+        assert!(binary.diff.is_empty());
+
+        let single = file_diff_for_path(&root, "image.bin", false).expect("single diff");
+        assert!(single.is_binary);
+        assert!(single.diff.is_empty());
+    }
 }
@@ -1,10 +1,10 @@
 use std::collections::HashSet;
-use std::path::{Path, PathBuf};
+use std::path::Path;
 
 use git2::{DiffOptions, Repository, Tree};
 use ignore::WalkBuilder;
 
-use crate::types::{GitLogEntry, WorkspaceEntry};
+use crate::types::GitLogEntry;
 use crate::utils::normalize_git_path;
 
 pub(crate) fn commit_to_entry(commit: git2::Commit) -> GitLogEntry {
@@ -93,29 +93,6 @@ pub(crate) fn parse_github_repo(remote_url: &str) -> Option<String> {
     }
 }
 
-pub(crate) fn resolve_git_root(entry: &WorkspaceEntry) -> Result<PathBuf, String> {
-    let base = PathBuf::from(&entry.path);
-    let root = entry
-        .settings
-        .git_root
-        .as_ref()
-        .map(|value| value.trim())
-        .filter(|value| !value.is_empty());
-    let Some(root) = root else {
-        return Ok(base);
-    };
-    let root_path = if Path::new(root).is_absolute() {
-        PathBuf::from(root)
-    } else {
-        base.join(root)
-    };
-    if root_path.is_dir() {
-        Ok(root_path)
-    } else {
-        Err(format!("Git root not found: {root}"))
-    }
-}
-
 fn should_skip_dir(name: &str) -> bool {
     matches!(
         name,
@@ -1,5 +1,6 @@
 use tauri::Manager;
 
+#[allow(dead_code)]
 mod backend;
 mod codex;
 mod codex_home;
@@ -72,11 +73,14 @@ pub fn run() {
             workspaces::add_worktree,
             workspaces::remove_workspace,
             workspaces::remove_worktree,
+            workspaces::prune_worktrees,
             workspaces::rename_worktree,
             workspaces::rename_worktree_upstream,
             workspaces::apply_worktree_changes,
             workspaces::update_workspace_settings,
             workspaces::update_workspace_codex_bin,
+            workspaces::rename_workspace,
+            workspaces::relocate_workspace,
             codex::start_thread,
             codex::send_user_message,
             codex::turn_interrupt,
@@ -86,24 +90,55 @@ pub fn run() {
             codex::get_commit_message_prompt,
             codex::generate_commit_message,
             codex::resume_thread,
+            codex::resume_latest_thread,
             codex::list_threads,
             codex::archive_thread,
             codex::collaboration_mode_list,
             workspaces::connect_workspace,
+            workspaces::disconnect_workspace,
             git::get_git_status,
+            git::get_git_status_batch,
+            git::is_workspace_dirty,
             git::list_git_roots,
             git::get_git_diffs,
+            git::get_git_file_diff,
+            git::get_git_file_diff_structured,
+            git::get_diff_digest,
             git::get_git_log,
             git::get_git_commit_diff,
+            git::get_commit_details,
+            git::git_show,
+            git::resolve_ref,
+            git::get_merge_base,
+            git::is_ancestor,
+            git::get_git_diff_range,
             git::get_git_remote,
+            git::git_stash_apply,
+            git::git_stash_drop,
+            git::git_stash_pop,
+            git::git_stash_save,
+            git::git_stash_list,
             git::stage_git_file,
             git::stage_git_all,
             git::unstage_git_file,
+            git::stage_file,
+            git::unstage_file,
+            git::stage_hunks,
+            git::git_blame,
+            git::get_git_identity,
+            git::set_git_identity,
             git::revert_git_file,
+            git::discard_file_changes,
             git::revert_git_all,
             git::commit_git,
+            git::commit_all,
             git::push_git,
+            git::git_push,
             git::pull_git,
+            git::git_fetch,
+            git::git_merge,
+            git::git_rebase,
+            git::git_pull,
             git::sync_git,
             git::get_github_issues,
             git::get_github_pull_requests,
@@ -112,9 +147,12 @@ pub fn run() {
             workspaces::list_workspace_files,
             workspaces::open_workspace_in,
             git::list_git_branches,
+            git::list_recent_branches,
             git::checkout_git_branch,
             git::create_git_branch,
+            git::restore_branch,
             codex::model_list,
+            codex::get_codex_project_config,
             codex::account_rate_limits,
             codex::skills_list,
             prompts::prompts_list,
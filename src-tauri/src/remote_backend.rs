@@ -146,11 +146,17 @@ async fn ensure_remote_backend(state: &AppState, app: AppHandle) -> Result<Remot
         }),
     };
 
+    let mut client_info = json!({
+        "clientName": "CodexMonitor",
+        "clientVersion": env!("CARGO_PKG_VERSION"),
+        "platform": std::env::consts::OS,
+    });
     if let Some(token) = token {
-        client
-            .call("auth", json!({ "token": token }))
-            .await
-            .map(|_| ())?;
+        client_info["token"] = json!(token);
+        client.call("auth", client_info).await.map(|_| ())?;
+    } else {
+        // Identity only; older daemons reject the unknown method, which is harmless.
+        let _ = client.call("set_client_info", client_info).await;
     }
 
     {
@@ -217,6 +223,15 @@ async fn read_loop(
             "terminal-output" => {
                 let _ = app.emit("terminal-output", params);
             }
+            "github-issue-new" => {
+                let _ = app.emit("github-issue-new", params);
+            }
+            "branch-diverged" => {
+                let _ = app.emit("branch-diverged", params);
+            }
+            "workspace-connection-changed" => {
+                let _ = app.emit("workspace-connection-changed", params);
+            }
             _ => {}
         }
     }
@@ -1,5 +1,6 @@
 use tauri::{State, Window};
 
+use crate::backend::proxy_env::set_proxy;
 use crate::codex_config;
 use crate::state::AppState;
 use crate::storage::write_settings;
@@ -35,6 +36,7 @@ pub(crate) async fn update_app_settings(
     let _ = codex_config::write_steer_enabled(settings.experimental_steer_enabled);
     let _ = codex_config::write_unified_exec_enabled(settings.experimental_unified_exec_enabled);
     write_settings(&state.settings_path, &settings)?;
+    set_proxy(settings.proxy_url.as_deref(), settings.no_proxy.as_deref());
     let mut current = state.app_settings.lock().await;
     *current = settings.clone();
     let _ = window::apply_window_appearance(&window, settings.theme.as_str());
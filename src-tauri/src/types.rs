@@ -4,14 +4,80 @@ use serde::{Deserialize, Serialize};
 pub(crate) struct GitFileStatus {
     pub(crate) path: String,
     pub(crate) status: String,
-    pub(crate) additions: i64,
-    pub(crate) deletions: i64,
+    /// `None` when stats were skipped because the changeset exceeded
+    /// `maxFilesForStats`.
+    pub(crate) additions: Option<i64>,
+    pub(crate) deletions: Option<i64>,
+    #[serde(default, rename = "oldPath")]
+    pub(crate) old_path: Option<String>,
+    /// Staged (HEAD vs index) status letter, if the file has staged changes.
+    #[serde(default, rename = "indexStatus")]
+    pub(crate) index_status: Option<String>,
+    /// Unstaged (index vs worktree) status letter, if any.
+    #[serde(default, rename = "worktreeStatus")]
+    pub(crate) worktree_status: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub(crate) struct GitFileDiff {
     pub(crate) path: String,
     pub(crate) diff: String,
+    #[serde(default)]
+    pub(crate) additions: usize,
+    #[serde(default)]
+    pub(crate) deletions: usize,
+    /// Previous path when git detected the file as a rename.
+    #[serde(default, rename = "renamedFrom")]
+    pub(crate) renamed_from: Option<String>,
+    /// Binary files are listed without patch text.
+    #[serde(default, rename = "isBinary")]
+    pub(crate) is_binary: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub(crate) struct GitDiffLine {
+    /// `add`, `del` or `ctx`.
+    pub(crate) kind: String,
+    pub(crate) content: String,
+    #[serde(rename = "oldLine")]
+    pub(crate) old_line: Option<u32>,
+    #[serde(rename = "newLine")]
+    pub(crate) new_line: Option<u32>,
+    /// Set when this line is the last in its file and has no trailing newline.
+    #[serde(default, rename = "noNewline")]
+    pub(crate) no_newline: bool,
+    /// Changed character ranges within the line, when word diffs were
+    /// requested and the line pairs with one on the other side.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) highlights: Option<Vec<GitDiffSpan>>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub(crate) struct GitDiffSpan {
+    pub(crate) start: usize,
+    pub(crate) end: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub(crate) struct GitDiffHunk {
+    /// Position of the hunk within the file's diff.
+    pub(crate) id: usize,
+    pub(crate) header: String,
+    #[serde(rename = "oldStart")]
+    pub(crate) old_start: u32,
+    #[serde(rename = "oldLines")]
+    pub(crate) old_lines: u32,
+    #[serde(rename = "newStart")]
+    pub(crate) new_start: u32,
+    #[serde(rename = "newLines")]
+    pub(crate) new_lines: u32,
+    pub(crate) lines: Vec<GitDiffLine>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub(crate) struct GitStructuredFileDiff {
+    pub(crate) path: String,
+    pub(crate) hunks: Vec<GitDiffHunk>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -21,6 +87,84 @@ pub(crate) struct GitCommitDiff {
     pub(crate) diff: String,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub(crate) struct GitBlameHunk {
+    #[serde(rename = "startLine")]
+    pub(crate) start_line: usize,
+    #[serde(rename = "lineCount")]
+    pub(crate) line_count: usize,
+    /// Commit that last touched the lines; `None` for local modifications.
+    pub(crate) sha: Option<String>,
+    pub(crate) author: Option<String>,
+    pub(crate) timestamp: Option<i64>,
+    /// Lines that differ from HEAD in the working tree.
+    pub(crate) local: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub(crate) struct GitCommitDetails {
+    pub(crate) sha: String,
+    pub(crate) summary: String,
+    pub(crate) message: String,
+    pub(crate) author: String,
+    #[serde(rename = "authorEmail")]
+    pub(crate) author_email: String,
+    pub(crate) committer: String,
+    #[serde(rename = "committerEmail")]
+    pub(crate) committer_email: String,
+    pub(crate) timestamp: i64,
+    pub(crate) parents: Vec<String>,
+    pub(crate) files: Vec<GitCommitDiff>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub(crate) struct GitTagDetails {
+    pub(crate) sha: String,
+    pub(crate) name: String,
+    pub(crate) message: String,
+    pub(crate) tagger: Option<String>,
+    #[serde(rename = "taggerEmail")]
+    pub(crate) tagger_email: Option<String>,
+    pub(crate) timestamp: Option<i64>,
+    #[serde(rename = "targetType")]
+    pub(crate) target_type: String,
+    #[serde(rename = "targetSha")]
+    pub(crate) target_sha: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub(crate) struct GitBlobDetails {
+    pub(crate) sha: String,
+    pub(crate) size: usize,
+    #[serde(rename = "isBinary")]
+    pub(crate) is_binary: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub(crate) struct GitTreeDetails {
+    pub(crate) sha: String,
+    pub(crate) entries: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub(crate) struct GitResolvedRef {
+    pub(crate) sha: String,
+    #[serde(rename = "type")]
+    pub(crate) kind: String,
+    #[serde(rename = "shortName")]
+    pub(crate) short_name: String,
+}
+
+/// Any object a ref can resolve to, as returned by `git_show`.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub(crate) enum GitShowResult {
+    Commit(GitCommitDetails),
+    Tag(GitTagDetails),
+    Blob(GitBlobDetails),
+    Tree(GitTreeDetails),
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub(crate) struct GitLogEntry {
     pub(crate) sha: String,
@@ -43,6 +187,64 @@ pub(crate) struct GitLogResponse {
     pub(crate) behind_entries: Vec<GitLogEntry>,
     #[serde(default)]
     pub(crate) upstream: Option<String>,
+    /// Distinguishes "no tracking branch" from an upstream that is in sync.
+    #[serde(default, rename = "hasUpstream")]
+    pub(crate) has_upstream: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub(crate) struct GitStashEntry {
+    pub(crate) index: usize,
+    pub(crate) message: String,
+    /// Branch the stash was made on; `None` when HEAD was detached.
+    pub(crate) branch: Option<String>,
+    pub(crate) timestamp: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub(crate) struct GitMergeResult {
+    #[serde(rename = "hasConflicts")]
+    pub(crate) has_conflicts: bool,
+    #[serde(default)]
+    pub(crate) conflicts: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub(crate) struct GitRebaseResult {
+    #[serde(rename = "hasConflicts")]
+    pub(crate) has_conflicts: bool,
+    #[serde(default)]
+    pub(crate) conflicts: Vec<String>,
+    /// Whether a rebase is still underway after this call.
+    #[serde(rename = "inProgress")]
+    pub(crate) in_progress: bool,
+    /// Current step of an in-progress rebase, e.g. `"1/4"`.
+    pub(crate) step: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub(crate) struct GitStashApplyResult {
+    #[serde(rename = "hasConflicts")]
+    pub(crate) has_conflicts: bool,
+    #[serde(default)]
+    pub(crate) conflicts: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub(crate) struct GitIdentity {
+    pub(crate) name: Option<String>,
+    pub(crate) email: Option<String>,
+    /// Whether both `user.name` and `user.email` are set.
+    pub(crate) configured: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub(crate) struct GitPushResult {
+    pub(crate) remote: Option<String>,
+    #[serde(rename = "createdRemoteBranch")]
+    pub(crate) created_remote_branch: bool,
+    #[serde(rename = "upstreamSet")]
+    pub(crate) upstream_set: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -177,6 +379,12 @@ pub(crate) struct WorkspaceEntry {
     pub(crate) settings: WorkspaceSettings,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub(crate) struct WorkspaceFileList {
+    pub(crate) files: Vec<String>,
+    pub(crate) truncated: bool,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub(crate) struct WorkspaceInfo {
     pub(crate) id: String,
@@ -192,6 +400,17 @@ pub(crate) struct WorkspaceInfo {
     pub(crate) worktree: Option<WorktreeInfo>,
     #[serde(default)]
     pub(crate) settings: WorkspaceSettings,
+    /// Root of the repository the workspace belongs to, which can be a
+    /// parent of `path`.
+    #[serde(default, rename = "gitRoot")]
+    pub(crate) git_root: Option<String>,
+    /// False when `path` was deleted or moved since the workspace was added.
+    #[serde(default = "default_path_exists", rename = "pathExists")]
+    pub(crate) path_exists: bool,
+    /// Full path of the codex binary a session would launch, after the
+    /// workspace `codex_bin`, app default and PATH lookup; `None` if missing.
+    #[serde(default, rename = "resolvedCodexBin")]
+    pub(crate) resolved_codex_bin: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -236,8 +455,32 @@ pub(crate) struct WorkspaceSettings {
     pub(crate) sort_order: Option<u32>,
     #[serde(default, rename = "groupId")]
     pub(crate) group_id: Option<String>,
+    /// Free-form group label for clients without `workspaceGroups`; the name
+    /// of a defined `groupId` takes precedence in `list_workspaces`.
+    #[serde(default, rename = "groupName")]
+    pub(crate) group_name: Option<String>,
     #[serde(default, rename = "gitRoot")]
     pub(crate) git_root: Option<String>,
+    /// Extra arguments appended to `codex app-server`; applied on the next connect.
+    #[serde(default, rename = "extraArgs")]
+    pub(crate) extra_args: Vec<String>,
+    /// Lets full-access turns run without a per-request `confirmFullAccess`.
+    #[serde(default, rename = "allowFullAccess")]
+    pub(crate) allow_full_access: bool,
+    /// Polls GitHub for new issues and emits `github-issue-new` (daemon only).
+    #[serde(default, rename = "watchGithubIssues")]
+    pub(crate) watch_github_issues: bool,
+    #[serde(default, rename = "githubIssuePollSeconds")]
+    pub(crate) github_issue_poll_seconds: Option<u64>,
+    /// Fetches and emits `branch-diverged` when upstream moves ahead (daemon only).
+    #[serde(default, rename = "watchUpstream")]
+    pub(crate) watch_upstream: bool,
+    #[serde(default, rename = "upstreamPollSeconds")]
+    pub(crate) upstream_poll_seconds: Option<u64>,
+    /// Directory relative image attachments resolve against; relative to the
+    /// workspace path, and must lie inside it or an `attachmentRoots` entry.
+    #[serde(default, rename = "attachmentsRoot")]
+    pub(crate) attachments_root: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -252,6 +495,18 @@ pub(crate) struct AppSettings {
     pub(crate) remote_backend_token: Option<String>,
     #[serde(default = "default_access_mode", rename = "defaultAccessMode")]
     pub(crate) default_access_mode: String,
+    #[serde(default, rename = "accessModePolicies")]
+    pub(crate) access_mode_policies: AccessModePolicies,
+    /// Directories outside any workspace that `attachmentsRoot` may point into.
+    #[serde(default, rename = "attachmentRoots")]
+    pub(crate) attachment_roots: Vec<String>,
+    /// Proxy for the git and gh network traffic of both the app and the
+    /// daemon, such as `http://proxy:3128` or `socks5://127.0.0.1:1080`.
+    #[serde(default, rename = "proxyUrl")]
+    pub(crate) proxy_url: Option<String>,
+    /// Hosts that bypass `proxyUrl`, in `NO_PROXY` syntax.
+    #[serde(default, rename = "noProxy")]
+    pub(crate) no_proxy: Option<String>,
     #[serde(
         default = "default_composer_model_shortcut",
         rename = "composerModelShortcut"
@@ -392,6 +647,22 @@ pub(crate) struct AppSettings {
     pub(crate) workspace_groups: Vec<WorkspaceGroup>,
 }
 
+/// Approval policy used for turns in each access mode. Unset modes keep the
+/// built-in mapping (`never` for full access, `on-request` otherwise).
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub(crate) struct AccessModePolicies {
+    #[serde(default, rename = "readOnly", skip_serializing_if = "Option::is_none")]
+    pub(crate) read_only: Option<String>,
+    #[serde(
+        default,
+        rename = "workspaceWrite",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub(crate) workspace_write: Option<String>,
+    #[serde(default, rename = "fullAccess", skip_serializing_if = "Option::is_none")]
+    pub(crate) full_access: Option<String>,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "lowercase")]
 pub(crate) enum BackendMode {
@@ -405,6 +676,10 @@ impl Default for BackendMode {
     }
 }
 
+fn default_path_exists() -> bool {
+    true
+}
+
 fn default_access_mode() -> String {
     "current".to_string()
 }
@@ -566,6 +841,10 @@ impl Default for AppSettings {
             remote_backend_host: default_remote_backend_host(),
             remote_backend_token: None,
             default_access_mode: "current".to_string(),
+            access_mode_policies: AccessModePolicies::default(),
+            attachment_roots: Vec::new(),
+            proxy_url: None,
+            no_proxy: None,
             composer_model_shortcut: default_composer_model_shortcut(),
             composer_access_shortcut: default_composer_access_shortcut(),
             composer_reasoning_shortcut: default_composer_reasoning_shortcut(),
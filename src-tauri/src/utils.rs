@@ -1,7 +1,17 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
 pub(crate) fn normalize_git_path(path: &str) -> String {
     path.replace('\\', "/")
 }
 
+/// Milliseconds since the Unix epoch, as used in event and client timestamps.
+pub(crate) fn now_millis() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as i64
+}
+
 #[cfg(test)]
 mod tests {
     use super::normalize_git_path;
@@ -1,5 +1,5 @@
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::Stdio;
 
 use ignore::WalkBuilder;
@@ -9,14 +9,22 @@ use tokio::io::AsyncWriteExt;
 use tokio::process::Command;
 use uuid::Uuid;
 
+use crate::backend::app_server::validate_extra_args;
+use crate::backend::git_worktrees::{parse_worktree_list, stale_worktree_ids};
+use crate::backend::proxy_env::proxy_env;
+use crate::backend::workspace_files::{collect_file_list, listed_files_limit};
+use crate::backend::workspace_groups::{resolve_workspace_groups, sort_workspaces};
+use crate::backend::workspace_paths::{
+    check_nested_workspace, discover_git_root, resolve_git_root, workspace_info,
+};
 use crate::codex::spawn_workspace_session;
 use crate::codex_home::resolve_workspace_codex_home;
 use crate::remote_backend;
 use crate::state::AppState;
-use crate::git_utils::resolve_git_root;
 use crate::storage::write_workspaces;
 use crate::types::{
-    WorkspaceEntry, WorkspaceInfo, WorkspaceKind, WorkspaceSettings, WorktreeInfo,
+    WorkspaceEntry, WorkspaceFileList, WorkspaceInfo, WorkspaceKind, WorkspaceSettings,
+    WorktreeInfo,
 };
 use crate::utils::normalize_git_path;
 
@@ -61,8 +69,7 @@ fn sanitize_clone_dir_name(name: &str) -> String {
     }
 }
 
-fn list_workspace_files_inner(root: &PathBuf, max_files: usize) -> Vec<String> {
-    let mut results = Vec::new();
+fn list_workspace_files_inner(root: &PathBuf, max_files: usize) -> WorkspaceFileList {
     let walker = WalkBuilder::new(root)
         // Allow hidden entries.
         .hidden(false)
@@ -82,38 +89,25 @@ fn list_workspace_files_inner(root: &PathBuf, max_files: usize) -> Vec<String> {
         })
         .build();
 
-    for entry in walker {
-        let entry = match entry {
-            Ok(entry) => entry,
-            Err(_) => continue,
-        };
-        if !entry.file_type().is_some_and(|ft| ft.is_file()) {
-            continue;
-        }
-        if let Ok(rel_path) = entry.path().strip_prefix(root) {
+    let paths = walker
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_type().is_some_and(|ft| ft.is_file()))
+        .filter_map(|entry| {
+            let rel_path = entry.path().strip_prefix(root).ok()?;
             let normalized = normalize_git_path(&rel_path.to_string_lossy());
-            if !normalized.is_empty() {
-                results.push(normalized);
-            }
-        }
-        if results.len() >= max_files {
-            break;
-        }
-    }
-
-    results.sort();
-    results
+            (!normalized.is_empty()).then_some(normalized)
+        });
+    collect_file_list(paths, max_files)
 }
 
-fn sort_workspaces(list: &mut Vec<WorkspaceInfo>) {
-    list.sort_by(|a, b| {
-        let a_order = a.settings.sort_order.unwrap_or(u32::MAX);
-        let b_order = b.settings.sort_order.unwrap_or(u32::MAX);
-        a_order
-            .cmp(&b_order)
-            .then_with(|| a.name.cmp(&b.name))
-            .then_with(|| a.id.cmp(&b.id))
-    });
+/// [`workspace_info`] against the current default codex binary.
+async fn workspace_info_for(
+    state: &AppState,
+    entry: &WorkspaceEntry,
+    connected: bool,
+) -> WorkspaceInfo {
+    let default_codex_bin = state.app_settings.lock().await.codex_bin.clone();
+    workspace_info(entry, connected, default_codex_bin.as_deref())
 }
 
 fn apply_workspace_settings_update(
@@ -121,6 +115,7 @@ fn apply_workspace_settings_update(
     id: &str,
     settings: WorkspaceSettings,
 ) -> Result<WorkspaceEntry, String> {
+    validate_extra_args(&settings.extra_args)?;
     match workspaces.get_mut(id) {
         Some(entry) => {
             entry.settings = settings.clone();
@@ -132,6 +127,7 @@ fn apply_workspace_settings_update(
 
 async fn run_git_command(repo_path: &PathBuf, args: &[&str]) -> Result<String, String> {
   let output = Command::new("git")
+        .envs(proxy_env())
         .args(args)
         .current_dir(repo_path)
         .output()
@@ -161,6 +157,7 @@ fn is_missing_worktree_error(error: &str) -> bool {
 
 async fn run_git_command_bytes(repo_path: &PathBuf, args: &[&str]) -> Result<Vec<u8>, String> {
     let output = Command::new("git")
+        .envs(proxy_env())
         .args(args)
         .current_dir(repo_path)
         .output()
@@ -186,6 +183,7 @@ async fn run_git_command_bytes(repo_path: &PathBuf, args: &[&str]) -> Result<Vec
 
 async fn run_git_diff(repo_path: &PathBuf, args: &[&str]) -> Result<Vec<u8>, String> {
     let output = Command::new("git")
+        .envs(proxy_env())
         .args(args)
         .current_dir(repo_path)
         .output()
@@ -211,6 +209,7 @@ async fn run_git_diff(repo_path: &PathBuf, args: &[&str]) -> Result<Vec<u8>, Str
 
 async fn git_branch_exists(repo_path: &PathBuf, branch: &str) -> Result<bool, String> {
     let status = Command::new("git")
+        .envs(proxy_env())
         .args(["show-ref", "--verify", &format!("refs/heads/{branch}")])
         .current_dir(repo_path)
         .status()
@@ -219,8 +218,25 @@ async fn git_branch_exists(repo_path: &PathBuf, branch: &str) -> Result<bool, St
     Ok(status.success())
 }
 
+/// Whether `rev` names a commit (SHA, tag, branch or remote branch).
+async fn git_commit_exists(repo_path: &PathBuf, rev: &str) -> Result<bool, String> {
+    if rev.starts_with('-') {
+        return Ok(false);
+    }
+    let status = Command::new("git")
+        .envs(proxy_env())
+        .args(["rev-parse", "--verify", "--quiet", &format!("{rev}^{{commit}}")])
+        .current_dir(repo_path)
+        .stdout(Stdio::null())
+        .status()
+        .await
+        .map_err(|e| format!("Failed to run git: {e}"))?;
+    Ok(status.success())
+}
+
 async fn git_remote_exists(repo_path: &PathBuf, remote: &str) -> Result<bool, String> {
     let status = Command::new("git")
+        .envs(proxy_env())
         .args(["remote", "get-url", remote])
         .current_dir(repo_path)
         .status()
@@ -235,6 +251,7 @@ async fn git_remote_branch_exists(
     branch: &str,
 ) -> Result<bool, String> {
     let output = Command::new("git")
+        .envs(proxy_env())
         .args([
             "ls-remote",
             "--heads",
@@ -295,6 +312,46 @@ async fn git_find_remote_for_branch(
     Ok(None)
 }
 
+async fn git_remote_tracking_branch_exists(
+    repo_path: &PathBuf,
+    remote: &str,
+    branch: &str,
+) -> Result<bool, String> {
+    let status = Command::new("git")
+        .envs(proxy_env())
+        .args([
+            "show-ref",
+            "--verify",
+            &format!("refs/remotes/{remote}/{branch}"),
+        ])
+        .current_dir(repo_path)
+        .status()
+        .await
+        .map_err(|e| format!("Failed to run git: {e}"))?;
+    Ok(status.success())
+}
+
+/// Finds an already-fetched `<remote>/<branch>` ref, preferring `origin`.
+async fn git_find_remote_tracking_branch(
+    repo_path: &PathBuf,
+    branch: &str,
+) -> Result<Option<String>, String> {
+    if git_remote_tracking_branch_exists(repo_path, "origin", branch).await? {
+        return Ok(Some(format!("origin/{branch}")));
+    }
+
+    for remote in git_list_remotes(repo_path).await? {
+        if remote == "origin" {
+            continue;
+        }
+        if git_remote_tracking_branch_exists(repo_path, &remote, branch).await? {
+            return Ok(Some(format!("{remote}/{branch}")));
+        }
+    }
+
+    Ok(None)
+}
+
 async fn unique_branch_name(
     repo_path: &PathBuf,
     desired: &str,
@@ -395,23 +452,22 @@ pub(crate) async fn list_workspaces(
         return serde_json::from_value(response).map_err(|err| err.to_string());
     }
 
+    let (groups, default_codex_bin) = {
+        let settings = state.app_settings.lock().await;
+        (settings.workspace_groups.clone(), settings.codex_bin.clone())
+    };
     let workspaces = state.workspaces.lock().await;
     let sessions = state.sessions.lock().await;
     let mut result = Vec::new();
     for entry in workspaces.values() {
-        result.push(WorkspaceInfo {
-            id: entry.id.clone(),
-            name: entry.name.clone(),
-            path: entry.path.clone(),
-            codex_bin: entry.codex_bin.clone(),
-            connected: sessions.contains_key(&entry.id),
-            kind: entry.kind.clone(),
-            parent_id: entry.parent_id.clone(),
-            worktree: entry.worktree.clone(),
-            settings: entry.settings.clone(),
-        });
+        result.push(workspace_info(
+            entry,
+            sessions.contains_key(&entry.id),
+            default_codex_bin.as_deref(),
+        ));
     }
-    sort_workspaces(&mut result);
+    resolve_workspace_groups(&mut result, &groups);
+    sort_workspaces(&mut result, &groups);
     Ok(result)
 }
 
@@ -438,6 +494,7 @@ pub(crate) async fn is_workspace_path_dir(
 pub(crate) async fn add_workspace(
     path: String,
     codex_bin: Option<String>,
+    force: Option<bool>,
     state: State<'_, AppState>,
     app: AppHandle,
 ) -> Result<WorkspaceInfo, String> {
@@ -446,7 +503,7 @@ pub(crate) async fn add_workspace(
             &*state,
             app,
             "add_workspace",
-            json!({ "path": path, "codex_bin": codex_bin }),
+            json!({ "path": path, "codex_bin": codex_bin, "force": force }),
         )
         .await?;
         return serde_json::from_value(response).map_err(|err| err.to_string());
@@ -455,6 +512,10 @@ pub(crate) async fn add_workspace(
     if !PathBuf::from(&path).is_dir() {
         return Err("Workspace path must be a folder.".to_string());
     }
+    if !force.unwrap_or(false) {
+        let workspaces = state.workspaces.lock().await;
+        check_nested_workspace(&path, workspaces.values())?;
+    }
 
     let name = PathBuf::from(&path)
         .file_name()
@@ -500,17 +561,7 @@ pub(crate) async fn add_workspace(
         .await
         .insert(entry.id.clone(), session);
 
-    Ok(WorkspaceInfo {
-        id: entry.id,
-        name: entry.name,
-        path: entry.path,
-        codex_bin: entry.codex_bin,
-        connected: true,
-        kind: entry.kind,
-        parent_id: entry.parent_id,
-        worktree: entry.worktree,
-        settings: entry.settings,
-    })
+    Ok(workspace_info_for(&state, &entry, true).await)
 }
 
 #[tauri::command]
@@ -625,23 +676,14 @@ pub(crate) async fn add_clone(
         .await
         .insert(entry.id.clone(), session);
 
-    Ok(WorkspaceInfo {
-        id: entry.id,
-        name: entry.name,
-        path: entry.path,
-        codex_bin: entry.codex_bin,
-        connected: true,
-        kind: entry.kind,
-        parent_id: entry.parent_id,
-        worktree: entry.worktree,
-        settings: entry.settings,
-    })
+    Ok(workspace_info_for(&state, &entry, true).await)
 }
 
 #[tauri::command]
 pub(crate) async fn add_worktree(
     parent_id: String,
     branch: String,
+    base_ref: Option<String>,
     state: State<'_, AppState>,
     app: AppHandle,
 ) -> Result<WorkspaceInfo, String> {
@@ -649,6 +691,9 @@ pub(crate) async fn add_worktree(
     if branch.is_empty() {
         return Err("Branch name is required.".to_string());
     }
+    let base_ref = base_ref
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty());
 
     let parent_entry = {
         let workspaces = state.workspaces.lock().await;
@@ -662,6 +707,19 @@ pub(crate) async fn add_worktree(
         return Err("Cannot create a worktree from another worktree.".to_string());
     }
 
+    let repo_path = PathBuf::from(&parent_entry.path);
+    let branch_exists = git_branch_exists(&repo_path, branch).await?;
+    if let Some(base_ref) = base_ref.as_deref() {
+        if !git_commit_exists(&repo_path, base_ref).await? {
+            return Err(format!("Unknown base ref: {base_ref}"));
+        }
+        if branch_exists {
+            return Err(format!(
+                "Branch {branch} already exists; omit baseRef to check it out."
+            ));
+        }
+    }
+
     let worktree_root = app
         .path()
         .app_data_dir()
@@ -675,16 +733,35 @@ pub(crate) async fn add_worktree(
     let worktree_path = unique_worktree_path(&worktree_root, &safe_name);
     let worktree_path_string = worktree_path.to_string_lossy().to_string();
 
-    let branch_exists = git_branch_exists(&PathBuf::from(&parent_entry.path), branch).await?;
-    if branch_exists {
+    if let Some(base_ref) = base_ref.as_deref() {
+        run_git_command(
+            &repo_path,
+            &["worktree", "add", "-b", branch, &worktree_path_string, base_ref],
+        )
+        .await?;
+    } else if branch_exists {
         run_git_command(
-            &PathBuf::from(&parent_entry.path),
+            &repo_path,
             &["worktree", "add", &worktree_path_string, branch],
         )
         .await?;
+    } else if let Some(remote_ref) = git_find_remote_tracking_branch(&repo_path, branch).await? {
+        run_git_command(
+            &repo_path,
+            &[
+                "worktree",
+                "add",
+                "--track",
+                "-b",
+                branch,
+                &worktree_path_string,
+                &remote_ref,
+            ],
+        )
+        .await?;
     } else {
         run_git_command(
-            &PathBuf::from(&parent_entry.path),
+            &repo_path,
             &["worktree", "add", "-b", branch, &worktree_path_string],
         )
         .await?;
@@ -721,17 +798,7 @@ pub(crate) async fn add_worktree(
         .await
         .insert(entry.id.clone(), session);
 
-    Ok(WorkspaceInfo {
-        id: entry.id,
-        name: entry.name,
-        path: entry.path,
-        codex_bin: entry.codex_bin,
-        connected: true,
-        kind: entry.kind,
-        parent_id: entry.parent_id,
-        worktree: entry.worktree,
-        settings: entry.settings,
-    })
+    Ok(workspace_info_for(&state, &entry, true).await)
 }
 
 #[tauri::command]
@@ -864,6 +931,67 @@ pub(crate) async fn remove_worktree(
     Ok(())
 }
 
+#[tauri::command]
+pub(crate) async fn prune_worktrees(
+    parent_id: String,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<serde_json::Value, String> {
+    if remote_backend::is_remote_mode(&*state).await {
+        return remote_backend::call_remote(
+            &*state,
+            app,
+            "prune_worktrees",
+            json!({ "parentId": parent_id }),
+        )
+        .await;
+    }
+
+    let (parent, children) = {
+        let workspaces = state.workspaces.lock().await;
+        let parent = workspaces
+            .get(&parent_id)
+            .cloned()
+            .ok_or("workspace not found")?;
+        let children: Vec<(String, String)> = workspaces
+            .values()
+            .filter(|entry| {
+                entry.kind.is_worktree() && entry.parent_id.as_deref() == Some(&parent_id)
+            })
+            .map(|entry| (entry.id.clone(), entry.path.clone()))
+            .collect();
+        (parent, children)
+    };
+    if parent.kind.is_worktree() {
+        return Err("Prune worktrees from the parent workspace.".to_string());
+    }
+
+    let parent_path = PathBuf::from(&parent.path);
+    let output = run_git_command(&parent_path, &["worktree", "list", "--porcelain"]).await?;
+    let pruned = stale_worktree_ids(&children, &parse_worktree_list(&output));
+    if pruned.is_empty() {
+        return Ok(json!({ "pruned": pruned }));
+    }
+    let _ = run_git_command(&parent_path, &["worktree", "prune", "--expire", "now"]).await;
+
+    for id in &pruned {
+        if let Some(session) = state.sessions.lock().await.remove(id) {
+            let mut child = session.child.lock().await;
+            let _ = child.kill().await;
+        }
+    }
+    {
+        let mut workspaces = state.workspaces.lock().await;
+        for id in &pruned {
+            workspaces.remove(id);
+        }
+        let list: Vec<_> = workspaces.values().cloned().collect();
+        write_workspaces(&state.storage_path, &list)?;
+    }
+
+    Ok(json!({ "pruned": pruned }))
+}
+
 #[tauri::command]
 pub(crate) async fn rename_worktree(
     id: String,
@@ -1012,17 +1140,7 @@ pub(crate) async fn rename_worktree(
     }
 
     let connected = state.sessions.lock().await.contains_key(&entry_snapshot.id);
-    Ok(WorkspaceInfo {
-        id: entry_snapshot.id,
-        name: entry_snapshot.name,
-        path: entry_snapshot.path,
-        codex_bin: entry_snapshot.codex_bin,
-        connected,
-        kind: entry_snapshot.kind,
-        parent_id: entry_snapshot.parent_id,
-        worktree: entry_snapshot.worktree,
-        settings: entry_snapshot.settings,
-    })
+    Ok(workspace_info_for(&state, &entry_snapshot, connected).await)
 }
 
 #[tauri::command]
@@ -1203,6 +1321,7 @@ pub(crate) async fn apply_worktree_changes(
     }
 
     let mut child = Command::new("git")
+        .envs(proxy_env())
         .args(["apply", "--3way", "--whitespace=nowarn", "-"])
         .current_dir(&parent_root)
         .stdin(Stdio::piped())
@@ -1269,17 +1388,7 @@ pub(crate) async fn update_workspace_settings(
     write_workspaces(&state.storage_path, &list)?;
 
     let connected = state.sessions.lock().await.contains_key(&id);
-    Ok(WorkspaceInfo {
-        id: entry_snapshot.id,
-        name: entry_snapshot.name,
-        path: entry_snapshot.path,
-        codex_bin: entry_snapshot.codex_bin,
-        connected,
-        kind: entry_snapshot.kind,
-        parent_id: entry_snapshot.parent_id,
-        worktree: entry_snapshot.worktree,
-        settings: entry_snapshot.settings,
-    })
+    Ok(workspace_info_for(&state, &entry_snapshot, connected).await)
 }
 
 #[tauri::command]
@@ -1303,17 +1412,103 @@ pub(crate) async fn update_workspace_codex_bin(
     write_workspaces(&state.storage_path, &list)?;
 
     let connected = state.sessions.lock().await.contains_key(&id);
-    Ok(WorkspaceInfo {
-        id: entry_snapshot.id,
-        name: entry_snapshot.name,
-        path: entry_snapshot.path,
-        codex_bin: entry_snapshot.codex_bin,
-        connected,
-        kind: entry_snapshot.kind,
-        parent_id: entry_snapshot.parent_id,
-        worktree: entry_snapshot.worktree,
-        settings: entry_snapshot.settings,
-    })
+    Ok(workspace_info_for(&state, &entry_snapshot, connected).await)
+}
+
+#[tauri::command]
+pub(crate) async fn rename_workspace(
+    id: String,
+    name: String,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<WorkspaceInfo, String> {
+    if remote_backend::is_remote_mode(&*state).await {
+        let response = remote_backend::call_remote(
+            &*state,
+            app,
+            "rename_workspace",
+            json!({ "id": id, "name": name }),
+        )
+        .await?;
+        return serde_json::from_value(response).map_err(|err| err.to_string());
+    }
+
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Workspace name is required.".to_string());
+    }
+    let (entry_snapshot, list) = {
+        let mut workspaces = state.workspaces.lock().await;
+        let entry_snapshot = match workspaces.get_mut(&id) {
+            Some(entry) => {
+                entry.name = name.to_string();
+                entry.clone()
+            }
+            None => return Err("workspace not found".to_string()),
+        };
+        let list: Vec<_> = workspaces.values().cloned().collect();
+        (entry_snapshot, list)
+    };
+    write_workspaces(&state.storage_path, &list)?;
+
+    let connected = state.sessions.lock().await.contains_key(&id);
+    Ok(workspace_info_for(&state, &entry_snapshot, connected).await)
+}
+
+#[tauri::command]
+pub(crate) async fn relocate_workspace(
+    id: String,
+    path: String,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<WorkspaceInfo, String> {
+    if remote_backend::is_remote_mode(&*state).await {
+        let response = remote_backend::call_remote(
+            &*state,
+            app,
+            "relocate_workspace",
+            json!({ "id": id, "path": path }),
+        )
+        .await?;
+        return serde_json::from_value(response).map_err(|err| err.to_string());
+    }
+
+    let path = path.trim().to_string();
+    if !PathBuf::from(&path).is_dir() {
+        return Err("Workspace path must be a folder.".to_string());
+    }
+    let kind = {
+        let workspaces = state.workspaces.lock().await;
+        let entry = workspaces.get(&id).ok_or("workspace not found")?;
+        check_nested_workspace(
+            &path,
+            workspaces.values().filter(|other| other.id != id),
+        )?;
+        entry.kind.clone()
+    };
+    if !kind.is_worktree() && discover_git_root(Path::new(&path)).is_none() {
+        return Err(format!("Not a git repository: {path}"));
+    }
+
+    let (entry_snapshot, list) = {
+        let mut workspaces = state.workspaces.lock().await;
+        let entry_snapshot = match workspaces.get_mut(&id) {
+            Some(entry) => {
+                entry.path = path;
+                entry.clone()
+            }
+            None => return Err("workspace not found".to_string()),
+        };
+        let list: Vec<_> = workspaces.values().cloned().collect();
+        (entry_snapshot, list)
+    };
+    write_workspaces(&state.storage_path, &list)?;
+    if let Some(session) = state.sessions.lock().await.remove(&id) {
+        let mut child = session.child.lock().await;
+        let _ = child.kill().await;
+    }
+
+    Ok(workspace_info_for(&state, &entry_snapshot, false).await)
 }
 
 #[tauri::command]
@@ -1343,6 +1538,9 @@ pub(crate) async fn connect_workspace(
             })
             .ok_or("workspace not found")?
     };
+    if !Path::new(&entry.path).exists() {
+        return Err(format!("workspace path no longer exists: {}", entry.path));
+    }
 
     let default_bin = {
         let settings = state.app_settings.lock().await;
@@ -1354,20 +1552,51 @@ pub(crate) async fn connect_workspace(
     Ok(())
 }
 
+#[tauri::command]
+pub(crate) async fn disconnect_workspace(
+    id: String,
+    state: State<'_, AppState>,
+    app: AppHandle,
+) -> Result<(), String> {
+    if remote_backend::is_remote_mode(&*state).await {
+        remote_backend::call_remote(&*state, app, "disconnect_workspace", json!({ "id": id }))
+            .await?;
+        return Ok(());
+    }
+
+    if !state.workspaces.lock().await.contains_key(&id) {
+        return Err("workspace not found".to_string());
+    }
+    if let Some(session) = state.sessions.lock().await.remove(&id) {
+        let mut child = session.child.lock().await;
+        let _ = child.kill().await;
+    }
+    Ok(())
+}
+
 #[tauri::command]
 pub(crate) async fn list_workspace_files(
     workspace_id: String,
+    max_files: Option<usize>,
     state: State<'_, AppState>,
     app: AppHandle,
-) -> Result<Vec<String>, String> {
+) -> Result<WorkspaceFileList, String> {
     if remote_backend::is_remote_mode(&*state).await {
         let response = remote_backend::call_remote(
             &*state,
             app,
             "list_workspace_files",
-            json!({ "workspaceId": workspace_id }),
+            json!({ "workspaceId": workspace_id, "maxFiles": max_files }),
         )
         .await?;
+        // Daemons from before `maxFiles` return the bare list of paths.
+        if response.is_array() {
+            let files = serde_json::from_value(response).map_err(|err| err.to_string())?;
+            return Ok(WorkspaceFileList {
+                files,
+                truncated: false,
+            });
+        }
         return serde_json::from_value(response).map_err(|err| err.to_string());
     }
 
@@ -1376,7 +1605,7 @@ pub(crate) async fn list_workspace_files(
         .get(&workspace_id)
         .ok_or("workspace not found")?;
     let root = PathBuf::from(&entry.path);
-    Ok(list_workspace_files_inner(&root, usize::MAX))
+    Ok(list_workspace_files_inner(&root, listed_files_limit(max_files)))
 }
 
 #[tauri::command]
@@ -1443,8 +1672,19 @@ mod tests {
                 sidebar_collapsed: false,
                 sort_order,
                 group_id: None,
+                group_name: None,
                 git_root: None,
+                extra_args: Vec::new(),
+                allow_full_access: false,
+                watch_github_issues: false,
+                github_issue_poll_seconds: None,
+                watch_upstream: false,
+                upstream_poll_seconds: None,
+                attachments_root: None,
             },
+            git_root: None,
+            path_exists: true,
+            resolved_codex_bin: None,
         }
     }
 
@@ -1508,7 +1748,7 @@ mod tests {
             workspace("gamma", Some(1)),
         ];
 
-        sort_workspaces(&mut items);
+        sort_workspaces(&mut items, &[]);
 
         let names: Vec<_> = items.into_iter().map(|item| item.name).collect();
         assert_eq!(names, vec!["gamma", "delta", "alpha", "beta"]);
@@ -1523,14 +1763,14 @@ mod tests {
             workspace("gamma", None),
         ];
 
-        sort_workspaces(&mut items);
+        sort_workspaces(&mut items, &[]);
 
         let names: Vec<_> = items.into_iter().map(|item| item.name).collect();
         assert_eq!(names, vec!["alpha", "beta", "delta", "gamma"]);
     }
 
     #[test]
-    fn sort_workspaces_ignores_group_ids() {
+    fn sort_workspaces_ignores_undefined_group_ids() {
         let mut first = workspace("beta", Some(2));
         first.settings.group_id = Some("group-b".to_string());
         let mut second = workspace("alpha", Some(1));
@@ -1539,7 +1779,7 @@ mod tests {
         third.settings.group_id = Some("group-a".to_string());
 
         let mut items = vec![first, second, third];
-        sort_workspaces(&mut items);
+        sort_workspaces(&mut items, &[]);
 
         let names: Vec<_> = items.into_iter().map(|item| item.name).collect();
         assert_eq!(names, vec!["alpha", "beta", "gamma"]);
@@ -1552,7 +1792,7 @@ mod tests {
             workspace_with_id_and_kind("alpha", "a-id", Some(1), WorkspaceKind::Main),
         ];
 
-        sort_workspaces(&mut items);
+        sort_workspaces(&mut items, &[]);
 
         let ids: Vec<_> = items.into_iter().map(|item| item.id).collect();
         assert_eq!(ids, vec!["a-id", "b-id"]);
@@ -1565,7 +1805,7 @@ mod tests {
             workspace_with_id_and_kind("worktree", "worktree", Some(1), WorkspaceKind::Worktree),
         ];
 
-        sort_workspaces(&mut items);
+        sort_workspaces(&mut items, &[]);
 
         let kinds: Vec<_> = items.into_iter().map(|item| item.kind).collect();
         assert!(matches!(
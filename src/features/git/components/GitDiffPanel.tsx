@@ -71,14 +71,14 @@ type GitDiffPanelProps = {
   stagedFiles: {
     path: string;
     status: string;
-    additions: number;
-    deletions: number;
+    additions: number | null;
+    deletions: number | null;
   }[];
   unstagedFiles: {
     path: string;
     status: string;
-    additions: number;
-    deletions: number;
+    additions: number | null;
+    deletions: number | null;
   }[];
   onStageAllChanges?: () => void | Promise<void>;
   onStageFile?: (path: string) => Promise<void> | void;
@@ -252,8 +252,8 @@ const DEPTH_OPTIONS = [1, 2, 3, 4, 5, 6];
 type DiffFile = {
   path: string;
   status: string;
-  additions: number;
-  deletions: number;
+  additions: number | null;
+  deletions: number | null;
 };
 
 type DiffFileRowProps = {
@@ -315,14 +315,16 @@ function DiffFileRow({
         {dir && <div className="diff-dir">{dir}</div>}
       </div>
       <div className="diff-row-meta">
-        <span
-          className="diff-counts-inline"
-          aria-label={`+${file.additions} -${file.deletions}`}
-        >
-          <span className="diff-add">+{file.additions}</span>
-          <span className="diff-sep">/</span>
-          <span className="diff-del">-{file.deletions}</span>
-        </span>
+        {file.additions !== null && file.deletions !== null && (
+          <span
+            className="diff-counts-inline"
+            aria-label={`+${file.additions} -${file.deletions}`}
+          >
+            <span className="diff-add">+{file.additions}</span>
+            <span className="diff-sep">/</span>
+            <span className="diff-del">-{file.deletions}</span>
+          </span>
+        )}
         <div className="diff-row-actions" role="group" aria-label="File actions">
           {showStage && (
             <button
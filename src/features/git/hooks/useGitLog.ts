@@ -10,6 +10,7 @@ type GitLogState = {
   aheadEntries: GitLogEntry[];
   behindEntries: GitLogEntry[];
   upstream: string | null;
+  hasUpstream: boolean;
   isLoading: boolean;
   error: string | null;
 };
@@ -22,6 +23,7 @@ const emptyState: GitLogState = {
   aheadEntries: [],
   behindEntries: [],
   upstream: null,
+  hasUpstream: false,
   isLoading: false,
   error: null,
 };
@@ -61,6 +63,7 @@ export function useGitLog(
         aheadEntries: response.aheadEntries,
         behindEntries: response.behindEntries,
         upstream: response.upstream,
+        hasUpstream: response.hasUpstream ?? Boolean(response.upstream),
         isLoading: false,
         error: null,
       });
@@ -80,6 +83,7 @@ export function useGitLog(
         aheadEntries: [],
         behindEntries: [],
         upstream: null,
+        hasUpstream: false,
         isLoading: false,
         error: error instanceof Error ? error.message : String(error),
       });
@@ -116,6 +120,7 @@ export function useGitLog(
     aheadEntries: state.aheadEntries,
     behindEntries: state.behindEntries,
     upstream: state.upstream,
+    hasUpstream: state.hasUpstream,
     isLoading: state.isLoading,
     error: state.error,
     refresh,
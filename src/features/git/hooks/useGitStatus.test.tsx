@@ -9,6 +9,10 @@ vi.mock("../../../services/tauri", () => ({
   getGitStatus: vi.fn(),
 }));
 
+vi.mock("../../../services/events", () => ({
+  subscribeGitStatusChanged: vi.fn(() => () => {}),
+}));
+
 const workspace: WorkspaceInfo = {
   id: "workspace-1",
   name: "CodexMonitor",
@@ -32,6 +36,7 @@ const makeStatus = (branchName: string, additions = 0, deletions = 0) => ({
   unstagedFiles: [],
   totalAdditions: additions,
   totalDeletions: deletions,
+  stashCount: 0,
 });
 
 describe("useGitStatus", () => {
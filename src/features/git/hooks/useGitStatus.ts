@@ -1,5 +1,6 @@
 import { useCallback, useEffect, useRef, useState } from "react";
 import type { GitFileStatus, WorkspaceInfo } from "../../../types";
+import { subscribeGitStatusChanged } from "../../../services/events";
 import { getGitStatus } from "../../../services/tauri";
 
 type GitStatusState = {
@@ -9,6 +10,7 @@ type GitStatusState = {
   unstagedFiles: GitFileStatus[];
   totalAdditions: number;
   totalDeletions: number;
+  stashCount: number;
   error: string | null;
 };
 
@@ -19,6 +21,7 @@ const emptyStatus: GitStatusState = {
   unstagedFiles: [],
   totalAdditions: 0,
   totalDeletions: 0,
+  stashCount: 0,
   error: null,
 };
 
@@ -64,6 +67,7 @@ export function useGitStatus(activeWorkspace: WorkspaceInfo | null) {
         const nextStatus = {
           ...data,
           branchName: resolvedBranchName,
+          stashCount: data.stashCount ?? 0,
           error: null,
         };
         setStatus(nextStatus);
@@ -111,9 +115,15 @@ export function useGitStatus(activeWorkspace: WorkspaceInfo | null) {
 
     fetchStatus();
     const interval = window.setInterval(fetchStatus, REFRESH_INTERVAL_MS);
+    const unsubscribe = subscribeGitStatusChanged((event) => {
+      if (event.workspaceId === workspaceId) {
+        fetchStatus();
+      }
+    });
 
     return () => {
       window.clearInterval(interval);
+      unsubscribe();
     };
   }, [refresh, workspaceId]);
 
@@ -1,5 +1,6 @@
 // @vitest-environment jsdom
 import { act, renderHook, waitFor } from "@testing-library/react";
+import { ask } from "@tauri-apps/plugin-dialog";
 import { afterEach, beforeEach, describe, expect, it, vi } from "vitest";
 import type { WorkspaceInfo } from "../../../types";
 import type { useAppServerEvents } from "../../app/hooks/useAppServerEvents";
@@ -35,6 +36,10 @@ vi.mock("../../../services/tauri", () => ({
   interruptTurn: vi.fn(),
 }));
 
+vi.mock("@tauri-apps/plugin-dialog", () => ({
+  ask: vi.fn(),
+}));
+
 const workspace: WorkspaceInfo = {
   id: "ws-1",
   name: "CodexMonitor",
@@ -426,4 +431,48 @@ describe("useThreads UX integration", () => {
     ]);
     expect(unpinnedRows.map((row) => row.thread.id)).toEqual(["thread-b"]);
   });
+
+  it("confirms full access once per workspace before sending the flag", async () => {
+    vi.mocked(sendUserMessage).mockResolvedValue({
+      result: { turn: { id: "turn-1" } },
+    });
+    vi.mocked(ask).mockResolvedValueOnce(false).mockResolvedValueOnce(true);
+
+    const { result } = renderHook(() =>
+      useThreads({
+        activeWorkspace: workspace,
+        onWorkspaceConnected: vi.fn(),
+        accessMode: "full-access",
+      }),
+    );
+
+    await act(async () => {
+      await result.current.sendUserMessageToThread(workspace, "thread-1", "Declined");
+    });
+    expect(sendUserMessage).not.toHaveBeenCalled();
+
+    await act(async () => {
+      await result.current.sendUserMessageToThread(workspace, "thread-1", "First");
+      await result.current.sendUserMessageToThread(workspace, "thread-1", "Second");
+    });
+    expect(ask).toHaveBeenCalledTimes(2);
+    expect(sendUserMessage).toHaveBeenCalledTimes(2);
+    expect(vi.mocked(sendUserMessage).mock.calls[1][3]).toMatchObject({
+      accessMode: "full-access",
+      confirmFullAccess: true,
+    });
+
+    const optedIn: WorkspaceInfo = {
+      ...workspace,
+      id: "ws-2",
+      settings: { sidebarCollapsed: false, allowFullAccess: true },
+    };
+    await act(async () => {
+      await result.current.sendUserMessageToThread(optedIn, "thread-9", "Trusted");
+    });
+    expect(ask).toHaveBeenCalledTimes(2);
+    expect(vi.mocked(sendUserMessage).mock.calls[2][3]).toMatchObject({
+      confirmFullAccess: false,
+    });
+  });
 });
@@ -1,5 +1,6 @@
 import { useCallback, useEffect, useMemo, useReducer, useRef, useState } from "react";
 import * as Sentry from "@sentry/react";
+import { ask } from "@tauri-apps/plugin-dialog";
 import type {
   ApprovalRequest,
   AppServerEvent,
@@ -427,6 +428,7 @@ export function useThreads({
   const pendingInterruptsRef = useRef<Set<string>>(new Set());
   const customNamesRef = useRef<CustomNamesMap>({});
   const approvalAllowlistRef = useRef<Record<string, string[][]>>({});
+  const fullAccessConfirmedRef = useRef<Set<string>>(new Set());
 
   useEffect(() => {
     if (typeof window === "undefined") {
@@ -1538,6 +1540,27 @@ export function useThreads({
         }
         finalText = promptExpansion?.expanded ?? messageText;
       }
+      // Workspaces that opted into full access need no confirmation; others
+      // ask once per app session before the flag is sent.
+      let confirmFullAccess = false;
+      if (accessMode === "full-access" && !workspace.settings.allowFullAccess) {
+        if (!fullAccessConfirmedRef.current.has(workspace.id)) {
+          const confirmed = await ask(
+            `Full access runs Codex in "${workspace.name}" without a sandbox or approval prompts. Allow it for this workspace until CodexMonitor restarts?`,
+            {
+              title: "Full Access",
+              kind: "warning",
+              okLabel: "Allow Full Access",
+              cancelLabel: "Cancel",
+            },
+          );
+          if (!confirmed) {
+            return;
+          }
+          fullAccessConfirmedRef.current.add(workspace.id);
+        }
+        confirmFullAccess = true;
+      }
       Sentry.metrics.count("prompt_sent", 1, {
         attributes: {
           workspace_id: workspace.id,
@@ -1582,7 +1605,14 @@ export function useThreads({
             workspace.id,
             threadId,
             finalText,
-            { model, effort, collaborationMode, accessMode, images },
+            {
+              model,
+              effort,
+              collaborationMode,
+              accessMode,
+              images,
+              confirmFullAccess,
+            },
           )) as Record<string, unknown>;
         onDebug?.({
           id: `${Date.now()}-server-turn-start`,
@@ -12,6 +12,7 @@ export function useWorkspaceFiles({
   onDebug,
 }: UseWorkspaceFilesOptions) {
   const [files, setFiles] = useState<string[]>([]);
+  const [truncated, setTruncated] = useState(false);
   const [isLoading, setIsLoading] = useState(false);
   const lastFetchedWorkspaceId = useRef<string | null>(null);
   const inFlight = useRef<string | null>(null);
@@ -47,7 +48,8 @@ export function useWorkspaceFiles({
         payload: response,
       });
       if (requestWorkspaceId === workspaceId) {
-        setFiles(Array.isArray(response) ? response : []);
+        setFiles(Array.isArray(response?.files) ? response.files : []);
+        setTruncated(Boolean(response?.truncated));
         lastFetchedWorkspaceId.current = requestWorkspaceId;
       }
     } catch (error) {
@@ -68,6 +70,7 @@ export function useWorkspaceFiles({
 
   useEffect(() => {
     setFiles([]);
+    setTruncated(false);
     lastFetchedWorkspaceId.current = null;
     inFlight.current = null;
     setIsLoading(Boolean(workspaceId && isConnected));
@@ -101,6 +104,7 @@ export function useWorkspaceFiles({
 
   return {
     files: fileOptions,
+    truncated,
     isLoading,
     refreshFiles,
   };
@@ -224,7 +224,32 @@ export function useWorkspaces(options: UseWorkspacesOptions = {}) {
         payload: { path: selection },
       });
       try {
-        const workspace = await addWorkspaceService(selection, defaultCodexBin ?? null);
+        let workspace: WorkspaceInfo;
+        try {
+          workspace = await addWorkspaceService(selection, defaultCodexBin ?? null);
+        } catch (error) {
+          const reason = error instanceof Error ? error.message : String(error);
+          if (!reason.startsWith("nested_workspace")) {
+            throw error;
+          }
+          const confirmed = await ask(
+            `${reason.replace(/^nested_workspace:\s*/, "").replace(/\s*Pass force.*$/, "")}\n\nOverlapping workspaces show the same files and changes twice. Add it anyway?`,
+            {
+              title: "Nested Workspace",
+              kind: "warning",
+              okLabel: "Add Anyway",
+              cancelLabel: "Cancel",
+            },
+          );
+          if (!confirmed) {
+            return null;
+          }
+          workspace = await addWorkspaceService(
+            selection,
+            defaultCodexBin ?? null,
+            true,
+          );
+        }
         setWorkspaces((prev) => [...prev, workspace]);
         setActiveWorkspaceId(workspace.id);
         Sentry.metrics.count("workspace_added", 1, {
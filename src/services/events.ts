@@ -9,6 +9,11 @@ export type TerminalOutputEvent = {
   data: string;
 };
 
+export type GitStatusChangedEvent = {
+  workspaceId: string;
+  reason: string;
+};
+
 type SubscriptionOptions = {
   onError?: (error: unknown) => void;
 };
@@ -80,6 +85,7 @@ const appServerHub = createEventHub<AppServerEvent>("app-server-event");
 const dictationDownloadHub = createEventHub<DictationModelStatus>("dictation-download");
 const dictationEventHub = createEventHub<DictationEvent>("dictation-event");
 const terminalOutputHub = createEventHub<TerminalOutputEvent>("terminal-output");
+const gitStatusChangedHub = createEventHub<GitStatusChangedEvent>("git-status-changed");
 const updaterCheckHub = createEventHub<void>("updater-check");
 const menuNewAgentHub = createEventHub<void>("menu-new-agent");
 const menuNewWorktreeAgentHub = createEventHub<void>("menu-new-worktree-agent");
@@ -129,6 +135,13 @@ export function subscribeTerminalOutput(
   return terminalOutputHub.subscribe(onEvent, options);
 }
 
+export function subscribeGitStatusChanged(
+  onEvent: (event: GitStatusChangedEvent) => void,
+  options?: SubscriptionOptions,
+): Unsubscribe {
+  return gitStatusChangedHub.subscribe(onEvent, options);
+}
+
 export function subscribeUpdaterCheck(
   onEvent: () => void,
   options?: SubscriptionOptions,
@@ -47,6 +47,8 @@ describe("tauri invoke wrappers", () => {
 
     expect(invokeMock).toHaveBeenCalledWith("get_git_status", {
       workspaceId: "ws-1",
+      subdir: null,
+      maxFilesForStats: null,
     });
   });
 
@@ -110,6 +112,9 @@ describe("tauri invoke wrappers", () => {
       accessMode: "full-access",
       images: ["image.png"],
       collaborationMode: null,
+      cwd: null,
+      confirmFullAccess: false,
+      whenBusy: null,
     });
   });
 
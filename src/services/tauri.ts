@@ -6,18 +6,32 @@ import type {
   DictationModelStatus,
   DictationSessionState,
   LocalUsageSnapshot,
+  WorkspaceFileList,
   WorkspaceInfo,
   WorkspaceSettings,
 } from "../types";
 import type {
+  CodexProjectConfig,
+  DiffDigest,
+  GitBlameHunk,
   GitFileDiff,
   GitFileStatus,
+  GitCommitDetails,
   GitCommitDiff,
   GitHubIssuesResponse,
   GitHubPullRequestComment,
   GitHubPullRequestDiff,
   GitHubPullRequestsResponse,
+  GitIdentity,
   GitLogResponse,
+  GitMergeResult,
+  GitPushResult,
+  GitRebaseResult,
+  GitResolvedRef,
+  GitShowResult,
+  GitStashApplyResult,
+  GitStashEntry,
+  GitStructuredFileDiff,
   ReviewTarget,
 } from "../types";
 
@@ -52,7 +66,11 @@ export async function listWorkspaces(): Promise<WorkspaceInfo[]> {
 export async function addWorkspace(
   path: string,
   codex_bin: string | null,
+  force?: boolean,
 ): Promise<WorkspaceInfo> {
+  if (force) {
+    return invoke<WorkspaceInfo>("add_workspace", { path, codex_bin, force });
+  }
   return invoke<WorkspaceInfo>("add_workspace", { path, codex_bin });
 }
 
@@ -75,8 +93,13 @@ export async function addClone(
 export async function addWorktree(
   parentId: string,
   branch: string,
+  baseRef?: string,
 ): Promise<WorkspaceInfo> {
-  return invoke<WorkspaceInfo>("add_worktree", { parentId, branch });
+  return invoke<WorkspaceInfo>("add_worktree", {
+    parentId,
+    branch,
+    baseRef: baseRef ?? null,
+  });
 }
 
 export async function updateWorkspaceSettings(
@@ -93,6 +116,20 @@ export async function updateWorkspaceCodexBin(
   return invoke<WorkspaceInfo>("update_workspace_codex_bin", { id, codex_bin });
 }
 
+export async function renameWorkspace(
+  id: string,
+  name: string,
+): Promise<WorkspaceInfo> {
+  return invoke<WorkspaceInfo>("rename_workspace", { id, name });
+}
+
+export async function relocateWorkspace(
+  id: string,
+  path: string,
+): Promise<WorkspaceInfo> {
+  return invoke<WorkspaceInfo>("relocate_workspace", { id, path });
+}
+
 export async function removeWorkspace(id: string): Promise<void> {
   return invoke("remove_workspace", { id });
 }
@@ -101,6 +138,12 @@ export async function removeWorktree(id: string): Promise<void> {
   return invoke("remove_worktree", { id });
 }
 
+export async function pruneWorktrees(
+  parentId: string,
+): Promise<{ pruned: string[] }> {
+  return invoke<{ pruned: string[] }>("prune_worktrees", { parentId });
+}
+
 export async function renameWorktree(
   id: string,
   branch: string,
@@ -128,8 +171,12 @@ export async function connectWorkspace(id: string): Promise<void> {
   return invoke("connect_workspace", { id });
 }
 
-export async function startThread(workspaceId: string) {
-  return invoke<any>("start_thread", { workspaceId });
+export async function disconnectWorkspace(id: string): Promise<void> {
+  return invoke("disconnect_workspace", { id });
+}
+
+export async function startThread(workspaceId: string, cwd?: string | null) {
+  return invoke<any>("start_thread", { workspaceId, cwd: cwd ?? null });
 }
 
 export async function sendUserMessage(
@@ -142,6 +189,9 @@ export async function sendUserMessage(
     accessMode?: "read-only" | "current" | "full-access";
     images?: string[];
     collaborationMode?: Record<string, unknown> | null;
+    cwd?: string | null;
+    confirmFullAccess?: boolean;
+    whenBusy?: "queue" | "reject";
   },
 ) {
   return invoke("send_user_message", {
@@ -153,6 +203,9 @@ export async function sendUserMessage(
     accessMode: options?.accessMode ?? null,
     images: options?.images ?? null,
     collaborationMode: options?.collaborationMode ?? null,
+    cwd: options?.cwd ?? null,
+    confirmFullAccess: options?.confirmFullAccess ?? false,
+    whenBusy: options?.whenBusy ?? null,
   });
 }
 
@@ -196,15 +249,47 @@ export async function rememberApprovalRule(
   return invoke("remember_approval_rule", { workspaceId, command });
 }
 
-export async function getGitStatus(workspace_id: string): Promise<{
+export async function getGitStatus(
+  workspace_id: string,
+  subdir?: string | null,
+  maxFilesForStats?: number | null,
+): Promise<{
   branchName: string;
   files: GitFileStatus[];
   stagedFiles: GitFileStatus[];
   unstagedFiles: GitFileStatus[];
   totalAdditions: number;
   totalDeletions: number;
+  stashCount: number;
 }> {
-  return invoke("get_git_status", { workspaceId: workspace_id });
+  return invoke("get_git_status", {
+    workspaceId: workspace_id,
+    subdir: subdir ?? null,
+    maxFilesForStats: maxFilesForStats ?? null,
+  });
+}
+
+export type GitStatusSummary = Awaited<ReturnType<typeof getGitStatus>> & {
+  dirty: boolean;
+  ahead: number;
+  behind: number;
+  upstream: string | null;
+};
+
+export async function getGitStatusBatch(
+  workspaceIds?: string[] | null,
+  maxFilesForStats?: number | null,
+): Promise<
+  Record<string, { status: GitStatusSummary | null; error: string | null }>
+> {
+  return invoke("get_git_status_batch", {
+    workspaceIds: workspaceIds ?? null,
+    maxFilesForStats: maxFilesForStats ?? null,
+  });
+}
+
+export async function isWorkspaceDirty(workspaceId: string): Promise<boolean> {
+  return invoke<boolean>("is_workspace_dirty", { workspaceId });
 }
 
 export async function listGitRoots(
@@ -220,6 +305,41 @@ export async function getGitDiffs(
   return invoke("get_git_diffs", { workspaceId: workspace_id });
 }
 
+export async function getGitFileDiff(
+  workspaceId: string,
+  path: string,
+  options: { staged?: boolean } = {},
+): Promise<GitFileDiff> {
+  return invoke<GitFileDiff>("get_git_file_diff", {
+    workspaceId,
+    path,
+    staged: options.staged ?? false,
+  });
+}
+
+export async function getGitFileDiffStructured(
+  workspaceId: string,
+  path: string,
+  options: { staged?: boolean; wordDiff?: boolean } = {},
+): Promise<GitStructuredFileDiff> {
+  return invoke<GitStructuredFileDiff>("get_git_file_diff_structured", {
+    workspaceId,
+    path,
+    staged: options.staged ?? null,
+    wordDiff: options.wordDiff ?? false,
+  });
+}
+
+export async function getDiffDigest(
+  workspace_id: string,
+  maxBytes?: number,
+): Promise<DiffDigest> {
+  return invoke("get_diff_digest", {
+    workspaceId: workspace_id,
+    maxBytes: maxBytes ?? null,
+  });
+}
+
 export async function getGitLog(
   workspace_id: string,
   limit = 40,
@@ -234,10 +354,95 @@ export async function getGitCommitDiff(
   return invoke("get_git_commit_diff", { workspaceId: workspace_id, sha });
 }
 
+export async function getCommitDetails(
+  workspaceId: string,
+  sha: string,
+  parentIndex?: number,
+): Promise<GitCommitDetails> {
+  return invoke<GitCommitDetails>("get_commit_details", {
+    workspaceId,
+    sha,
+    parentIndex: parentIndex ?? null,
+  });
+}
+
+export async function gitShow(
+  workspaceId: string,
+  ref: string,
+): Promise<GitShowResult> {
+  return invoke<GitShowResult>("git_show", { workspaceId, rev: ref });
+}
+
+export async function resolveRef(
+  workspaceId: string,
+  ref: string,
+): Promise<GitResolvedRef> {
+  return invoke<GitResolvedRef>("resolve_ref", { workspaceId, rev: ref });
+}
+
+export async function getMergeBase(
+  workspaceId: string,
+  base: string,
+  head: string,
+): Promise<string> {
+  return invoke<string>("get_merge_base", { workspaceId, base, head });
+}
+
+export async function isAncestor(
+  workspaceId: string,
+  ancestor: string,
+  descendant: string,
+): Promise<boolean> {
+  return invoke<boolean>("is_ancestor", { workspaceId, ancestor, descendant });
+}
+
+export async function getGitDiffRange(
+  workspaceId: string,
+  base: string,
+  head: string,
+  mode: "twoDot" | "threeDot" = "threeDot",
+): Promise<GitCommitDiff[]> {
+  return invoke<GitCommitDiff[]>("get_git_diff_range", {
+    workspaceId,
+    base,
+    head,
+    mode,
+  });
+}
+
 export async function getGitRemote(workspace_id: string): Promise<string | null> {
   return invoke("get_git_remote", { workspaceId: workspace_id });
 }
 
+export async function gitStashApply(
+  workspaceId: string,
+  index = 0,
+): Promise<GitStashApplyResult> {
+  return invoke<GitStashApplyResult>("git_stash_apply", { workspaceId, index });
+}
+
+export async function gitStashDrop(workspaceId: string, index = 0): Promise<void> {
+  return invoke("git_stash_drop", { workspaceId, index });
+}
+
+export async function gitStashPop(
+  workspaceId: string,
+  index = 0,
+): Promise<GitStashApplyResult> {
+  return invoke<GitStashApplyResult>("git_stash_pop", { workspaceId, index });
+}
+
+export async function gitStashSave(
+  workspaceId: string,
+  message?: string | null,
+): Promise<void> {
+  return invoke("git_stash_save", { workspaceId, message: message ?? null });
+}
+
+export async function gitStashList(workspaceId: string): Promise<GitStashEntry[]> {
+  return invoke<GitStashEntry[]>("git_stash_list", { workspaceId });
+}
+
 export async function stageGitFile(workspaceId: string, path: string) {
   return invoke("stage_git_file", { workspaceId, path });
 }
@@ -250,14 +455,71 @@ export async function unstageGitFile(workspaceId: string, path: string) {
   return invoke("unstage_git_file", { workspaceId, path });
 }
 
+export async function stageFile(
+  workspaceId: string,
+  path: string,
+): Promise<GitFileStatus | null> {
+  return invoke<GitFileStatus | null>("stage_file", { workspaceId, path });
+}
+
+export async function unstageFile(
+  workspaceId: string,
+  path: string,
+): Promise<GitFileStatus | null> {
+  return invoke<GitFileStatus | null>("unstage_file", { workspaceId, path });
+}
+
+export async function gitBlame(
+  workspaceId: string,
+  path: string,
+): Promise<GitBlameHunk[]> {
+  return invoke<GitBlameHunk[]>("git_blame", { workspaceId, path });
+}
+
+export async function stageHunks(
+  workspaceId: string,
+  path: string,
+  hunkIds: number[],
+): Promise<GitFileStatus | null> {
+  return invoke<GitFileStatus | null>("stage_hunks", {
+    workspaceId,
+    path,
+    hunkIds,
+  });
+}
+
 export async function revertGitFile(workspaceId: string, path: string) {
   return invoke("revert_git_file", { workspaceId, path });
 }
 
+export async function discardFileChanges(
+  workspaceId: string,
+  path: string,
+  options: { staged?: boolean } = {},
+): Promise<Awaited<ReturnType<typeof getGitStatus>>> {
+  return invoke("discard_file_changes", {
+    workspaceId,
+    path,
+    staged: options.staged ?? false,
+  });
+}
+
 export async function revertGitAll(workspaceId: string) {
   return invoke("revert_git_all", { workspaceId });
 }
 
+export async function getGitIdentity(workspaceId: string): Promise<GitIdentity> {
+  return invoke<GitIdentity>("get_git_identity", { workspaceId });
+}
+
+export async function setGitIdentity(
+  workspaceId: string,
+  name: string,
+  email: string,
+): Promise<GitIdentity> {
+  return invoke<GitIdentity>("set_git_identity", { workspaceId, name, email });
+}
+
 export async function commitGit(
   workspaceId: string,
   message: string,
@@ -265,10 +527,67 @@ export async function commitGit(
   return invoke("commit_git", { workspaceId, message });
 }
 
+export async function commitAll(
+  workspaceId: string,
+  message: string,
+): Promise<string> {
+  return invoke<string>("commit_all", { workspaceId, message });
+}
+
 export async function pushGit(workspaceId: string): Promise<void> {
   return invoke("push_git", { workspaceId });
 }
 
+export async function gitPush(
+  workspaceId: string,
+  options: { remote?: string; branch?: string; setUpstream?: boolean } = {},
+): Promise<GitPushResult> {
+  return invoke<GitPushResult>("git_push", { workspaceId, ...options });
+}
+
+export async function gitFetch(
+  workspaceId: string,
+  remote?: string,
+): Promise<void> {
+  return invoke("git_fetch", { workspaceId, remote });
+}
+
+export async function gitPull(
+  workspaceId: string,
+  ffOnly = false,
+): Promise<void> {
+  return invoke("git_pull", { workspaceId, ffOnly });
+}
+
+export async function gitMerge(
+  workspaceId: string,
+  branch: string,
+  options: { noFf?: boolean } = {},
+): Promise<GitMergeResult> {
+  return invoke<GitMergeResult>("git_merge", {
+    workspaceId,
+    branch,
+    noFf: options.noFf ?? false,
+    abort: false,
+  });
+}
+
+export async function gitMergeAbort(workspaceId: string): Promise<GitMergeResult> {
+  return invoke<GitMergeResult>("git_merge", { workspaceId, branch: null, abort: true });
+}
+
+export async function gitRebase(
+  workspaceId: string,
+  action: "start" | "continue" | "abort",
+  onto?: string,
+): Promise<GitRebaseResult> {
+  return invoke<GitRebaseResult>("git_rebase", {
+    workspaceId,
+    action,
+    onto: onto ?? null,
+  });
+}
+
 export async function pullGit(workspaceId: string): Promise<void> {
   return invoke("pull_git", { workspaceId });
 }
@@ -324,6 +643,12 @@ export async function getModelList(workspaceId: string) {
   return invoke<any>("model_list", { workspaceId });
 }
 
+export async function getCodexProjectConfig(
+  workspaceId: string,
+): Promise<CodexProjectConfig> {
+  return invoke<CodexProjectConfig>("get_codex_project_config", { workspaceId });
+}
+
 export async function getCollaborationModes(workspaceId: string) {
   return invoke<any>("collaboration_mode_list", { workspaceId });
 }
@@ -428,14 +753,24 @@ export async function runCodexDoctor(
   return invoke<CodexDoctorResult>("codex_doctor", { codexBin });
 }
 
-export async function getWorkspaceFiles(workspaceId: string) {
-  return invoke<string[]>("list_workspace_files", { workspaceId });
+export async function getWorkspaceFiles(
+  workspaceId: string,
+  maxFiles?: number,
+) {
+  return invoke<WorkspaceFileList>("list_workspace_files", {
+    workspaceId,
+    maxFiles: maxFiles ?? null,
+  });
 }
 
 export async function listGitBranches(workspaceId: string) {
   return invoke<any>("list_git_branches", { workspaceId });
 }
 
+export async function listRecentBranches(workspaceId: string, limit?: number) {
+  return invoke<any>("list_recent_branches", { workspaceId, limit: limit ?? null });
+}
+
 export async function checkoutGitBranch(workspaceId: string, name: string) {
   return invoke("checkout_git_branch", { workspaceId, name });
 }
@@ -444,6 +779,14 @@ export async function createGitBranch(workspaceId: string, name: string) {
   return invoke("create_git_branch", { workspaceId, name });
 }
 
+export async function restoreBranch(
+  workspaceId: string,
+  name: string,
+  sha: string,
+) {
+  return invoke<any>("restore_branch", { workspaceId, name, sha });
+}
+
 function withModelId(modelId?: string | null) {
   return modelId ? { modelId } : {};
 }
@@ -543,6 +886,10 @@ export async function resumeThread(workspaceId: string, threadId: string) {
   return invoke<any>("resume_thread", { workspaceId, threadId });
 }
 
+export async function resumeLatestThread(workspaceId: string) {
+  return invoke<any>("resume_latest_thread", { workspaceId });
+}
+
 export async function archiveThread(workspaceId: string, threadId: string) {
   return invoke<any>("archive_thread", { workspaceId, threadId });
 }
@@ -2,7 +2,15 @@ export type WorkspaceSettings = {
   sidebarCollapsed: boolean;
   sortOrder?: number | null;
   groupId?: string | null;
+  groupName?: string | null;
   gitRoot?: string | null;
+  extraArgs?: string[];
+  allowFullAccess?: boolean;
+  watchGithubIssues?: boolean;
+  githubIssuePollSeconds?: number | null;
+  watchUpstream?: boolean;
+  upstreamPollSeconds?: number | null;
+  attachmentsRoot?: string | null;
 };
 
 export type WorkspaceGroup = {
@@ -18,6 +26,11 @@ export type WorktreeInfo = {
   branch: string;
 };
 
+export type WorkspaceFileList = {
+  files: string[];
+  truncated: boolean;
+};
+
 export type WorkspaceInfo = {
   id: string;
   name: string;
@@ -28,6 +41,12 @@ export type WorkspaceInfo = {
   parentId?: string | null;
   worktree?: WorktreeInfo | null;
   settings: WorkspaceSettings;
+  // Repository root, which can be a parent of `path`; null outside a repo.
+  gitRoot?: string | null;
+  // False when the folder was deleted or moved after the workspace was added.
+  pathExists?: boolean;
+  // Binary a session would launch after codex_bin, the app default and PATH.
+  resolvedCodexBin?: string | null;
 };
 
 export type AppServerEvent = {
@@ -72,6 +91,12 @@ export type ReviewTarget =
 
 export type AccessMode = "read-only" | "current" | "full-access";
 export type BackendMode = "local" | "remote";
+export type ApprovalPolicy = "untrusted" | "on-failure" | "on-request" | "never";
+export type AccessModePolicies = {
+  readOnly?: ApprovalPolicy;
+  workspaceWrite?: ApprovalPolicy;
+  fullAccess?: ApprovalPolicy;
+};
 export type ThemePreference = "system" | "light" | "dark";
 
 
@@ -94,6 +119,10 @@ export type AppSettings = {
   remoteBackendHost: string;
   remoteBackendToken: string | null;
   defaultAccessMode: AccessMode;
+  accessModePolicies?: AccessModePolicies;
+  attachmentRoots?: string[];
+  proxyUrl?: string | null;
+  noProxy?: string | null;
   composerModelShortcut: string | null;
   composerAccessShortcut: string | null;
   composerReasoningShortcut: string | null;
@@ -157,13 +186,112 @@ export type ApprovalRequest = {
 export type GitFileStatus = {
   path: string;
   status: string;
-  additions: number;
-  deletions: number;
+  // null when stats were skipped because the changeset exceeded maxFilesForStats.
+  additions: number | null;
+  deletions: number | null;
+  oldPath?: string | null;
+  // Staged and unstaged status letters; `status` combines them.
+  indexStatus?: string | null;
+  worktreeStatus?: string | null;
+};
+
+export type GitStashEntry = {
+  index: number;
+  message: string;
+  branch: string | null;
+  timestamp: number;
+};
+
+export type GitStashApplyResult = {
+  hasConflicts: boolean;
+  conflicts: string[];
+};
+
+export type GitMergeResult = {
+  hasConflicts: boolean;
+  conflicts: string[];
+};
+
+export type GitRebaseResult = {
+  hasConflicts: boolean;
+  conflicts: string[];
+  inProgress: boolean;
+  // Current step while a rebase is in progress, e.g. "1/4".
+  step: string | null;
+};
+
+export type GitPushResult = {
+  remote: string | null;
+  createdRemoteBranch: boolean;
+  upstreamSet: boolean;
 };
 
 export type GitFileDiff = {
   path: string;
   diff: string;
+  additions?: number;
+  deletions?: number;
+  renamedFrom?: string | null;
+  isBinary?: boolean;
+};
+
+export type GitDiffLine = {
+  kind: "add" | "del" | "ctx";
+  content: string;
+  oldLine: number | null;
+  newLine: number | null;
+  noNewline: boolean;
+  // Changed character ranges, present when requested with `wordDiff`.
+  highlights?: GitDiffSpan[];
+};
+
+export type GitDiffSpan = {
+  start: number;
+  end: number;
+};
+
+export type GitDiffHunk = {
+  id: number;
+  header: string;
+  oldStart: number;
+  oldLines: number;
+  newStart: number;
+  newLines: number;
+  lines: GitDiffLine[];
+};
+
+export type GitStructuredFileDiff = {
+  path: string;
+  hunks: GitDiffHunk[];
+};
+
+export type CodexProjectConfigFile = {
+  name: string;
+  isDir: boolean;
+  size: number;
+  contents?: string;
+  parsed?: Record<string, unknown>;
+};
+
+export type CodexProjectConfig = {
+  dir: string;
+  exists: boolean;
+  files: CodexProjectConfigFile[];
+};
+
+export type DiffDigestFile = {
+  path: string;
+  additions: number;
+  deletions: number;
+  mode: "patch" | "outline" | "omitted";
+};
+
+export type DiffDigest = {
+  text: string;
+  files: DiffDigestFile[];
+  truncated: boolean;
+  bytes: number;
+  maxBytes: number;
 };
 
 export type GitCommitDiff = {
@@ -172,6 +300,57 @@ export type GitCommitDiff = {
   diff: string;
 };
 
+export type GitCommitDetails = {
+  sha: string;
+  summary: string;
+  message: string;
+  author: string;
+  authorEmail: string;
+  committer: string;
+  committerEmail: string;
+  timestamp: number;
+  parents: string[];
+  files: GitCommitDiff[];
+};
+
+export type GitTagDetails = {
+  sha: string;
+  name: string;
+  message: string;
+  tagger: string | null;
+  taggerEmail: string | null;
+  timestamp: number | null;
+  targetType: string;
+  targetSha: string;
+};
+
+export type GitResolvedRef = {
+  sha: string;
+  type: "commit" | "tag" | "tree" | "blob";
+  shortName: string;
+};
+
+export type GitShowResult =
+  | ({ type: "commit" } & GitCommitDetails)
+  | ({ type: "tag" } & GitTagDetails)
+  | { type: "blob"; sha: string; size: number; isBinary: boolean }
+  | { type: "tree"; sha: string; entries: string[] };
+
+export type GitBlameHunk = {
+  startLine: number;
+  lineCount: number;
+  sha: string | null;
+  author: string | null;
+  timestamp: number | null;
+  local: boolean;
+};
+
+export type GitIdentity = {
+  name: string | null;
+  email: string | null;
+  configured: boolean;
+};
+
 export type GitLogEntry = {
   sha: string;
   summary: string;
@@ -187,6 +366,7 @@ export type GitLogResponse = {
   aheadEntries: GitLogEntry[];
   behindEntries: GitLogEntry[];
   upstream: string | null;
+  hasUpstream?: boolean;
 };
 
 export type GitHubIssue = {